@@ -6,12 +6,16 @@ use cap_primitives::fs::{
     access, open_dir_nofollow, set_symlink_permissions, set_times, set_times_nofollow,
     FollowSymlinks, Permissions,
 };
+#[cfg(unix)]
+use cap_primitives::fs::{create_fifo, mknod, set_owner, set_owner_recursive};
 #[cfg(windows)]
 use cap_primitives::fs::{symlink_dir, symlink_file};
 use io_lifetimes::AsFilelike;
 use std::io;
 use std::path::Path;
 
+#[cfg(unix)]
+pub use cap_primitives::fs::NodeKind;
 pub use cap_primitives::fs::{AccessType, SystemTimeSpec};
 
 /// Extension trait for `Dir`.
@@ -106,6 +110,62 @@ pub trait DirExt {
     /// symbolic links.
     fn set_symlink_permissions<P: AsRef<Path>>(&self, path: P, perm: Permissions)
         -> io::Result<()>;
+
+    /// Creates a new FIFO, also known as a named pipe, on a filesystem.
+    ///
+    /// `mode` holds the permission bits of the new FIFO, which are modified
+    /// by the process's umask.
+    ///
+    /// This corresponds to [`nix::unistd::mkfifo`].
+    ///
+    /// [`nix::unistd::mkfifo`]: https://docs.rs/nix/latest/nix/unistd/fn.mkfifo.html
+    #[cfg(unix)]
+    fn create_fifo<P: AsRef<Path>>(&self, path: P, mode: u32) -> io::Result<()>;
+
+    /// Creates a new special file, such as a FIFO, socket, or device node, on
+    /// a filesystem.
+    ///
+    /// `mode` holds the permission bits of the new file, which are modified
+    /// by the process's umask. `dev` is the device number for character and
+    /// block devices, and is ignored otherwise.
+    ///
+    /// This corresponds to [`nix::sys::stat::mknod`].
+    ///
+    /// [`nix::sys::stat::mknod`]: https://docs.rs/nix/latest/nix/sys/stat/fn.mknod.html
+    #[cfg(unix)]
+    fn mknod<P: AsRef<Path>>(&self, path: P, kind: NodeKind, mode: u32, dev: u64)
+        -> io::Result<()>;
+
+    /// Changes the owner and group of a file on a filesystem. `None` leaves
+    /// the corresponding ID unchanged.
+    ///
+    /// With `FollowSymlinks::Yes`, this corresponds to
+    /// [`std::os::unix::fs::chown`], and with `FollowSymlinks::No`, to
+    /// [`std::os::unix::fs::lchown`].
+    ///
+    /// [`std::os::unix::fs::chown`]: https://doc.rust-lang.org/std/os/unix/fs/fn.chown.html
+    /// [`std::os::unix::fs::lchown`]: https://doc.rust-lang.org/std/os/unix/fs/fn.lchown.html
+    #[cfg(unix)]
+    fn set_owner<P: AsRef<Path>>(
+        &self,
+        path: P,
+        owner: Option<u32>,
+        group: Option<u32>,
+        follow: FollowSymlinks,
+    ) -> io::Result<()>;
+
+    /// Changes the owner and group of a file and, if it's a directory, of
+    /// everything within it. `None` leaves the corresponding ID unchanged.
+    ///
+    /// Symlinks are not followed; the owners of the symlinks themselves are
+    /// changed instead.
+    #[cfg(unix)]
+    fn set_owner_recursive<P: AsRef<Path>>(
+        &self,
+        path: P,
+        owner: Option<u32>,
+        group: Option<u32>,
+    ) -> io::Result<()>;
 }
 
 /// `fs_utf8` version of `DirExt`.
@@ -209,6 +269,49 @@ pub trait DirExtUtf8 {
         path: P,
         perm: Permissions,
     ) -> io::Result<()>;
+
+    /// Creates a new FIFO, also known as a named pipe, on a filesystem.
+    ///
+    /// `mode` holds the permission bits of the new FIFO, which are modified
+    /// by the process's umask.
+    #[cfg(unix)]
+    fn create_fifo<P: AsRef<Utf8Path>>(&self, path: P, mode: u32) -> io::Result<()>;
+
+    /// Creates a new special file, such as a FIFO, socket, or device node, on
+    /// a filesystem.
+    ///
+    /// `mode` holds the permission bits of the new file, which are modified
+    /// by the process's umask. `dev` is the device number for character and
+    /// block devices, and is ignored otherwise.
+    #[cfg(unix)]
+    fn mknod<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        kind: NodeKind,
+        mode: u32,
+        dev: u64,
+    ) -> io::Result<()>;
+
+    /// Changes the owner and group of a file on a filesystem. `None` leaves
+    /// the corresponding ID unchanged.
+    #[cfg(unix)]
+    fn set_owner<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        owner: Option<u32>,
+        group: Option<u32>,
+        follow: FollowSymlinks,
+    ) -> io::Result<()>;
+
+    /// Changes the owner and group of a file and, if it's a directory, of
+    /// everything within it. `None` leaves the corresponding ID unchanged.
+    #[cfg(unix)]
+    fn set_owner_recursive<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        owner: Option<u32>,
+        group: Option<u32>,
+    ) -> io::Result<()>;
 }
 
 #[cfg(feature = "std")]
@@ -398,6 +501,68 @@ impl DirExt for cap_std::fs::Dir {
             perm,
         )
     }
+
+    #[cfg(unix)]
+    #[inline]
+    fn create_fifo<P: AsRef<Path>>(&self, path: P, mode: u32) -> io::Result<()> {
+        create_fifo(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            mode,
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn mknod<P: AsRef<Path>>(
+        &self,
+        path: P,
+        kind: NodeKind,
+        mode: u32,
+        dev: u64,
+    ) -> io::Result<()> {
+        mknod(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            kind,
+            mode,
+            dev,
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn set_owner<P: AsRef<Path>>(
+        &self,
+        path: P,
+        owner: Option<u32>,
+        group: Option<u32>,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        set_owner(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            owner,
+            group,
+            follow,
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn set_owner_recursive<P: AsRef<Path>>(
+        &self,
+        path: P,
+        owner: Option<u32>,
+        group: Option<u32>,
+    ) -> io::Result<()> {
+        set_owner_recursive(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            owner,
+            group,
+        )
+    }
 }
 
 #[cfg(all(feature = "std", feature = "fs_utf8"))]
@@ -598,17 +763,79 @@ impl DirExtUtf8 for cap_std::fs_utf8::Dir {
             perm,
         )
     }
+
+    #[cfg(unix)]
+    #[inline]
+    fn create_fifo<P: AsRef<Utf8Path>>(&self, path: P, mode: u32) -> io::Result<()> {
+        create_fifo(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref().as_ref(),
+            mode,
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn mknod<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        kind: NodeKind,
+        mode: u32,
+        dev: u64,
+    ) -> io::Result<()> {
+        mknod(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref().as_ref(),
+            kind,
+            mode,
+            dev,
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn set_owner<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        owner: Option<u32>,
+        group: Option<u32>,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        set_owner(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref().as_ref(),
+            owner,
+            group,
+            follow,
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn set_owner_recursive<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        owner: Option<u32>,
+        group: Option<u32>,
+    ) -> io::Result<()> {
+        set_owner_recursive(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref().as_ref(),
+            owner,
+            group,
+        )
+    }
 }
 
 #[cfg(all(feature = "std", feature = "fs_utf8"))]
 #[cfg(not(feature = "arf_strings"))]
-fn from_utf8<'a>(path: &'a Utf8Path) -> io::Result<&'a std::path::Path> {
+pub(crate) fn from_utf8<'a>(path: &'a Utf8Path) -> io::Result<&'a std::path::Path> {
     Ok(path.as_std_path())
 }
 
 #[cfg(all(feature = "std", feature = "fs_utf8"))]
 #[cfg(feature = "arf_strings")]
-fn from_utf8<'a>(path: &'a Utf8Path) -> io::Result<std::path::PathBuf> {
+pub(crate) fn from_utf8<'a>(path: &'a Utf8Path) -> io::Result<std::path::PathBuf> {
     #[cfg(not(windows))]
     let path = {
         #[cfg(unix)]
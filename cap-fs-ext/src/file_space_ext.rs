@@ -0,0 +1,146 @@
+pub use cap_primitives::fs::DataExtents;
+use cap_primitives::fs::{allocate, data_extents, punch_hole, seek_data, seek_hole, zero_range};
+#[cfg(feature = "std")]
+use io_lifetimes::AsFilelike;
+use std::io;
+
+/// Extension trait for `File` for preallocating space and for working with
+/// sparse files.
+pub trait FileSpaceExt {
+    /// Allocates space for the `len` bytes starting at `offset`, extending
+    /// the file if needed.
+    ///
+    /// This corresponds to `fallocate` with no flags.
+    fn allocate(&self, offset: u64, len: u64) -> io::Result<()>;
+
+    /// Deallocates the `len` bytes starting at `offset`, leaving a hole which
+    /// reads as zeros. The file's size is not changed.
+    ///
+    /// This corresponds to `fallocate` with `FALLOC_FL_PUNCH_HOLE`.
+    fn punch_hole(&self, offset: u64, len: u64) -> io::Result<()>;
+
+    /// Zeros the `len` bytes starting at `offset`, extending the file if
+    /// needed, without necessarily writing to the underlying storage.
+    ///
+    /// This corresponds to `fallocate` with `FALLOC_FL_ZERO_RANGE`.
+    fn zero_range(&self, offset: u64, len: u64) -> io::Result<()>;
+
+    /// Returns the offset of the first data at or after `offset`, or `None`
+    /// if there is only a hole after `offset`.
+    ///
+    /// This corresponds to `lseek` with `SEEK_DATA`, and moves the file's
+    /// position to the returned offset.
+    fn seek_data(&self, offset: u64) -> io::Result<Option<u64>>;
+
+    /// Returns the offset of the first hole at or after `offset`. There is an
+    /// implicit hole at the end of every file.
+    ///
+    /// This corresponds to `lseek` with `SEEK_HOLE`, and moves the file's
+    /// position to the returned offset.
+    fn seek_hole(&self, offset: u64) -> io::Result<u64>;
+
+    /// Returns an iterator over the ranges of the file which contain data,
+    /// skipping over holes.
+    ///
+    /// Filesystems which don't track holes report the whole file as data.
+    /// The iterator moves the file's position.
+    fn data_extents(&self) -> DataExtents<'_>;
+}
+
+impl FileSpaceExt for std::fs::File {
+    #[inline]
+    fn allocate(&self, offset: u64, len: u64) -> io::Result<()> {
+        allocate(self, offset, len)
+    }
+
+    #[inline]
+    fn punch_hole(&self, offset: u64, len: u64) -> io::Result<()> {
+        punch_hole(self, offset, len)
+    }
+
+    #[inline]
+    fn zero_range(&self, offset: u64, len: u64) -> io::Result<()> {
+        zero_range(self, offset, len)
+    }
+
+    #[inline]
+    fn seek_data(&self, offset: u64) -> io::Result<Option<u64>> {
+        seek_data(self, offset)
+    }
+
+    #[inline]
+    fn seek_hole(&self, offset: u64) -> io::Result<u64> {
+        seek_hole(self, offset)
+    }
+
+    #[inline]
+    fn data_extents(&self) -> DataExtents<'_> {
+        data_extents(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl FileSpaceExt for cap_std::fs::File {
+    #[inline]
+    fn allocate(&self, offset: u64, len: u64) -> io::Result<()> {
+        allocate(&self.as_filelike_view::<std::fs::File>(), offset, len)
+    }
+
+    #[inline]
+    fn punch_hole(&self, offset: u64, len: u64) -> io::Result<()> {
+        punch_hole(&self.as_filelike_view::<std::fs::File>(), offset, len)
+    }
+
+    #[inline]
+    fn zero_range(&self, offset: u64, len: u64) -> io::Result<()> {
+        zero_range(&self.as_filelike_view::<std::fs::File>(), offset, len)
+    }
+
+    #[inline]
+    fn seek_data(&self, offset: u64) -> io::Result<Option<u64>> {
+        seek_data(&self.as_filelike_view::<std::fs::File>(), offset)
+    }
+
+    #[inline]
+    fn seek_hole(&self, offset: u64) -> io::Result<u64> {
+        seek_hole(&self.as_filelike_view::<std::fs::File>(), offset)
+    }
+
+    #[inline]
+    fn data_extents(&self) -> DataExtents<'_> {
+        data_extents(self)
+    }
+}
+
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+impl FileSpaceExt for cap_std::fs_utf8::File {
+    #[inline]
+    fn allocate(&self, offset: u64, len: u64) -> io::Result<()> {
+        allocate(&self.as_filelike_view::<std::fs::File>(), offset, len)
+    }
+
+    #[inline]
+    fn punch_hole(&self, offset: u64, len: u64) -> io::Result<()> {
+        punch_hole(&self.as_filelike_view::<std::fs::File>(), offset, len)
+    }
+
+    #[inline]
+    fn zero_range(&self, offset: u64, len: u64) -> io::Result<()> {
+        zero_range(&self.as_filelike_view::<std::fs::File>(), offset, len)
+    }
+
+    #[inline]
+    fn seek_data(&self, offset: u64) -> io::Result<Option<u64>> {
+        seek_data(&self.as_filelike_view::<std::fs::File>(), offset)
+    }
+
+    #[inline]
+    fn seek_hole(&self, offset: u64) -> io::Result<u64> {
+        seek_hole(&self.as_filelike_view::<std::fs::File>(), offset)
+    }
+
+    #[inline]
+    fn data_extents(&self) -> DataExtents<'_> {
+        data_extents(self)
+    }
+}
@@ -12,25 +12,65 @@
 
 mod dir_entry_ext;
 mod dir_ext;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod file_space_ext;
 mod file_type_ext;
 mod is_file_read_write;
 mod metadata_ext;
 mod open_options_follow_ext;
 mod open_options_maybe_dir_ext;
+mod open_options_resolve_ext;
 mod open_options_sync_ext;
 mod reopen;
+#[cfg(target_os = "linux")]
+mod statx_ext;
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+mod uring_ext;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod watch_ext;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod xattr_ext;
 
 pub use dir_entry_ext::DirEntryExt;
 #[cfg(all(feature = "std", feature = "fs_utf8"))]
 pub use dir_ext::DirExtUtf8;
+#[cfg(unix)]
+pub use dir_ext::NodeKind;
 pub use dir_ext::{AccessType, DirExt, SystemTimeSpec};
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub use file_space_ext::{DataExtents, FileSpaceExt};
 pub use file_type_ext::FileTypeExt;
 pub use is_file_read_write::IsFileReadWrite;
 pub use metadata_ext::MetadataExt;
 pub use open_options_follow_ext::OpenOptionsFollowExt;
 pub use open_options_maybe_dir_ext::OpenOptionsMaybeDirExt;
+pub use open_options_resolve_ext::OpenOptionsResolveExt;
 pub use open_options_sync_ext::OpenOptionsSyncExt;
 pub use reopen::Reopen;
+#[cfg(all(target_os = "linux", feature = "std", feature = "fs_utf8"))]
+pub use statx_ext::StatxExtUtf8;
+#[cfg(target_os = "linux")]
+pub use statx_ext::{ExtendedMetadata, FileStatxExt, StatxExt};
+#[cfg(all(target_os = "linux", feature = "io_uring", feature = "fs_utf8"))]
+pub use uring_ext::UringExtUtf8;
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+pub use uring_ext::{Uring, UringExt};
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    feature = "std",
+    feature = "fs_utf8"
+))]
+pub use watch_ext::WatchExtUtf8;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub use watch_ext::{WatchEvent, WatchExt, Watcher};
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    feature = "std",
+    feature = "fs_utf8"
+))]
+pub use xattr_ext::XattrExtUtf8;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub use xattr_ext::{FileXattrExt, XattrExt};
 
 /// Re-export these to allow them to be used with `Reuse`.
 pub use cap_primitives::fs::{
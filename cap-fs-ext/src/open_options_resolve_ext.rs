@@ -0,0 +1,77 @@
+/// Extension trait for `cap_primitives::fs::OpenOptions` which adds
+/// `no_xdev`, `no_magiclinks`, `no_symlinks`, and `cached` functions for
+/// further restricting how a path is resolved.
+///
+/// On Linux, these correspond to the `RESOLVE_*` flags of [`openat2`]. Where
+/// `openat2` is unavailable, they're enforced during manual path resolution.
+///
+/// [`openat2`]: https://man7.org/linux/man-pages/man2/openat2.2.html
+pub trait OpenOptionsResolveExt {
+    /// Requests that path resolution fail if it would cross a mount point,
+    /// including bind mounts.
+    ///
+    /// This corresponds to `RESOLVE_NO_XDEV`. Without `openat2`, this is
+    /// checked by comparing the device of each path component with that of
+    /// the starting directory, which doesn't detect bind mounts within the
+    /// same filesystem.
+    fn no_xdev(&mut self, enable: bool) -> &mut Self;
+
+    /// Requests that path resolution fail if it would follow a "magic link",
+    /// such as those in `/proc/self/fd`.
+    ///
+    /// This corresponds to `RESOLVE_NO_MAGICLINKS`. Sandboxed path resolution
+    /// never follows magic links, so this is always in effect.
+    fn no_magiclinks(&mut self, enable: bool) -> &mut Self;
+
+    /// Requests that path resolution fail if it would follow any symlink.
+    ///
+    /// This corresponds to `RESOLVE_NO_SYMLINKS`. A symlink in the last
+    /// component of a path is still opened if
+    /// [`follow`](crate::OpenOptionsFollowExt::follow) is set to
+    /// `FollowSymlinks::No` and the open would otherwise succeed.
+    fn no_symlinks(&mut self, enable: bool) -> &mut Self;
+
+    /// Requests that the open fail with `std::io::ErrorKind::WouldBlock`
+    /// unless path resolution can be done entirely from the kernel's lookup
+    /// cache, without any I/O.
+    ///
+    /// This corresponds to `RESOLVE_CACHED`. When it fails, callers should
+    /// retry the open without this option. Where `openat2` with
+    /// `RESOLVE_CACHED` is unavailable, opens with this option always fail.
+    fn cached(&mut self, enable: bool) -> &mut Self;
+}
+
+impl OpenOptionsResolveExt for cap_primitives::fs::OpenOptions {
+    #[inline]
+    fn no_xdev(&mut self, enable: bool) -> &mut Self {
+        // `no_xdev` functionality is implemented within `cap_primitives`;
+        // we're just exposing it here since `OpenOptions` is re-exported by
+        // `cap_std` etc. and `no_xdev` isn't in `std`.
+        self._cap_fs_ext_no_xdev(enable)
+    }
+
+    #[inline]
+    fn no_magiclinks(&mut self, enable: bool) -> &mut Self {
+        // `no_magiclinks` functionality is implemented within
+        // `cap_primitives`; we're just exposing it here since `OpenOptions`
+        // is re-exported by `cap_std` etc. and `no_magiclinks` isn't in
+        // `std`.
+        self._cap_fs_ext_no_magiclinks(enable)
+    }
+
+    #[inline]
+    fn no_symlinks(&mut self, enable: bool) -> &mut Self {
+        // `no_symlinks` functionality is implemented within `cap_primitives`;
+        // we're just exposing it here since `OpenOptions` is re-exported by
+        // `cap_std` etc. and `no_symlinks` isn't in `std`.
+        self._cap_fs_ext_no_symlinks(enable)
+    }
+
+    #[inline]
+    fn cached(&mut self, enable: bool) -> &mut Self {
+        // `cached` functionality is implemented within `cap_primitives`;
+        // we're just exposing it here since `OpenOptions` is re-exported by
+        // `cap_std` etc. and `cached` isn't in `std`.
+        self._cap_fs_ext_cached(enable)
+    }
+}
@@ -0,0 +1,105 @@
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+use crate::dir_ext::from_utf8;
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+use camino::Utf8Path;
+pub use cap_primitives::fs::ExtendedMetadata;
+use cap_primitives::fs::{file_statx, statx, FollowSymlinks};
+#[cfg(feature = "std")]
+use io_lifetimes::AsFilelike;
+use std::io;
+use std::path::Path;
+
+/// Extension trait for `Dir` for querying metadata using `statx`, such as
+/// birth times, mount IDs, and file attribute flags.
+///
+/// Paths are resolved relative to the `Dir`, and never escape it.
+pub trait StatxExt {
+    /// Returns the extended metadata of a file.
+    ///
+    /// Fields which the kernel or filesystem don't support are reported as
+    /// `None`.
+    fn statx<P: AsRef<Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+    ) -> io::Result<ExtendedMetadata>;
+}
+
+/// `fs_utf8` version of `StatxExt`.
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+pub trait StatxExtUtf8 {
+    /// Returns the extended metadata of a file.
+    ///
+    /// Fields which the kernel or filesystem don't support are reported as
+    /// `None`.
+    fn statx<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+    ) -> io::Result<ExtendedMetadata>;
+}
+
+/// Extension trait for `File` for querying metadata using `statx`.
+pub trait FileStatxExt {
+    /// Returns the extended metadata of this file.
+    ///
+    /// Fields which the kernel or filesystem don't support are reported as
+    /// `None`.
+    fn statx(&self) -> io::Result<ExtendedMetadata>;
+}
+
+#[cfg(feature = "std")]
+impl StatxExt for cap_std::fs::Dir {
+    #[inline]
+    fn statx<P: AsRef<Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+    ) -> io::Result<ExtendedMetadata> {
+        statx(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            follow,
+        )
+    }
+}
+
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+impl StatxExtUtf8 for cap_std::fs_utf8::Dir {
+    #[inline]
+    fn statx<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+    ) -> io::Result<ExtendedMetadata> {
+        let path = from_utf8(path.as_ref())?;
+        statx(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            follow,
+        )
+    }
+}
+
+impl FileStatxExt for std::fs::File {
+    #[inline]
+    fn statx(&self) -> io::Result<ExtendedMetadata> {
+        file_statx(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl FileStatxExt for cap_std::fs::File {
+    #[inline]
+    fn statx(&self) -> io::Result<ExtendedMetadata> {
+        file_statx(&self.as_filelike_view::<std::fs::File>())
+    }
+}
+
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+impl FileStatxExt for cap_std::fs_utf8::File {
+    #[inline]
+    fn statx(&self) -> io::Result<ExtendedMetadata> {
+        file_statx(&self.as_filelike_view::<std::fs::File>())
+    }
+}
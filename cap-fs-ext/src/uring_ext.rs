@@ -0,0 +1,224 @@
+#[cfg(feature = "fs_utf8")]
+use crate::dir_ext::from_utf8;
+#[cfg(feature = "fs_utf8")]
+use camino::Utf8Path;
+use cap_primitives::fs::OpenOptions;
+pub use cap_primitives::fs::Uring;
+use io_lifetimes::AsFilelike;
+use std::io;
+use std::path::Path;
+
+/// Extension trait for `Dir` for opening, reading, and writing files using
+/// io_uring.
+///
+/// Opens are submitted as `IORING_OP_OPENAT2` with `RESOLVE_BENEATH`, so paths
+/// are resolved relative to the `Dir` and never escape it, just as with the
+/// regular `Dir` methods. Reads and writes on the returned files can also be
+/// performed with [`Uring::read_at`] and [`Uring::write_at`].
+pub trait UringExt {
+    /// Opens a file at `path` with the options specified by `options`.
+    ///
+    /// This is the same as `Dir::open_with`, but submits the open to `uring`.
+    fn open_with_uring<P: AsRef<Path>>(
+        &self,
+        uring: &mut Uring,
+        path: P,
+        options: &OpenOptions,
+    ) -> io::Result<cap_std::fs::File>;
+
+    /// Opens each of `paths` with the options specified by `options`,
+    /// submitting the opens to `uring` together.
+    ///
+    /// The results are in the same order as `paths`.
+    fn open_many_with_uring<P: AsRef<Path>>(
+        &self,
+        uring: &mut Uring,
+        paths: &[P],
+        options: &OpenOptions,
+    ) -> Vec<io::Result<cap_std::fs::File>>;
+
+    /// Read the entire contents of a file into a bytes vector.
+    ///
+    /// This is the same as `Dir::read`, but performs the open and reads
+    /// using `uring`.
+    fn read_with_uring<P: AsRef<Path>>(&self, uring: &mut Uring, path: P) -> io::Result<Vec<u8>>;
+
+    /// Write a slice as the entire contents of a file.
+    ///
+    /// This is the same as `Dir::write`, but performs the open and writes
+    /// using `uring`.
+    fn write_with_uring<P: AsRef<Path>, C: AsRef<[u8]>>(
+        &self,
+        uring: &mut Uring,
+        path: P,
+        contents: C,
+    ) -> io::Result<()>;
+}
+
+/// `fs_utf8` version of `UringExt`.
+#[cfg(feature = "fs_utf8")]
+pub trait UringExtUtf8 {
+    /// Opens a file at `path` with the options specified by `options`.
+    ///
+    /// This is the same as `Dir::open_with`, but submits the open to `uring`.
+    fn open_with_uring<P: AsRef<Utf8Path>>(
+        &self,
+        uring: &mut Uring,
+        path: P,
+        options: &OpenOptions,
+    ) -> io::Result<cap_std::fs_utf8::File>;
+
+    /// Opens each of `paths` with the options specified by `options`,
+    /// submitting the opens to `uring` together.
+    ///
+    /// The results are in the same order as `paths`.
+    fn open_many_with_uring<P: AsRef<Utf8Path>>(
+        &self,
+        uring: &mut Uring,
+        paths: &[P],
+        options: &OpenOptions,
+    ) -> Vec<io::Result<cap_std::fs_utf8::File>>;
+
+    /// Read the entire contents of a file into a bytes vector.
+    ///
+    /// This is the same as `Dir::read`, but performs the open and reads
+    /// using `uring`.
+    fn read_with_uring<P: AsRef<Utf8Path>>(
+        &self,
+        uring: &mut Uring,
+        path: P,
+    ) -> io::Result<Vec<u8>>;
+
+    /// Write a slice as the entire contents of a file.
+    ///
+    /// This is the same as `Dir::write`, but performs the open and writes
+    /// using `uring`.
+    fn write_with_uring<P: AsRef<Utf8Path>, C: AsRef<[u8]>>(
+        &self,
+        uring: &mut Uring,
+        path: P,
+        contents: C,
+    ) -> io::Result<()>;
+}
+
+impl UringExt for cap_std::fs::Dir {
+    #[inline]
+    fn open_with_uring<P: AsRef<Path>>(
+        &self,
+        uring: &mut Uring,
+        path: P,
+        options: &OpenOptions,
+    ) -> io::Result<cap_std::fs::File> {
+        let file = uring.open(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            options,
+        )?;
+        Ok(cap_std::fs::File::from_std(file))
+    }
+
+    fn open_many_with_uring<P: AsRef<Path>>(
+        &self,
+        uring: &mut Uring,
+        paths: &[P],
+        options: &OpenOptions,
+    ) -> Vec<io::Result<cap_std::fs::File>> {
+        let paths = paths.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        uring
+            .open_many(&self.as_filelike_view::<std::fs::File>(), &paths, options)
+            .into_iter()
+            .map(|file| file.map(cap_std::fs::File::from_std))
+            .collect()
+    }
+
+    #[inline]
+    fn read_with_uring<P: AsRef<Path>>(&self, uring: &mut Uring, path: P) -> io::Result<Vec<u8>> {
+        uring.read(&self.as_filelike_view::<std::fs::File>(), path.as_ref())
+    }
+
+    #[inline]
+    fn write_with_uring<P: AsRef<Path>, C: AsRef<[u8]>>(
+        &self,
+        uring: &mut Uring,
+        path: P,
+        contents: C,
+    ) -> io::Result<()> {
+        uring.write(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            contents.as_ref(),
+        )
+    }
+}
+
+#[cfg(feature = "fs_utf8")]
+impl UringExtUtf8 for cap_std::fs_utf8::Dir {
+    #[inline]
+    fn open_with_uring<P: AsRef<Utf8Path>>(
+        &self,
+        uring: &mut Uring,
+        path: P,
+        options: &OpenOptions,
+    ) -> io::Result<cap_std::fs_utf8::File> {
+        let path = from_utf8(path.as_ref())?;
+        let file = uring.open(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            options,
+        )?;
+        Ok(cap_std::fs_utf8::File::from_std(file))
+    }
+
+    fn open_many_with_uring<P: AsRef<Utf8Path>>(
+        &self,
+        uring: &mut Uring,
+        paths: &[P],
+        options: &OpenOptions,
+    ) -> Vec<io::Result<cap_std::fs_utf8::File>> {
+        // Submit the paths which convert successfully, and report conversion
+        // errors in place.
+        let converted = paths
+            .iter()
+            .map(|path| from_utf8(path.as_ref()))
+            .collect::<Vec<_>>();
+        let valid = converted
+            .iter()
+            .filter_map(|path| path.as_ref().ok().map(AsRef::as_ref))
+            .collect::<Vec<&Path>>();
+        let mut opened = uring
+            .open_many(&self.as_filelike_view::<std::fs::File>(), &valid, options)
+            .into_iter();
+        converted
+            .into_iter()
+            .map(|path| {
+                path?;
+                opened.next().unwrap().map(cap_std::fs_utf8::File::from_std)
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn read_with_uring<P: AsRef<Utf8Path>>(
+        &self,
+        uring: &mut Uring,
+        path: P,
+    ) -> io::Result<Vec<u8>> {
+        let path = from_utf8(path.as_ref())?;
+        uring.read(&self.as_filelike_view::<std::fs::File>(), path.as_ref())
+    }
+
+    #[inline]
+    fn write_with_uring<P: AsRef<Utf8Path>, C: AsRef<[u8]>>(
+        &self,
+        uring: &mut Uring,
+        path: P,
+        contents: C,
+    ) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        uring.write(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            contents.as_ref(),
+        )
+    }
+}
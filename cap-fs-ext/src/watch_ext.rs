@@ -0,0 +1,53 @@
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+use crate::dir_ext::from_utf8;
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+use camino::Utf8Path;
+#[cfg(feature = "std")]
+use cap_primitives::fs::watch;
+pub use cap_primitives::fs::{WatchEvent, Watcher};
+#[cfg(feature = "std")]
+use io_lifetimes::AsFilelike;
+use std::io;
+use std::path::Path;
+
+/// Extension trait for `Dir` for watching directories for changes.
+pub trait WatchExt {
+    /// Start watching the directory at `path` for changes to its entries.
+    ///
+    /// The returned [`Watcher`] is an iterator that blocks until the next
+    /// change, yielding [`WatchEvent`]s with paths relative to this `Dir`.
+    /// Changes within subdirectories of `path` are not reported. Pass `"."`
+    /// to watch this `Dir` itself.
+    ///
+    /// The watched directory is resolved relative to this `Dir`, and never
+    /// escapes it. If the watched directory is later removed or renamed, the
+    /// iterator ends, rather than report changes to a directory which may no
+    /// longer be within this `Dir`.
+    fn watch<P: AsRef<Path>>(&self, path: P) -> io::Result<Watcher>;
+}
+
+/// `fs_utf8` version of `WatchExt`.
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+pub trait WatchExtUtf8 {
+    /// Start watching the directory at `path` for changes to its entries.
+    ///
+    /// This is the same as [`WatchExt::watch`], but takes a UTF-8 path.
+    fn watch<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<Watcher>;
+}
+
+#[cfg(feature = "std")]
+impl WatchExt for cap_std::fs::Dir {
+    #[inline]
+    fn watch<P: AsRef<Path>>(&self, path: P) -> io::Result<Watcher> {
+        watch(&self.as_filelike_view::<std::fs::File>(), path.as_ref())
+    }
+}
+
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+impl WatchExtUtf8 for cap_std::fs_utf8::Dir {
+    #[inline]
+    fn watch<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<Watcher> {
+        let path = from_utf8(path.as_ref())?;
+        watch(&self.as_filelike_view::<std::fs::File>(), path.as_ref())
+    }
+}
@@ -0,0 +1,373 @@
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+use crate::dir_ext::from_utf8;
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+use camino::Utf8Path;
+use cap_primitives::fs::{
+    get_file_xattr, get_xattr, list_file_xattrs, list_xattrs, remove_file_xattr, remove_xattr,
+    set_file_xattr, set_xattr, FollowSymlinks,
+};
+#[cfg(feature = "std")]
+use io_lifetimes::AsFilelike;
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::Path;
+
+/// Extension trait for `Dir` for working with extended attributes.
+///
+/// Paths are resolved relative to the `Dir`, and never escape it.
+///
+/// On Linux, only the attributes of regular files and directories can be
+/// accessed. The attributes of a symlink itself, with `FollowSymlinks::No`,
+/// can't be accessed, and attempting to fails with
+/// [`io::ErrorKind::Unsupported`]. Devices, FIFOs, and sockets are refused
+/// with [`io::ErrorKind::InvalidInput`], since accessing them would require
+/// opening them.
+pub trait XattrExt {
+    /// Returns the value of the extended attribute `name` of a file, or `None`
+    /// if it has no such attribute.
+    ///
+    /// This corresponds to [`xattr::get`] or [`xattr::get_deref`], depending
+    /// on `follow`.
+    ///
+    /// [`xattr::get`]: https://docs.rs/xattr/latest/xattr/fn.get.html
+    /// [`xattr::get_deref`]: https://docs.rs/xattr/latest/xattr/fn.get_deref.html
+    fn get_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<Option<Vec<u8>>>;
+
+    /// Sets the extended attribute `name` of a file to `value`, creating it if
+    /// needed.
+    ///
+    /// This corresponds to [`xattr::set`] or [`xattr::set_deref`], depending
+    /// on `follow`.
+    ///
+    /// [`xattr::set`]: https://docs.rs/xattr/latest/xattr/fn.set.html
+    /// [`xattr::set_deref`]: https://docs.rs/xattr/latest/xattr/fn.set_deref.html
+    fn set_xattr<P: AsRef<Path>, N: AsRef<OsStr>, V: AsRef<[u8]>>(
+        &self,
+        path: P,
+        name: N,
+        value: V,
+        follow: FollowSymlinks,
+    ) -> io::Result<()>;
+
+    /// Returns the names of the extended attributes of a file.
+    ///
+    /// This corresponds to [`xattr::list`] or [`xattr::list_deref`],
+    /// depending on `follow`.
+    ///
+    /// [`xattr::list`]: https://docs.rs/xattr/latest/xattr/fn.list.html
+    /// [`xattr::list_deref`]: https://docs.rs/xattr/latest/xattr/fn.list_deref.html
+    fn list_xattrs<P: AsRef<Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+    ) -> io::Result<Vec<OsString>>;
+
+    /// Removes the extended attribute `name` of a file.
+    ///
+    /// This corresponds to [`xattr::remove`] or [`xattr::remove_deref`],
+    /// depending on `follow`.
+    ///
+    /// [`xattr::remove`]: https://docs.rs/xattr/latest/xattr/fn.remove.html
+    /// [`xattr::remove_deref`]: https://docs.rs/xattr/latest/xattr/fn.remove_deref.html
+    fn remove_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<()>;
+}
+
+/// `fs_utf8` version of `XattrExt`.
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+pub trait XattrExtUtf8 {
+    /// Returns the value of the extended attribute `name` of a file, or `None`
+    /// if it has no such attribute.
+    fn get_xattr<P: AsRef<Utf8Path>, N: AsRef<str>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<Option<Vec<u8>>>;
+
+    /// Sets the extended attribute `name` of a file to `value`, creating it if
+    /// needed.
+    fn set_xattr<P: AsRef<Utf8Path>, N: AsRef<str>, V: AsRef<[u8]>>(
+        &self,
+        path: P,
+        name: N,
+        value: V,
+        follow: FollowSymlinks,
+    ) -> io::Result<()>;
+
+    /// Returns the names of the extended attributes of a file.
+    ///
+    /// This function returns an `Err` if any of the names isn't valid UTF-8.
+    fn list_xattrs<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+    ) -> io::Result<Vec<String>>;
+
+    /// Removes the extended attribute `name` of a file.
+    fn remove_xattr<P: AsRef<Utf8Path>, N: AsRef<str>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<()>;
+}
+
+/// Extension trait for `File` for working with extended attributes.
+pub trait FileXattrExt {
+    /// Returns the value of the extended attribute `name` of this file, or
+    /// `None` if it has no such attribute.
+    ///
+    /// This corresponds to [`xattr::FileExt::get_xattr`].
+    ///
+    /// [`xattr::FileExt::get_xattr`]: https://docs.rs/xattr/latest/xattr/trait.FileExt.html#method.get_xattr
+    fn get_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<Option<Vec<u8>>>;
+
+    /// Sets the extended attribute `name` of this file to `value`, creating it
+    /// if needed.
+    ///
+    /// This corresponds to [`xattr::FileExt::set_xattr`].
+    ///
+    /// [`xattr::FileExt::set_xattr`]: https://docs.rs/xattr/latest/xattr/trait.FileExt.html#method.set_xattr
+    fn set_xattr<N: AsRef<OsStr>, V: AsRef<[u8]>>(&self, name: N, value: V) -> io::Result<()>;
+
+    /// Returns the names of the extended attributes of this file.
+    ///
+    /// This corresponds to [`xattr::FileExt::list_xattr`].
+    ///
+    /// [`xattr::FileExt::list_xattr`]: https://docs.rs/xattr/latest/xattr/trait.FileExt.html#method.list_xattr
+    fn list_xattrs(&self) -> io::Result<Vec<OsString>>;
+
+    /// Removes the extended attribute `name` of this file.
+    ///
+    /// This corresponds to [`xattr::FileExt::remove_xattr`].
+    ///
+    /// [`xattr::FileExt::remove_xattr`]: https://docs.rs/xattr/latest/xattr/trait.FileExt.html#method.remove_xattr
+    fn remove_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()>;
+}
+
+#[cfg(feature = "std")]
+impl XattrExt for cap_std::fs::Dir {
+    #[inline]
+    fn get_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<Option<Vec<u8>>> {
+        get_xattr(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            name.as_ref(),
+            follow,
+        )
+    }
+
+    #[inline]
+    fn set_xattr<P: AsRef<Path>, N: AsRef<OsStr>, V: AsRef<[u8]>>(
+        &self,
+        path: P,
+        name: N,
+        value: V,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        set_xattr(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            name.as_ref(),
+            value.as_ref(),
+            follow,
+        )
+    }
+
+    #[inline]
+    fn list_xattrs<P: AsRef<Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+    ) -> io::Result<Vec<OsString>> {
+        list_xattrs(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            follow,
+        )
+    }
+
+    #[inline]
+    fn remove_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        remove_xattr(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            name.as_ref(),
+            follow,
+        )
+    }
+}
+
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+impl XattrExtUtf8 for cap_std::fs_utf8::Dir {
+    #[inline]
+    fn get_xattr<P: AsRef<Utf8Path>, N: AsRef<str>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<Option<Vec<u8>>> {
+        let path = from_utf8(path.as_ref())?;
+        get_xattr(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            name.as_ref().as_ref(),
+            follow,
+        )
+    }
+
+    #[inline]
+    fn set_xattr<P: AsRef<Utf8Path>, N: AsRef<str>, V: AsRef<[u8]>>(
+        &self,
+        path: P,
+        name: N,
+        value: V,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        set_xattr(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            name.as_ref().as_ref(),
+            value.as_ref(),
+            follow,
+        )
+    }
+
+    #[inline]
+    fn list_xattrs<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+    ) -> io::Result<Vec<String>> {
+        let path = from_utf8(path.as_ref())?;
+        let names = list_xattrs(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            follow,
+        )?;
+        names
+            .into_iter()
+            .map(|name| {
+                name.into_string().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "attribute name is not valid UTF-8",
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn remove_xattr<P: AsRef<Utf8Path>, N: AsRef<str>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        remove_xattr(
+            &self.as_filelike_view::<std::fs::File>(),
+            path.as_ref(),
+            name.as_ref().as_ref(),
+            follow,
+        )
+    }
+}
+
+impl FileXattrExt for std::fs::File {
+    #[inline]
+    fn get_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<Option<Vec<u8>>> {
+        get_file_xattr(self, name.as_ref())
+    }
+
+    #[inline]
+    fn set_xattr<N: AsRef<OsStr>, V: AsRef<[u8]>>(&self, name: N, value: V) -> io::Result<()> {
+        set_file_xattr(self, name.as_ref(), value.as_ref())
+    }
+
+    #[inline]
+    fn list_xattrs(&self) -> io::Result<Vec<OsString>> {
+        list_file_xattrs(self)
+    }
+
+    #[inline]
+    fn remove_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()> {
+        remove_file_xattr(self, name.as_ref())
+    }
+}
+
+#[cfg(feature = "std")]
+impl FileXattrExt for cap_std::fs::File {
+    #[inline]
+    fn get_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<Option<Vec<u8>>> {
+        get_file_xattr(&self.as_filelike_view::<std::fs::File>(), name.as_ref())
+    }
+
+    #[inline]
+    fn set_xattr<N: AsRef<OsStr>, V: AsRef<[u8]>>(&self, name: N, value: V) -> io::Result<()> {
+        set_file_xattr(
+            &self.as_filelike_view::<std::fs::File>(),
+            name.as_ref(),
+            value.as_ref(),
+        )
+    }
+
+    #[inline]
+    fn list_xattrs(&self) -> io::Result<Vec<OsString>> {
+        list_file_xattrs(&self.as_filelike_view::<std::fs::File>())
+    }
+
+    #[inline]
+    fn remove_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()> {
+        remove_file_xattr(&self.as_filelike_view::<std::fs::File>(), name.as_ref())
+    }
+}
+
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+impl FileXattrExt for cap_std::fs_utf8::File {
+    #[inline]
+    fn get_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<Option<Vec<u8>>> {
+        get_file_xattr(&self.as_filelike_view::<std::fs::File>(), name.as_ref())
+    }
+
+    #[inline]
+    fn set_xattr<N: AsRef<OsStr>, V: AsRef<[u8]>>(&self, name: N, value: V) -> io::Result<()> {
+        set_file_xattr(
+            &self.as_filelike_view::<std::fs::File>(),
+            name.as_ref(),
+            value.as_ref(),
+        )
+    }
+
+    #[inline]
+    fn list_xattrs(&self) -> io::Result<Vec<OsString>> {
+        list_file_xattrs(&self.as_filelike_view::<std::fs::File>())
+    }
+
+    #[inline]
+    fn remove_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()> {
+        remove_file_xattr(&self.as_filelike_view::<std::fs::File>(), name.as_ref())
+    }
+}
@@ -39,7 +39,7 @@
     html_favicon_url = "https://raw.githubusercontent.com/bytecodealliance/cap-std/main/media/cap-std.ico"
 )]
 
-use cap_primitives::net::no_socket_addrs;
+use cap_primitives::net::{no_socket_addrs, Access};
 use cap_std::net::{IpAddr, Pool, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
 use rustix::fd::OwnedFd;
 use std::io;
@@ -335,7 +335,7 @@ impl PoolExt for Pool {
 
         let mut last_err = None;
         for addr in addrs {
-            self._pool().check_addr(&addr)?;
+            self._pool().check_addr_access(&addr, Access::TCP_BIND)?;
 
             set_reuseaddr(listener)?;
 
@@ -359,7 +359,7 @@ impl PoolExt for Pool {
 
         let mut last_err = None;
         for addr in addrs {
-            self._pool().check_addr(&addr)?;
+            self._pool().check_addr_access(&addr, Access::UDP_BIND)?;
 
             match rustix::net::bind(socket, &addr) {
                 Ok(()) => return Ok(()),
@@ -390,7 +390,7 @@ impl PoolExt for Pool {
 
         let mut last_err = None;
         for addr in addrs {
-            self._pool().check_addr(&addr)?;
+            self._pool().check_addr_access(&addr, Access::TCP_CONNECT)?;
 
             match rustix::net::connect(socket, &addr) {
                 Ok(()) => return Ok(()),
@@ -412,7 +412,7 @@ impl PoolExt for Pool {
 
         let mut last_err = None;
         for addr in addrs {
-            self._pool().check_addr(&addr)?;
+            self._pool().check_addr_access(&addr, Access::UDP_CONNECT)?;
 
             match rustix::net::connect(socket, &addr) {
                 Ok(()) => return Ok(()),
@@ -426,30 +426,48 @@ impl PoolExt for Pool {
     }
 
     fn tcp_binder<A: ToSocketAddrs>(&self, addrs: A) -> io::Result<TcpBinder> {
-        Ok(TcpBinder(check_addrs(self._pool(), addrs)?))
+        Ok(TcpBinder(check_addrs(
+            self._pool(),
+            addrs,
+            Access::TCP_BIND,
+        )?))
     }
 
     fn udp_binder<A: ToSocketAddrs>(&self, addrs: A) -> io::Result<UdpBinder> {
-        Ok(UdpBinder(check_addrs(self._pool(), addrs)?))
+        Ok(UdpBinder(check_addrs(
+            self._pool(),
+            addrs,
+            Access::UDP_BIND,
+        )?))
     }
 
     fn tcp_connecter<A: ToSocketAddrs>(&self, addrs: A) -> io::Result<TcpConnecter> {
-        Ok(TcpConnecter(check_addrs(self._pool(), addrs)?))
+        Ok(TcpConnecter(check_addrs(
+            self._pool(),
+            addrs,
+            Access::TCP_CONNECT,
+        )?))
     }
 
     fn udp_connecter<A: ToSocketAddrs>(&self, addrs: A) -> io::Result<UdpConnecter> {
-        Ok(UdpConnecter(check_addrs(self._pool(), addrs)?))
+        Ok(UdpConnecter(check_addrs(
+            self._pool(),
+            addrs,
+            Access::UDP_CONNECT,
+        )?))
     }
 }
 
-/// Check all the addresses in `addrs` and return a new list of them.
+/// Check all the addresses in `addrs` for the operations in `access` and
+/// return a new list of them.
 fn check_addrs<A: ToSocketAddrs>(
     pool: &cap_primitives::net::Pool,
     addrs: A,
+    access: Access,
 ) -> io::Result<smallvec::SmallVec<[SocketAddr; 1]>> {
     let mut checked = smallvec::SmallVec::new();
     for addr in addrs.to_socket_addrs()? {
-        pool.check_addr(&addr)?;
+        pool.check_addr_access(&addr, access)?;
         checked.push(addr);
     }
     Ok(checked)
@@ -5,7 +5,7 @@
 use crate::fs::{
     canonicalize, create_dir_unchecked, map_result, stat_unchecked, FollowSymlinks, Metadata,
 };
-use crate::fs::{create_dir_impl, DirOptions};
+use crate::fs::{create_dir_impl, via_parent, DirOptions};
 use std::path::Path;
 use std::{fs, io};
 
@@ -29,6 +29,13 @@ pub fn create_dir(start: &fs::File, path: &Path, options: &DirOptions) -> io::Re
     result
 }
 
+/// Like `create_dir`, but fails if resolving the path would cross into a
+/// different filesystem than the one `start` is on.
+#[inline]
+pub fn create_dir_no_xdev(start: &fs::File, path: &Path, options: &DirOptions) -> io::Result<()> {
+    via_parent::create_dir_no_xdev(start, path, options)
+}
+
 #[cfg(racy_asserts)]
 #[allow(clippy::enum_glob_use)]
 fn check_create_dir(
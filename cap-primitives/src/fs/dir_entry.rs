@@ -1,5 +1,6 @@
 use crate::fs::{
-    dir_options, DirEntryInner, FileType, FollowSymlinks, Metadata, OpenOptions, ReadDir,
+    dir_options, not_dir, DirEntryInner, DirRights, FileType, FollowSymlinks, Metadata,
+    OpenOptions, ReadDir,
 };
 #[cfg(not(windows))]
 use rustix::fs::DirEntryExt;
@@ -37,6 +38,23 @@ impl DirEntry {
         self.inner.open(options)
     }
 
+    /// Open the file with the given options, failing unless `rights` permit
+    /// it.
+    ///
+    /// This corresponds to [`open_with_rights`], and similarly fails if the
+    /// entry is a directory.
+    ///
+    /// [`open_with_rights`]: crate::fs::open_with_rights
+    #[inline]
+    pub fn open_with_rights(
+        &self,
+        options: &OpenOptions,
+        rights: DirRights,
+    ) -> io::Result<fs::File> {
+        rights.check_open(options)?;
+        not_dir(self.open_with(options)?)
+    }
+
     /// Open the entry as a directory.
     #[inline]
     pub fn open_dir(&self) -> io::Result<fs::File> {
@@ -55,6 +73,22 @@ impl DirEntry {
         self.inner.remove_dir()
     }
 
+    /// Removes the file from its filesystem, failing unless `rights` permit
+    /// it.
+    #[inline]
+    pub fn remove_file_with_rights(&self, rights: DirRights) -> io::Result<()> {
+        rights.check_remove()?;
+        self.remove_file()
+    }
+
+    /// Removes the directory from its filesystem, failing unless `rights`
+    /// permit it.
+    #[inline]
+    pub fn remove_dir_with_rights(&self, rights: DirRights) -> io::Result<()> {
+        rights.check_remove()?;
+        self.remove_dir()
+    }
+
     /// Returns an iterator over the entries within the subdirectory.
     #[inline]
     pub fn read_dir(&self) -> io::Result<ReadDir> {
@@ -0,0 +1,335 @@
+//! Rights for attenuated directory handles.
+
+#[cfg(not(windows))]
+use crate::fs::symlink;
+use crate::fs::{
+    copy, create_dir, errors, hard_link, open, reflink, remove_dir, remove_dir_all, remove_file,
+    rename_with, DirOptions, Metadata, OpenOptions, RenameOptions,
+};
+#[cfg(not(target_os = "wasi"))]
+use crate::fs::{set_permissions, Permissions};
+#[cfg(windows)]
+use crate::fs::{symlink_dir, symlink_file};
+use std::path::Path;
+use std::{fs, io};
+
+/// The operations permitted through an attenuated directory handle.
+///
+/// Reading, such as opening files for reading, listing entries, and querying
+/// metadata, is always permitted. Each field grants an additional kind of
+/// modification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DirRights {
+    /// Create new files, directories, and links.
+    pub create: bool,
+    /// Open files for writing, including appending and truncating.
+    pub write: bool,
+    /// Remove files and directories.
+    pub remove: bool,
+    /// Rename files and directories.
+    pub rename: bool,
+    /// Set the permissions of files and directories.
+    pub set_permissions: bool,
+}
+
+impl DirRights {
+    /// Rights which permit every operation.
+    #[inline]
+    pub const fn all() -> Self {
+        Self {
+            create: true,
+            write: true,
+            remove: true,
+            rename: true,
+            set_permissions: true,
+        }
+    }
+
+    /// Rights which permit only reading.
+    #[inline]
+    pub const fn read_only() -> Self {
+        Self {
+            create: false,
+            write: false,
+            remove: false,
+            rename: false,
+            set_permissions: false,
+        }
+    }
+
+    /// Return the rights permitted by both `self` and `other`.
+    #[inline]
+    pub const fn intersect(self, other: Self) -> Self {
+        Self {
+            create: self.create && other.create,
+            write: self.write && other.write,
+            remove: self.remove && other.remove,
+            rename: self.rename && other.rename,
+            set_permissions: self.set_permissions && other.set_permissions,
+        }
+    }
+
+    /// Fail with a permission error unless these rights permit opening a
+    /// file with `options`.
+    pub fn check_open(&self, options: &OpenOptions) -> io::Result<()> {
+        if options.create || options.create_new {
+            self.check_create()?;
+        }
+        if options.write || options.append || options.truncate {
+            self.check_write()?;
+        }
+        #[cfg(any(unix, windows, target_os = "vxworks"))]
+        if options.ext.may_modify() {
+            self.check_create()?;
+            self.check_write()?;
+        }
+        Ok(())
+    }
+
+    /// Fail with a permission error unless these rights permit creating
+    /// files, directories, and links.
+    #[inline]
+    pub fn check_create(&self) -> io::Result<()> {
+        check(self.create, "creating entries")
+    }
+
+    /// Fail with a permission error unless these rights permit opening files
+    /// for writing.
+    #[inline]
+    pub fn check_write(&self) -> io::Result<()> {
+        check(self.write, "writing to files")
+    }
+
+    /// Fail with a permission error unless these rights permit removing files
+    /// and directories.
+    #[inline]
+    pub fn check_remove(&self) -> io::Result<()> {
+        check(self.remove, "removing entries")
+    }
+
+    /// Fail with a permission error unless these rights permit renaming files
+    /// and directories.
+    #[inline]
+    pub fn check_rename(&self) -> io::Result<()> {
+        check(self.rename, "renaming entries")
+    }
+
+    /// Fail with a permission error unless these rights permit setting
+    /// permissions.
+    #[inline]
+    pub fn check_set_permissions(&self) -> io::Result<()> {
+        check(self.set_permissions, "setting permissions")
+    }
+
+    /// Fail with a permission error unless these rights permit renaming an
+    /// entry into a directory with these rights, with `options`.
+    ///
+    /// Unless `options` requests `noreplace` or `exchange`, a rename replaces
+    /// any existing entry at the destination, so this also requires the right
+    /// to remove entries.
+    pub fn check_rename_to(&self, options: &RenameOptions) -> io::Result<()> {
+        self.check_rename()?;
+        if !options.noreplace && !options.exchange {
+            self.check_remove()?;
+        }
+        Ok(())
+    }
+}
+
+/// Open a file at `path` with `options`, failing unless `rights` permit it.
+///
+/// This fails if `path` names a directory, since a handle to a directory could
+/// be used to access the directory without the restrictions of `rights`. Use
+/// [`open_dir`] to open directories.
+///
+/// [`open_dir`]: crate::fs::open_dir
+pub fn open_with_rights(
+    start: &fs::File,
+    path: &Path,
+    options: &OpenOptions,
+    rights: DirRights,
+) -> io::Result<fs::File> {
+    rights.check_open(options)?;
+    not_dir(open(start, path, options)?)
+}
+
+/// Create a directory at `path` with `options`, failing unless `rights` permit
+/// it.
+#[inline]
+pub fn create_dir_with_rights(
+    start: &fs::File,
+    path: &Path,
+    options: &DirOptions,
+    rights: DirRights,
+) -> io::Result<()> {
+    rights.check_create()?;
+    create_dir(start, path, options)
+}
+
+/// Copy the file at `from_path` to `to_path`, failing unless `to_rights`
+/// permit creating and writing files.
+#[inline]
+pub fn copy_with_rights(
+    from_start: &fs::File,
+    from_path: &Path,
+    to_start: &fs::File,
+    to_path: &Path,
+    to_rights: DirRights,
+) -> io::Result<u64> {
+    to_rights.check_open(&copy_destination_options())?;
+    copy(from_start, from_path, to_start, to_path)
+}
+
+/// Make `to_path` a copy-on-write clone of the file at `from_path`, failing
+/// unless `to_rights` permit creating and writing files.
+#[inline]
+pub fn reflink_with_rights(
+    from_start: &fs::File,
+    from_path: &Path,
+    to_start: &fs::File,
+    to_path: &Path,
+    to_rights: DirRights,
+) -> io::Result<u64> {
+    to_rights.check_open(&copy_destination_options())?;
+    reflink(from_start, from_path, to_start, to_path)
+}
+
+/// Create a hard link at `new_path`, failing unless `new_rights` permit
+/// creating entries.
+#[inline]
+pub fn hard_link_with_rights(
+    old_start: &fs::File,
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    new_rights: DirRights,
+) -> io::Result<()> {
+    new_rights.check_create()?;
+    hard_link(old_start, old_path, new_start, new_path)
+}
+
+/// Remove the empty directory at `path`, failing unless `rights` permit it.
+#[inline]
+pub fn remove_dir_with_rights(start: &fs::File, path: &Path, rights: DirRights) -> io::Result<()> {
+    rights.check_remove()?;
+    remove_dir(start, path)
+}
+
+/// Remove the directory at `path` and all of its contents, failing unless
+/// `rights` permit it.
+#[inline]
+pub fn remove_dir_all_with_rights(
+    start: &fs::File,
+    path: &Path,
+    rights: DirRights,
+) -> io::Result<()> {
+    rights.check_remove()?;
+    remove_dir_all(start, path)
+}
+
+/// Remove the file at `path`, failing unless `rights` permit it.
+#[inline]
+pub fn remove_file_with_rights(start: &fs::File, path: &Path, rights: DirRights) -> io::Result<()> {
+    rights.check_remove()?;
+    remove_file(start, path)
+}
+
+/// Rename `old_path` to `new_path` with `options`, failing unless
+/// `old_rights` permit renaming and `new_rights` permit
+/// [renaming into the destination].
+///
+/// [renaming into the destination]: DirRights::check_rename_to
+#[inline]
+pub fn rename_with_rights(
+    old_start: &fs::File,
+    old_path: &Path,
+    old_rights: DirRights,
+    new_start: &fs::File,
+    new_path: &Path,
+    new_rights: DirRights,
+    options: &RenameOptions,
+) -> io::Result<()> {
+    old_rights.check_rename()?;
+    new_rights.check_rename_to(options)?;
+    rename_with(old_start, old_path, new_start, new_path, options)
+}
+
+/// Set the permissions of the file or directory at `path`, failing unless
+/// `rights` permit it.
+#[cfg(not(target_os = "wasi"))]
+#[inline]
+pub fn set_permissions_with_rights(
+    start: &fs::File,
+    path: &Path,
+    perm: Permissions,
+    rights: DirRights,
+) -> io::Result<()> {
+    rights.check_set_permissions()?;
+    set_permissions(start, path, perm)
+}
+
+/// Create a symlink at `new_path`, failing unless `new_rights` permit creating
+/// entries.
+#[cfg(not(windows))]
+#[inline]
+pub fn symlink_with_rights(
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    new_rights: DirRights,
+) -> io::Result<()> {
+    new_rights.check_create()?;
+    symlink(old_path, new_start, new_path)
+}
+
+/// Create a file symlink at `new_path`, failing unless `new_rights` permit
+/// creating entries.
+#[cfg(windows)]
+#[inline]
+pub fn symlink_file_with_rights(
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    new_rights: DirRights,
+) -> io::Result<()> {
+    new_rights.check_create()?;
+    symlink_file(old_path, new_start, new_path)
+}
+
+/// Create a directory symlink at `new_path`, failing unless `new_rights`
+/// permit creating entries.
+#[cfg(windows)]
+#[inline]
+pub fn symlink_dir_with_rights(
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    new_rights: DirRights,
+) -> io::Result<()> {
+    new_rights.check_create()?;
+    symlink_dir(old_path, new_start, new_path)
+}
+
+/// The options `copy` and `reflink` use to open their destination.
+fn copy_destination_options() -> OpenOptions {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    options
+}
+
+/// Fail if `file`, which was opened through an attenuated directory handle,
+/// is a directory.
+pub(crate) fn not_dir(file: fs::File) -> io::Result<fs::File> {
+    if Metadata::from_file(&file)?.is_dir() {
+        return Err(errors::is_directory());
+    }
+    Ok(file)
+}
+
+fn check(permitted: bool, operation: &str) -> io::Result<()> {
+    if permitted {
+        Ok(())
+    } else {
+        Err(errors::not_permitted_by_rights(operation))
+    }
+}
@@ -12,3 +12,19 @@ pub(crate) fn escape_attempt() -> io::Error {
         "a path led outside of the filesystem",
     )
 }
+
+#[cold]
+pub(crate) fn not_permitted_by_rights(operation: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("the directory's rights do not permit {}", operation),
+    )
+}
+
+#[cold]
+pub(crate) fn reflink_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "copy-on-write clones are not supported for these files",
+    )
+}
@@ -0,0 +1,111 @@
+//! This defines functions for preallocating space in files and for working
+//! with sparse files.
+
+use crate::fs::{allocate_impl, punch_hole_impl, seek_data_impl, seek_hole_impl, zero_range_impl};
+use io_lifetimes::{AsFilelike, BorrowedFilelike};
+use std::ops::Range;
+use std::{fs, io};
+
+/// Allocate space for the `len` bytes starting at `offset` in `file`,
+/// extending the file if needed.
+///
+/// This corresponds to `fallocate` with no flags.
+#[inline]
+pub fn allocate(file: &fs::File, offset: u64, len: u64) -> io::Result<()> {
+    allocate_impl(file, offset, len)
+}
+
+/// Deallocate the `len` bytes starting at `offset` in `file`, leaving a hole
+/// which reads as zeros. The file's size is not changed.
+///
+/// This corresponds to `fallocate` with `FALLOC_FL_PUNCH_HOLE`.
+#[inline]
+pub fn punch_hole(file: &fs::File, offset: u64, len: u64) -> io::Result<()> {
+    punch_hole_impl(file, offset, len)
+}
+
+/// Zero the `len` bytes starting at `offset` in `file`, extending the file
+/// if needed, without necessarily writing to the underlying storage.
+///
+/// This corresponds to `fallocate` with `FALLOC_FL_ZERO_RANGE`.
+#[inline]
+pub fn zero_range(file: &fs::File, offset: u64, len: u64) -> io::Result<()> {
+    zero_range_impl(file, offset, len)
+}
+
+/// Return the offset of the first data at or after `offset` in `file`, or
+/// `None` if there is only a hole after `offset`.
+///
+/// This corresponds to `lseek` with `SEEK_DATA`, and moves the file's
+/// position to the returned offset.
+#[inline]
+pub fn seek_data(file: &fs::File, offset: u64) -> io::Result<Option<u64>> {
+    seek_data_impl(file, offset)
+}
+
+/// Return the offset of the first hole at or after `offset` in `file`. There
+/// is an implicit hole at the end of every file.
+///
+/// This corresponds to `lseek` with `SEEK_HOLE`, and moves the file's
+/// position to the returned offset.
+#[inline]
+pub fn seek_hole(file: &fs::File, offset: u64) -> io::Result<u64> {
+    seek_hole_impl(file, offset)
+}
+
+/// Return an iterator over the ranges of `file` which contain data, skipping
+/// over holes.
+///
+/// Filesystems which don't track holes report the whole file as data. The
+/// iterator moves the file's position.
+#[inline]
+pub fn data_extents<Filelike: AsFilelike>(file: &Filelike) -> DataExtents<'_> {
+    DataExtents {
+        file: file.as_filelike(),
+        offset: 0,
+        done: false,
+    }
+}
+
+/// An iterator over the ranges of a file which contain data.
+///
+/// This `struct` is created by [`data_extents`].
+#[derive(Debug)]
+pub struct DataExtents<'file> {
+    file: BorrowedFilelike<'file>,
+    offset: u64,
+    done: bool,
+}
+
+impl<'file> Iterator for DataExtents<'file> {
+    type Item = io::Result<Range<u64>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let file = self.file.as_filelike_view::<fs::File>();
+        let result = (|| {
+            let start = match seek_data(&file, self.offset)? {
+                Some(start) => start,
+                None => return Ok(None),
+            };
+            let end = seek_hole(&file, start)?;
+            Ok(Some(start..end))
+        })();
+        match result {
+            Ok(Some(extent)) => {
+                self.offset = extent.end;
+                Some(Ok(extent))
+            }
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! This defines advisory file locking.
+
+use crate::fs::{lock_impl, unlock_impl};
+#[cfg(any(target_os = "linux", windows))]
+use crate::fs::{lock_range_impl, unlock_range_impl};
+use std::{fs, io};
+
+/// The kind of an advisory lock.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum LockKind {
+    /// A shared lock, which may be held by any number of handles at once.
+    Shared,
+
+    /// An exclusive lock, which may only be held by one handle at a time.
+    Exclusive,
+}
+
+/// Acquire an advisory lock on the whole of `file`.
+///
+/// If `wait` is `true`, this blocks until the lock can be acquired. If it's
+/// `false` and the lock is held elsewhere, this returns `Ok(false)`.
+///
+/// On Unix-family platforms this uses `flock`, so the lock is associated with
+/// the open file description and is released when all handles to it are
+/// closed.
+#[inline]
+pub fn lock(file: &fs::File, kind: LockKind, wait: bool) -> io::Result<bool> {
+    lock_impl(file, kind, wait)
+}
+
+/// Release an advisory lock acquired with [`lock`].
+#[inline]
+pub fn unlock(file: &fs::File) -> io::Result<()> {
+    unlock_impl(file)
+}
+
+/// Acquire an advisory lock on the `len` bytes of `file` starting at
+/// `offset`. A `len` of 0 extends the range to the end of the file and
+/// beyond.
+///
+/// If `wait` is `true`, this blocks until the lock can be acquired. If it's
+/// `false` and the range is locked elsewhere, this returns `Ok(false)`.
+///
+/// On Linux this uses open file description locks (`F_OFD_SETLK`), which
+/// unlike traditional `fcntl` locks are not released when some other handle
+/// to the file in the process is closed.
+#[cfg(any(target_os = "linux", windows))]
+#[inline]
+pub fn lock_range(
+    file: &fs::File,
+    kind: LockKind,
+    offset: u64,
+    len: u64,
+    wait: bool,
+) -> io::Result<bool> {
+    lock_range_impl(file, kind, offset, len, wait)
+}
+
+/// Release an advisory lock acquired with [`lock_range`].
+#[cfg(any(target_os = "linux", windows))]
+#[inline]
+pub fn unlock_range(file: &fs::File, offset: u64, len: u64) -> io::Result<()> {
+    unlock_range_impl(file, offset, len)
+}
@@ -18,6 +18,6 @@ use read_link_one::read_link_one;
 pub(super) use canonicalize::canonicalize_with;
 
 pub(crate) use canonicalize::canonicalize;
-pub(crate) use open::{open, stat};
+pub(crate) use open::{open, stat, stat_no_xdev};
 #[cfg(not(any(windows, target_os = "freebsd")))]
 pub(crate) use open_entry::open_entry;
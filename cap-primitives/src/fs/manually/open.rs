@@ -3,7 +3,7 @@
 
 use super::{read_link_one, CanonicalPath, CowComponent};
 use crate::fs::{
-    dir_options, errors, open_unchecked, path_has_trailing_dot, path_has_trailing_slash,
+    device_id, dir_options, errors, open_unchecked, path_has_trailing_dot, path_has_trailing_slash,
     stat_unchecked, FollowSymlinks, MaybeOwnedFile, Metadata, OpenOptions, OpenUncheckedError,
 };
 #[cfg(any(target_os = "android", target_os = "linux", target_os = "freebsd"))]
@@ -60,6 +60,13 @@ struct Context<'start> {
     /// allocations.
     reuse: PathBuf,
 
+    /// Should following a symlink be an error?
+    no_symlinks: bool,
+
+    /// If we must not cross mount points, the device that every component
+    /// must be on.
+    device: Option<u64>,
+
     #[cfg(racy_asserts)]
     start_clone: MaybeOwnedFile<'start>,
 }
@@ -69,7 +76,7 @@ impl<'start> Context<'start> {
     fn new(
         start: MaybeOwnedFile<'start>,
         path: &'start Path,
-        _options: &OpenOptions,
+        options: &OpenOptions,
         canonical_path: Option<&'start mut PathBuf>,
     ) -> Self {
         let trailing_slash = path_has_trailing_slash(path);
@@ -116,7 +123,7 @@ impl<'start> Context<'start> {
             dir_required: trailing_slash,
 
             #[cfg(not(windows))]
-            dir_precluded: _options.write || _options.append,
+            dir_precluded: options.write || options.append,
 
             #[cfg(windows)]
             dir_precluded: false,
@@ -127,6 +134,10 @@ impl<'start> Context<'start> {
 
             reuse: PathBuf::new(),
 
+            no_symlinks: options.no_symlinks,
+
+            device: None,
+
             #[cfg(racy_asserts)]
             start_clone,
         }
@@ -235,6 +246,21 @@ impl<'start> Context<'start> {
 
         let dir_required = self.dir_required || use_options.dir_required;
 
+        // If we must not cross mount points, check the last component's device
+        // before opening it, since opening it with the caller's options may
+        // have side effects, such as truncating it. If it doesn't exist yet,
+        // anything we create is on the same device as its parent. Errors are
+        // left for `open_unchecked` to report.
+        #[cfg(not(windows))]
+        if let (Some(device), true) = (self.device, self.components.is_empty()) {
+            use crate::fs::MetadataExt;
+            if let Ok(stat) = stat_unchecked(&self.base, use_path.as_ref(), FollowSymlinks::No) {
+                if stat.dev() != device {
+                    return Err(errors::crosses_devices());
+                }
+            }
+        }
+
         #[allow(clippy::redundant_clone)]
         match open_unchecked(
             &self.base,
@@ -267,6 +293,14 @@ impl<'start> Context<'start> {
                     }
                 }
 
+                // If we must not cross mount points, check that this component
+                // is on the same device as the start.
+                if let Some(device) = self.device {
+                    if device_id(&file)? != device {
+                        return Err(errors::crosses_devices());
+                    }
+                }
+
                 // Normal case
                 let prev_base = self.base.descend_to(MaybeOwnedFile::owned(file));
                 self.dirs.push(prev_base);
@@ -314,6 +348,10 @@ impl<'start> Context<'start> {
 
     /// Push the components of `destination` onto the worklist stack.
     fn push_symlink_destination(&mut self, destination: PathBuf) -> io::Result<()> {
+        if self.no_symlinks {
+            return Err(errors::too_many_symlinks());
+        }
+
         let at_end = self.components.is_empty();
         let trailing_slash = path_has_trailing_slash(&destination);
         let trailing_dot = path_has_trailing_dot(&destination);
@@ -419,8 +457,22 @@ pub(super) fn internal_open<'start>(
         return Err(errors::no_such_file_or_directory());
     }
 
+    // We can't tell whether the lookup could be satisfied from the kernel's
+    // cache without doing it, so fail as `openat2` with `RESOLVE_CACHED`
+    // does, and let the caller retry without `cached`.
+    if options.cached {
+        return Err(errors::would_block());
+    }
+
     let mut ctx = Context::new(start, path, options, canonical_path);
 
+    // Magic links are never followed here, since symlinks are resolved by
+    // reading their contents, so `no_magiclinks` needs no checks. For
+    // `no_xdev`, compare each component's device with the start's.
+    if options.no_xdev {
+        ctx.device = Some(device_id(&ctx.base)?);
+    }
+
     while let Some(c) = ctx.components.pop() {
         match c {
             CowComponent::PrefixOrRootDir => return Err(errors::escape_attempt()),
@@ -456,17 +508,32 @@ pub(super) fn internal_open<'start>(
 
 /// Implement manual `stat` in a similar manner as manual `open`.
 pub(crate) fn stat(start: &fs::File, path: &Path, follow: FollowSymlinks) -> io::Result<Metadata> {
+    stat_with(start, path, OpenOptions::new().follow(follow))
+}
+
+/// Like `stat`, but fail if resolving `path` would cross into a different
+/// filesystem than the one `start` is on.
+pub(crate) fn stat_no_xdev(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+) -> io::Result<Metadata> {
+    stat_with(start, path, OpenOptions::new().follow(follow).no_xdev(true))
+}
+
+fn stat_with(start: &fs::File, path: &Path, options: &OpenOptions) -> io::Result<Metadata> {
     // POSIX returns `ENOENT` on an empty path. TODO: On Windows, we should
     // be compatible with what Windows does instead.
     if path.as_os_str().is_empty() {
         return Err(errors::no_such_file_or_directory());
     }
 
-    let mut options = OpenOptions::new();
-    options.follow(follow);
     let mut symlink_count = 0;
-    let mut ctx = Context::new(MaybeOwnedFile::borrowed(start), path, &options, None);
+    let mut ctx = Context::new(MaybeOwnedFile::borrowed(start), path, options, None);
     assert!(!ctx.dir_precluded);
+    if options.no_xdev {
+        ctx.device = Some(device_id(&ctx.base)?);
+    }
 
     while let Some(c) = ctx.components.pop() {
         match c {
@@ -479,6 +546,17 @@ pub(crate) fn stat(start: &fs::File, path: &Path, follow: FollowSymlinks) -> io:
                     // `stat_unchecked` on it.
                     let stat = stat_unchecked(&ctx.base, one.as_ref(), FollowSymlinks::No)?;
 
+                    // A mount point in the last component isn't opened, so
+                    // check its device here. On Windows, volumes are mounted
+                    // at reparse points, which are handled as symlinks.
+                    #[cfg(not(windows))]
+                    if let Some(device) = ctx.device {
+                        use crate::fs::MetadataExt;
+                        if stat.dev() != device {
+                            return Err(errors::crosses_devices());
+                        }
+                    }
+
                     // If we weren't asked to follow symlinks, or it wasn't a
                     // symlink, we're done.
                     if options.follow == FollowSymlinks::No || !stat.file_type().is_symlink() {
@@ -506,7 +584,7 @@ pub(crate) fn stat(start: &fs::File, path: &Path, follow: FollowSymlinks) -> io:
                     ctx.symlink(&one, &mut symlink_count)?
                 } else {
                     // Otherwise open the path component normally.
-                    ctx.normal(&one, &options, &mut symlink_count)?
+                    ctx.normal(&one, options, &mut symlink_count)?
                 }
             }
         }
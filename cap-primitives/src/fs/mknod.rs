@@ -0,0 +1,43 @@
+//! This defines `mknod` and `create_fifo`, for sandboxed creation of special
+//! files.
+
+use crate::fs::{create_fifo_impl, mknod_impl};
+use std::path::Path;
+use std::{fs, io};
+
+/// The type of file to create with [`mknod`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    /// An empty regular file.
+    RegularFile,
+    /// A FIFO, also known as a named pipe.
+    Fifo,
+    /// A character device.
+    CharacterDevice,
+    /// A block device.
+    BlockDevice,
+    /// A Unix-domain socket.
+    Socket,
+}
+
+/// Perform a `mknodat`-like operation, ensuring that the resolution of the
+/// path never escapes the directory tree rooted at `start`.
+///
+/// `mode` holds the permission bits of the new file, which are modified by
+/// the process's umask. `dev` is the device number for character and block
+/// devices, and is ignored otherwise. Creating devices typically requires
+/// privileges.
+#[inline]
+pub fn mknod(start: &fs::File, path: &Path, kind: NodeKind, mode: u32, dev: u64) -> io::Result<()> {
+    mknod_impl(start, path, kind, mode, dev)
+}
+
+/// Perform a `mkfifoat`-like operation, ensuring that the resolution of the
+/// path never escapes the directory tree rooted at `start`.
+///
+/// `mode` holds the permission bits of the new FIFO, which are modified by
+/// the process's umask.
+#[inline]
+pub fn create_fifo(start: &fs::File, path: &Path, mode: u32) -> io::Result<()> {
+    create_fifo_impl(start, path, mode)
+}
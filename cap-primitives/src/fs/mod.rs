@@ -11,15 +11,22 @@ mod create_dir;
 mod dir_builder;
 mod dir_entry;
 mod dir_options;
+mod dir_rights;
 mod file;
 #[cfg(not(any(target_os = "android", target_os = "linux", windows)))]
 mod file_path_by_searching;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod file_space;
 mod file_type;
 mod follow_symlinks;
 mod hard_link;
 mod is_file_read_write;
+#[cfg(not(target_os = "wasi"))]
+mod lock;
 mod maybe_owned_file;
 mod metadata;
+#[cfg(not(any(windows, target_os = "wasi")))]
+mod mknod;
 mod open;
 mod open_ambient;
 mod open_dir;
@@ -28,18 +35,30 @@ mod open_unchecked_error;
 mod permissions;
 mod read_dir;
 mod read_link;
+mod reflink;
 mod remove_dir;
 mod remove_dir_all;
 mod remove_file;
 mod remove_open_dir;
 mod rename;
+mod rename_options;
 mod reopen;
+#[cfg(not(any(windows, target_os = "wasi")))]
+mod set_owner;
 #[cfg(not(target_os = "wasi"))]
 mod set_permissions;
 mod set_times;
 mod stat;
+#[cfg(target_os = "linux")]
+mod statx;
 mod symlink;
 mod system_time_spec;
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+mod uring;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod watch;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod xattr;
 
 pub(crate) mod errors;
 pub(crate) mod manually;
@@ -47,6 +66,7 @@ pub(crate) mod via_parent;
 
 use maybe_owned_file::MaybeOwnedFile;
 
+pub(crate) use dir_rights::not_dir;
 #[cfg(not(any(target_os = "android", target_os = "linux", windows)))]
 pub(crate) use file_path_by_searching::file_path_by_searching;
 pub(crate) use open_unchecked_error::*;
@@ -62,13 +82,28 @@ pub(crate) use read_dir::{read_dir_nofollow, read_dir_unchecked};
 pub use access::{access, AccessModes, AccessType};
 pub use canonicalize::canonicalize;
 pub use copy::copy;
-pub use create_dir::create_dir;
+pub use create_dir::{create_dir, create_dir_no_xdev};
 pub use dir_builder::*;
 pub use dir_entry::DirEntry;
 #[cfg(windows)]
 pub use dir_entry::_WindowsDirEntryExt;
 pub use dir_options::DirOptions;
+#[cfg(not(target_os = "wasi"))]
+pub use dir_rights::set_permissions_with_rights;
+#[cfg(not(windows))]
+pub use dir_rights::symlink_with_rights;
+pub use dir_rights::{
+    copy_with_rights, create_dir_with_rights, hard_link_with_rights, open_with_rights,
+    reflink_with_rights, remove_dir_all_with_rights, remove_dir_with_rights,
+    remove_file_with_rights, rename_with_rights, DirRights,
+};
+#[cfg(windows)]
+pub use dir_rights::{symlink_dir_with_rights, symlink_file_with_rights};
 pub use file::FileExt;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub use file_space::{
+    allocate, data_extents, punch_hole, seek_data, seek_hole, zero_range, DataExtents,
+};
 pub use file_type::FileType;
 #[cfg(any(unix, target_os = "vxworks", all(windows, windows_file_type_ext)))]
 pub use file_type::FileTypeExt;
@@ -77,10 +112,16 @@ pub use file_type::_WindowsFileTypeExt;
 pub use follow_symlinks::FollowSymlinks;
 pub use hard_link::hard_link;
 pub use is_file_read_write::is_file_read_write;
+#[cfg(not(target_os = "wasi"))]
+pub use lock::{lock, unlock, LockKind};
+#[cfg(any(target_os = "linux", windows))]
+pub use lock::{lock_range, unlock_range};
 #[cfg(windows)]
 pub use metadata::_WindowsByHandle;
 pub use metadata::{Metadata, MetadataExt};
-pub use open::open;
+#[cfg(not(any(windows, target_os = "wasi")))]
+pub use mknod::{create_fifo, mknod, NodeKind};
+pub use open::{open, open_no_xdev};
 pub use open_ambient::open_ambient;
 pub use open_dir::*;
 pub use open_options::*;
@@ -89,21 +130,36 @@ pub use permissions::Permissions;
 pub use permissions::PermissionsExt;
 pub use read_dir::{read_base_dir, read_dir, ReadDir};
 pub use read_link::{read_link, read_link_contents};
-pub use remove_dir::remove_dir;
-pub use remove_dir_all::remove_dir_all;
-pub use remove_file::remove_file;
+pub use reflink::{clone_range, reflink};
+pub use remove_dir::{remove_dir, remove_dir_no_xdev};
+pub use remove_dir_all::{remove_dir_all, remove_dir_all_no_xdev};
+pub use remove_file::{remove_file, remove_file_no_xdev};
 pub use remove_open_dir::{remove_open_dir, remove_open_dir_all};
-pub use rename::rename;
+pub use rename::{rename, rename_with, rename_with_no_xdev};
+pub use rename_options::RenameOptions;
 pub use reopen::reopen;
+#[cfg(not(any(windows, target_os = "wasi")))]
+pub use set_owner::{set_file_owner, set_owner, set_owner_recursive};
 #[cfg(not(target_os = "wasi"))]
 pub use set_permissions::{set_permissions, set_symlink_permissions};
 pub use set_times::{set_times, set_times_nofollow};
-pub use stat::stat;
+pub use stat::{stat, stat_no_xdev};
+#[cfg(target_os = "linux")]
+pub use statx::{file_statx, statx, ExtendedMetadata};
 #[cfg(not(windows))]
 pub use symlink::{symlink, symlink_contents};
 #[cfg(windows)]
 pub use symlink::{symlink_dir, symlink_file};
 pub use system_time_spec::SystemTimeSpec;
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+pub use uring::Uring;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub use watch::{watch, WatchEvent, Watcher};
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub use xattr::{
+    get_file_xattr, get_xattr, list_file_xattrs, list_xattrs, remove_file_xattr, remove_xattr,
+    set_file_xattr, set_xattr,
+};
 
 #[cfg(racy_asserts)]
 fn map_result<T: Clone>(result: &std::io::Result<T>) -> Result<T, (std::io::ErrorKind, String)> {
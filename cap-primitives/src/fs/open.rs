@@ -3,7 +3,7 @@
 
 #[cfg(racy_asserts)]
 use crate::fs::{file_path, open_unchecked, stat_unchecked, Metadata};
-use crate::fs::{open_impl, OpenOptions};
+use crate::fs::{not_dir, open_impl, OpenOptions};
 use std::path::Path;
 use std::{fs, io};
 
@@ -27,6 +27,18 @@ pub fn open(start: &fs::File, path: &Path, options: &OpenOptions) -> io::Result<
     result
 }
 
+/// Like `open`, but fails if resolving the path would cross into a different
+/// filesystem than the one `start` is on, or if the path names a directory.
+///
+/// A handle to a directory could be used to resolve paths without this
+/// restriction, so directories must be opened with [`open_dir_no_xdev`].
+///
+/// [`open_dir_no_xdev`]: crate::fs::open_dir_no_xdev
+#[inline]
+pub fn open_no_xdev(start: &fs::File, path: &Path, options: &OpenOptions) -> io::Result<fs::File> {
+    not_dir(open(start, path, options.clone().no_xdev(true))?)
+}
+
 #[cfg(racy_asserts)]
 fn check_open(
     start: &fs::File,
@@ -34,6 +34,25 @@ pub fn open_dir_nofollow(start: &fs::File, path: &Path) -> io::Result<fs::File>
     open(start, path, dir_options().follow(FollowSymlinks::No))
 }
 
+/// Like `open_dir`, but fails if resolving the path would cross into a
+/// different filesystem than the one `start` is on.
+#[inline]
+pub fn open_dir_no_xdev(start: &fs::File, path: &Path) -> io::Result<fs::File> {
+    open(start, path, dir_options().no_xdev(true))
+}
+
+/// Like `open_dir_nofollow`, but fails if resolving the path would cross into
+/// a different filesystem than the one `start` is on.
+#[cfg(not(windows))]
+#[inline]
+pub(crate) fn open_dir_nofollow_no_xdev(start: &fs::File, path: &Path) -> io::Result<fs::File> {
+    open(
+        start,
+        path,
+        dir_options().follow(FollowSymlinks::No).no_xdev(true),
+    )
+}
+
 /// Open a directory by performing an unsandboxed `openat`-like operation.
 #[inline]
 #[allow(dead_code)]
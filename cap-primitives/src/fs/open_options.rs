@@ -31,6 +31,10 @@ pub struct OpenOptions {
     pub(crate) nonblock: bool,
     pub(crate) readdir_required: bool,
     pub(crate) follow: FollowSymlinks,
+    pub(crate) no_xdev: bool,
+    pub(crate) no_magiclinks: bool,
+    pub(crate) no_symlinks: bool,
+    pub(crate) cached: bool,
 
     #[cfg(any(unix, windows, target_os = "vxworks"))]
     pub(crate) ext: ImplOpenOptionsExt,
@@ -58,6 +62,10 @@ impl OpenOptions {
             nonblock: false,
             readdir_required: false,
             follow: FollowSymlinks::Yes,
+            no_xdev: false,
+            no_magiclinks: false,
+            no_symlinks: false,
+            cached: false,
 
             #[cfg(any(unix, windows, target_os = "vxworks"))]
             ext: ImplOpenOptionsExt::new(),
@@ -183,6 +191,36 @@ impl OpenOptions {
         self
     }
 
+    /// Sets the option to fail if path resolution would cross a mount point.
+    #[inline]
+    pub(crate) fn no_xdev(&mut self, enable: bool) -> &mut Self {
+        self.no_xdev = enable;
+        self
+    }
+
+    /// Sets the option to fail if path resolution would follow a "magic link",
+    /// such as those in `/proc/self/fd`.
+    #[inline]
+    pub(crate) fn no_magiclinks(&mut self, enable: bool) -> &mut Self {
+        self.no_magiclinks = enable;
+        self
+    }
+
+    /// Sets the option to fail if path resolution would follow any symlink.
+    #[inline]
+    pub(crate) fn no_symlinks(&mut self, enable: bool) -> &mut Self {
+        self.no_symlinks = enable;
+        self
+    }
+
+    /// Sets the option to fail with `std::io::ErrorKind::WouldBlock` unless
+    /// path resolution can be done entirely from the kernel's lookup cache.
+    #[inline]
+    pub(crate) fn cached(&mut self, enable: bool) -> &mut Self {
+        self.cached = enable;
+        self
+    }
+
     /// Wrapper to allow `follow` to be exposed by the `cap-fs-ext` crate.
     ///
     /// This is hidden from the main API since this functionality isn't present
@@ -248,6 +286,51 @@ impl OpenOptions {
     pub fn _cap_fs_ext_nonblock(&mut self, enable: bool) -> &mut Self {
         self.nonblock(enable)
     }
+
+    /// Wrapper to allow `no_xdev` to be exposed by the `cap-fs-ext` crate.
+    ///
+    /// This is hidden from the main API since this functionality isn't present
+    /// in `std`. Use `cap_fs_ext::OpenOptionsResolveExt` instead of
+    /// calling this directly.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_fs_ext_no_xdev(&mut self, enable: bool) -> &mut Self {
+        self.no_xdev(enable)
+    }
+
+    /// Wrapper to allow `no_magiclinks` to be exposed by the `cap-fs-ext`
+    /// crate.
+    ///
+    /// This is hidden from the main API since this functionality isn't present
+    /// in `std`. Use `cap_fs_ext::OpenOptionsResolveExt` instead of
+    /// calling this directly.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_fs_ext_no_magiclinks(&mut self, enable: bool) -> &mut Self {
+        self.no_magiclinks(enable)
+    }
+
+    /// Wrapper to allow `no_symlinks` to be exposed by the `cap-fs-ext` crate.
+    ///
+    /// This is hidden from the main API since this functionality isn't present
+    /// in `std`. Use `cap_fs_ext::OpenOptionsResolveExt` instead of
+    /// calling this directly.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_fs_ext_no_symlinks(&mut self, enable: bool) -> &mut Self {
+        self.no_symlinks(enable)
+    }
+
+    /// Wrapper to allow `cached` to be exposed by the `cap-fs-ext` crate.
+    ///
+    /// This is hidden from the main API since this functionality isn't present
+    /// in `std`. Use `cap_fs_ext::OpenOptionsResolveExt` instead of
+    /// calling this directly.
+    #[doc(hidden)]
+    #[inline]
+    pub fn _cap_fs_ext_cached(&mut self, enable: bool) -> &mut Self {
+        self.cached(enable)
+    }
 }
 
 /// Unix-specific extensions to [`fs::OpenOptions`].
@@ -0,0 +1,65 @@
+//! This defines `reflink` and `clone_range`, for copy-on-write copies of
+//! files and file ranges.
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+use crate::fs::errors;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::fs::{clone_range_impl, reflink_impl};
+use std::path::Path;
+use std::{fs, io};
+
+/// Make a copy-on-write clone of the file at `from_path` at `to_path`,
+/// ensuring that the resolution of the paths never escapes the directory
+/// trees rooted at `from_start` and `to_start`. The clone shares storage with
+/// the original until either is modified.
+///
+/// As with [`copy`], `to_path` is created or truncated, and gets the
+/// permission bits of `from_path`. If it fails after that, `to_path` may be
+/// left empty.
+///
+/// If the platform, filesystem, or the particular files don't support
+/// cloning, this fails with [`io::ErrorKind::Unsupported`], so that callers
+/// can fall back to [`copy`].
+///
+/// This corresponds to the `FICLONE` ioctl on Linux.
+///
+/// [`copy`]: crate::fs::copy
+#[inline]
+pub fn reflink(
+    from_start: &fs::File,
+    from_path: &Path,
+    to_start: &fs::File,
+    to_path: &Path,
+) -> io::Result<u64> {
+    reflink_impl(from_start, from_path, to_start, to_path)
+}
+
+/// Make the `len` bytes at `dst_offset` in `dst` a copy-on-write clone of
+/// the `len` bytes at `src_offset` in `src`.
+///
+/// Offsets and lengths typically need to be multiples of the filesystem's
+/// block size; `len` may be zero to clone to the end of `src`. If the
+/// platform, filesystem, or the particular files don't support cloning, this
+/// fails with [`io::ErrorKind::Unsupported`].
+///
+/// This corresponds to the `FICLONERANGE` ioctl on Linux.
+#[inline]
+pub fn clone_range(
+    src: &fs::File,
+    src_offset: u64,
+    len: u64,
+    dst: &fs::File,
+    dst_offset: u64,
+) -> io::Result<()> {
+    clone_range_impl(src, src_offset, len, dst, dst_offset)
+}
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+fn reflink_impl(_: &fs::File, _: &Path, _: &fs::File, _: &Path) -> io::Result<u64> {
+    Err(errors::reflink_unsupported())
+}
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+fn clone_range_impl(_: &fs::File, _: u64, _: u64, _: &fs::File, _: u64) -> io::Result<()> {
+    Err(errors::reflink_unsupported())
+}
@@ -1,11 +1,11 @@
 //! This defines `remove_dir`, the primary entrypoint to sandboxed file
 //! removal.
 
-use crate::fs::remove_dir_impl;
 #[cfg(racy_asserts)]
 use crate::fs::{
     manually, map_result, remove_dir_unchecked, stat_unchecked, FollowSymlinks, Metadata,
 };
+use crate::fs::{remove_dir_impl, via_parent};
 use std::path::Path;
 use std::{fs, io};
 
@@ -29,6 +29,13 @@ pub fn remove_dir(start: &fs::File, path: &Path) -> io::Result<()> {
     result
 }
 
+/// Like `remove_dir`, but fails if resolving the path would cross into a
+/// different filesystem than the one `start` is on.
+#[inline]
+pub fn remove_dir_no_xdev(start: &fs::File, path: &Path) -> io::Result<()> {
+    via_parent::remove_dir_no_xdev(start, path)
+}
+
 #[cfg(racy_asserts)]
 #[allow(clippy::enum_glob_use)]
 fn check_remove_dir(
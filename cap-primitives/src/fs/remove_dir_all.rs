@@ -1,4 +1,9 @@
-use crate::fs::remove_dir_all_impl;
+#[cfg(not(windows))]
+use crate::fs::{
+    open_dir_nofollow_no_xdev, read_base_dir, remove_dir_no_xdev, remove_dir_unchecked,
+    remove_file_no_xdev, remove_file_unchecked,
+};
+use crate::fs::{remove_dir_all_impl, stat_no_xdev, FollowSymlinks};
 use std::path::Path;
 use std::{fs, io};
 
@@ -7,3 +12,44 @@ use std::{fs, io};
 pub fn remove_dir_all(start: &fs::File, path: &Path) -> io::Result<()> {
     remove_dir_all_impl(start, path)
 }
+
+/// Like `remove_dir_all`, but fails if resolving the path, or any directory
+/// within it, would cross into a different filesystem than the one `start` is
+/// on.
+#[cfg(not(windows))]
+pub fn remove_dir_all_no_xdev(start: &fs::File, path: &Path) -> io::Result<()> {
+    let filetype = stat_no_xdev(start, path, FollowSymlinks::No)?.file_type();
+    if filetype.is_symlink() {
+        remove_file_no_xdev(start, path)
+    } else {
+        remove_contents_no_xdev(&open_dir_nofollow_no_xdev(start, path)?)?;
+        remove_dir_no_xdev(start, path)
+    }
+}
+
+/// Like `remove_dir_all`, but fails if resolving the path, or any directory
+/// within it, would cross into a different filesystem than the one `start` is
+/// on.
+#[cfg(windows)]
+pub fn remove_dir_all_no_xdev(start: &fs::File, path: &Path) -> io::Result<()> {
+    // Windows mounts volumes at reparse points, which `remove_dir_all_impl`
+    // removes rather than traversing, so only the path itself needs checking.
+    stat_no_xdev(start, path, FollowSymlinks::No)?;
+    remove_dir_all_impl(start, path)
+}
+
+#[cfg(not(windows))]
+fn remove_contents_no_xdev(dir: &fs::File) -> io::Result<()> {
+    for child in read_base_dir(dir)? {
+        let child = child?;
+        let name = child.file_name();
+        if child.file_type()?.is_dir() {
+            // This fails if the child is a mount point.
+            remove_contents_no_xdev(&open_dir_nofollow_no_xdev(dir, name.as_ref())?)?;
+            remove_dir_unchecked(dir, name.as_ref())?;
+        } else {
+            remove_file_unchecked(dir, name.as_ref())?;
+        }
+    }
+    Ok(())
+}
@@ -1,11 +1,11 @@
 //! This defines `remove_file`, the primary entrypoint to sandboxed file
 //! removal.
 
-use crate::fs::remove_file_impl;
 #[cfg(racy_asserts)]
 use crate::fs::{
     manually, map_result, remove_file_unchecked, stat_unchecked, FollowSymlinks, Metadata,
 };
+use crate::fs::{remove_file_impl, via_parent};
 use std::path::Path;
 use std::{fs, io};
 
@@ -29,6 +29,13 @@ pub fn remove_file(start: &fs::File, path: &Path) -> io::Result<()> {
     result
 }
 
+/// Like `remove_file`, but fails if resolving the path would cross into a
+/// different filesystem than the one `start` is on.
+#[inline]
+pub fn remove_file_no_xdev(start: &fs::File, path: &Path) -> io::Result<()> {
+    via_parent::remove_file_no_xdev(start, path)
+}
+
 #[cfg(racy_asserts)]
 #[allow(clippy::enum_glob_use)]
 fn check_remove_file(
@@ -2,7 +2,7 @@
 
 #[cfg(all(racy_asserts, not(windows)))]
 use crate::fs::append_dir_suffix;
-use crate::fs::rename_impl;
+use crate::fs::{rename_impl, rename_with_impl, via_parent, RenameOptions};
 use std::path::Path;
 use std::{fs, io};
 #[cfg(racy_asserts)]
@@ -56,6 +56,36 @@ pub fn rename(
     result
 }
 
+/// Perform a `renameat2`-like operation, ensuring that the resolution of both
+/// the old and new paths never escape the directory tree rooted at their
+/// respective starts.
+///
+/// This is the same as [`rename`], but with the behavior configured by
+/// `options`.
+#[inline]
+pub fn rename_with(
+    old_start: &fs::File,
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    options: &RenameOptions,
+) -> io::Result<()> {
+    rename_with_impl(old_start, old_path, new_start, new_path, options)
+}
+
+/// Like `rename_with`, but fails if resolving either path would cross into a
+/// different filesystem than the one its start is on.
+#[inline]
+pub fn rename_with_no_xdev(
+    old_start: &fs::File,
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    options: &RenameOptions,
+) -> io::Result<()> {
+    via_parent::rename_with_no_xdev(old_start, old_path, new_start, new_path, options)
+}
+
 #[cfg(racy_asserts)]
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::enum_glob_use)]
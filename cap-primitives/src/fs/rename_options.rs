@@ -0,0 +1,62 @@
+/// Options and flags which can be used to configure how an entry is renamed.
+///
+/// This is to `rename_with` what `OpenOptions` is to `open`.
+#[derive(Debug, Clone)]
+pub struct RenameOptions {
+    pub(crate) noreplace: bool,
+    pub(crate) exchange: bool,
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub(crate) whiteout: bool,
+}
+
+impl RenameOptions {
+    /// Creates a blank new set of options ready for configuration.
+    ///
+    /// With no options set, `rename_with` behaves like `rename`, replacing
+    /// the destination if it already exists.
+    #[allow(clippy::new_without_default)]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            noreplace: false,
+            exchange: false,
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            whiteout: false,
+        }
+    }
+
+    /// Sets the option to fail with [`io::ErrorKind::AlreadyExists`] if the
+    /// destination already exists, rather than replacing it.
+    ///
+    /// This corresponds to `RENAME_NOREPLACE` on Linux.
+    ///
+    /// [`io::ErrorKind::AlreadyExists`]: std::io::ErrorKind::AlreadyExists
+    #[inline]
+    pub fn noreplace(&mut self, noreplace: bool) -> &mut Self {
+        self.noreplace = noreplace;
+        self
+    }
+
+    /// Sets the option to atomically exchange the source and the destination,
+    /// which must both exist.
+    ///
+    /// This corresponds to `RENAME_EXCHANGE` on Linux. It is not supported on
+    /// all platforms.
+    #[inline]
+    pub fn exchange(&mut self, exchange: bool) -> &mut Self {
+        self.exchange = exchange;
+        self
+    }
+
+    /// Sets the option to leave a whiteout object at the source, for use by
+    /// overlay and union filesystems.
+    ///
+    /// This corresponds to `RENAME_WHITEOUT` on Linux, and typically requires
+    /// the `CAP_MKNOD` capability.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[inline]
+    pub fn whiteout(&mut self, whiteout: bool) -> &mut Self {
+        self.whiteout = whiteout;
+        self
+    }
+}
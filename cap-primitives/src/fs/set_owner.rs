@@ -0,0 +1,72 @@
+//! This defines `set_owner` and related functions, for sandboxed ownership
+//! changes.
+
+use crate::fs::{
+    open_dir_nofollow, read_base_dir, set_file_owner_impl, set_owner_impl, stat, FollowSymlinks,
+};
+use std::path::Path;
+use std::{fs, io};
+
+/// Perform a `fchownat`-like operation, ensuring that the resolution of the
+/// path never escapes the directory tree rooted at `start`.
+///
+/// `None` leaves the corresponding ID unchanged. With `FollowSymlinks::No`,
+/// if the path names a symlink, the owner of the symlink itself is changed.
+#[inline]
+pub fn set_owner(
+    start: &fs::File,
+    path: &Path,
+    owner: Option<u32>,
+    group: Option<u32>,
+    follow: FollowSymlinks,
+) -> io::Result<()> {
+    set_owner_impl(start, path, owner, group, follow)
+}
+
+/// Perform a `fchown`-like operation on `file`.
+///
+/// `None` leaves the corresponding ID unchanged.
+#[inline]
+pub fn set_file_owner(file: &fs::File, owner: Option<u32>, group: Option<u32>) -> io::Result<()> {
+    set_file_owner_impl(file, owner, group)
+}
+
+/// Change the owner of the file at `path` and, if it's a directory, of
+/// everything within it, ensuring that the resolution of the path never
+/// escapes the directory tree rooted at `start`.
+///
+/// Symlinks are never followed; the owners of the symlinks themselves are
+/// changed instead.
+pub fn set_owner_recursive(
+    start: &fs::File,
+    path: &Path,
+    owner: Option<u32>,
+    group: Option<u32>,
+) -> io::Result<()> {
+    set_owner(start, path, owner, group, FollowSymlinks::No)?;
+    if stat(start, path, FollowSymlinks::No)?.is_dir() {
+        let dir = open_dir_nofollow(start, path)?;
+        set_owner_recursive_within(&dir, owner, group)?;
+    }
+    Ok(())
+}
+
+fn set_owner_recursive_within(
+    dir: &fs::File,
+    owner: Option<u32>,
+    group: Option<u32>,
+) -> io::Result<()> {
+    for entry in read_base_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        set_owner(dir, name.as_ref(), owner, group, FollowSymlinks::No)?;
+
+        // `open_dir_nofollow` fails if the entry has been replaced by a
+        // symlink since we read it, so we never leave the tree.
+        if entry.file_type()?.is_dir() {
+            let subdir = open_dir_nofollow(dir, name.as_ref())?;
+            set_owner_recursive_within(&subdir, owner, group)?;
+        }
+    }
+    Ok(())
+}
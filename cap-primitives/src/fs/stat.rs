@@ -2,7 +2,7 @@
 
 #[cfg(racy_asserts)]
 use crate::fs::{canonicalize, map_result, stat_unchecked};
-use crate::fs::{stat_impl, FollowSymlinks, Metadata};
+use crate::fs::{stat_impl, stat_no_xdev_impl, FollowSymlinks, Metadata};
 use std::path::Path;
 use std::{fs, io};
 
@@ -23,6 +23,13 @@ pub fn stat(start: &fs::File, path: &Path, follow: FollowSymlinks) -> io::Result
     result
 }
 
+/// Like `stat`, but fails if resolving the path would cross into a different
+/// filesystem than the one `start` is on.
+#[inline]
+pub fn stat_no_xdev(start: &fs::File, path: &Path, follow: FollowSymlinks) -> io::Result<Metadata> {
+    stat_no_xdev_impl(start, path, follow)
+}
+
 #[cfg(racy_asserts)]
 #[allow(clippy::enum_glob_use)]
 fn check_stat(
@@ -0,0 +1,113 @@
+//! This defines the sandboxed `statx` functions.
+
+use crate::fs::{file_statx_impl, statx_impl, FollowSymlinks};
+use crate::time::SystemTime;
+use std::path::Path;
+use std::{fs, io};
+
+/// Perform a `statx`-like operation, ensuring that the resolution of the path
+/// never escapes the directory tree rooted at `start`.
+///
+/// This fetches metadata which [`stat`] doesn't provide. Fields which the
+/// kernel or filesystem don't support are reported as `None`.
+///
+/// [`stat`]: crate::fs::stat
+#[inline]
+pub fn statx(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+) -> io::Result<ExtendedMetadata> {
+    statx_impl(start, path, follow)
+}
+
+/// Perform an `fstatx`-like operation on `file`.
+#[inline]
+pub fn file_statx(file: &fs::File) -> io::Result<ExtendedMetadata> {
+    file_statx_impl(file)
+}
+
+/// Metadata about a file which is only available from `statx`.
+///
+/// Each accessor returns `None` if the kernel or the filesystem doesn't
+/// report the corresponding field.
+#[derive(Debug, Clone)]
+pub struct ExtendedMetadata {
+    pub(crate) created: Option<SystemTime>,
+    pub(crate) mount_id: Option<u64>,
+    pub(crate) dio_mem_align: Option<u32>,
+    pub(crate) dio_offset_align: Option<u32>,
+    pub(crate) immutable: Option<bool>,
+    pub(crate) append_only: Option<bool>,
+    pub(crate) encrypted: Option<bool>,
+    pub(crate) verity: Option<bool>,
+    pub(crate) dax: Option<bool>,
+}
+
+impl ExtendedMetadata {
+    /// Returns the creation time of the file, from `stx_btime`.
+    #[inline]
+    pub fn created(&self) -> Option<SystemTime> {
+        self.created
+    }
+
+    /// Returns the ID of the mount containing the file, from `stx_mnt_id`.
+    ///
+    /// This corresponds to the mount ID in `/proc/self/mountinfo`.
+    #[inline]
+    pub fn mount_id(&self) -> Option<u64> {
+        self.mount_id
+    }
+
+    /// Returns the alignment, in bytes, required for user memory buffers for
+    /// direct I/O on the file, from `stx_dio_mem_align`.
+    ///
+    /// This is `Some(0)` if the file doesn't support direct I/O.
+    #[inline]
+    pub fn dio_mem_align(&self) -> Option<u32> {
+        self.dio_mem_align
+    }
+
+    /// Returns the alignment, in bytes, required for file offsets and I/O
+    /// segment lengths for direct I/O on the file, from
+    /// `stx_dio_offset_align`.
+    ///
+    /// This is `Some(0)` if the file doesn't support direct I/O.
+    #[inline]
+    pub fn dio_offset_align(&self) -> Option<u32> {
+        self.dio_offset_align
+    }
+
+    /// Returns whether the file is immutable (`STATX_ATTR_IMMUTABLE`).
+    #[inline]
+    pub fn is_immutable(&self) -> Option<bool> {
+        self.immutable
+    }
+
+    /// Returns whether the file can only be appended to
+    /// (`STATX_ATTR_APPEND`).
+    #[inline]
+    pub fn is_append_only(&self) -> Option<bool> {
+        self.append_only
+    }
+
+    /// Returns whether the file is encrypted by the filesystem
+    /// (`STATX_ATTR_ENCRYPTED`).
+    #[inline]
+    pub fn is_encrypted(&self) -> Option<bool> {
+        self.encrypted
+    }
+
+    /// Returns whether the file has fs-verity enabled (`STATX_ATTR_VERITY`).
+    #[inline]
+    pub fn is_verity(&self) -> Option<bool> {
+        self.verity
+    }
+
+    /// Returns whether the file is accessed directly rather than through the
+    /// page cache (`STATX_ATTR_DAX`).
+    #[inline]
+    pub fn is_dax(&self) -> Option<bool> {
+        self.dax
+    }
+}
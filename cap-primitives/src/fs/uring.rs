@@ -0,0 +1,147 @@
+//! This defines `Uring`, for sandboxed opens and file I/O using io_uring.
+
+use crate::fs::{OpenOptions, UringInner};
+use io_lifetimes::{AsFd, AsFilelike, BorrowedFd};
+use std::path::Path;
+use std::{fmt, fs, io};
+
+/// An io_uring instance for performing sandboxed opens and file I/O.
+///
+/// Opens are submitted as `IORING_OP_OPENAT2` with `RESOLVE_BENEATH`, so
+/// they never escape the `start` directory, just as with [`open`]. Many opens
+/// can be submitted at once with [`Uring::open_many`]. On kernels without
+/// `IORING_OP_OPENAT2`, opens fall back to [`open`].
+///
+/// Each method waits for its operations to complete before returning.
+///
+/// [`open`]: crate::fs::open
+pub struct Uring {
+    inner: UringInner,
+}
+
+impl Uring {
+    /// Create a new io_uring instance with room for `entries` operations in
+    /// flight at once.
+    ///
+    /// This fails if io_uring is unavailable, such as on older kernels or
+    /// when disabled by a `seccomp` policy.
+    #[inline]
+    pub fn new(entries: u32) -> io::Result<Self> {
+        Ok(Self {
+            inner: UringInner::new(entries)?,
+        })
+    }
+
+    /// Perform an `openat`-like operation, ensuring that the resolution of
+    /// the path never escapes the directory tree rooted at `start`.
+    #[inline]
+    pub fn open(
+        &mut self,
+        start: &fs::File,
+        path: &Path,
+        options: &OpenOptions,
+    ) -> io::Result<fs::File> {
+        self.open_many(start, &[path], options).pop().unwrap()
+    }
+
+    /// Open each of `paths` with the same `options`, submitting the opens
+    /// together, ensuring that the resolution of the paths never escapes the
+    /// directory tree rooted at `start`.
+    ///
+    /// The results are in the same order as `paths`.
+    #[inline]
+    pub fn open_many(
+        &mut self,
+        start: &fs::File,
+        paths: &[&Path],
+        options: &OpenOptions,
+    ) -> Vec<io::Result<fs::File>> {
+        self.inner.open_many(start, paths, options)
+    }
+
+    /// Read the entire contents of a file, ensuring that the resolution of
+    /// the path never escapes the directory tree rooted at `start`.
+    pub fn read(&mut self, start: &fs::File, path: &Path) -> io::Result<Vec<u8>> {
+        let file = self.open(start, path, OpenOptions::new().read(true))?;
+        let size = file.metadata().map(|m| m.len() as usize).unwrap_or(0);
+
+        // Read into a buffer one byte larger than the file, so that the end
+        // is usually found without growing it.
+        let mut contents = vec![0; size + 1];
+        let mut len = 0;
+        loop {
+            if len == contents.len() {
+                contents.resize(len * 2, 0);
+            }
+            match self.read_at(&file, &mut contents[len..], len as u64)? {
+                0 => break,
+                n => len += n,
+            }
+        }
+        contents.truncate(len);
+        Ok(contents)
+    }
+
+    /// Write a slice as the entire contents of a file, ensuring that the
+    /// resolution of the path never escapes the directory tree rooted at
+    /// `start`.
+    pub fn write(&mut self, start: &fs::File, path: &Path, contents: &[u8]) -> io::Result<()> {
+        let file = self.open(
+            start,
+            path,
+            OpenOptions::new().write(true).create(true).truncate(true),
+        )?;
+        let mut written = 0;
+        while written < contents.len() {
+            match self.write_at(&file, &contents[written..], written as u64)? {
+                0 => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    ))
+                }
+                n => written += n,
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a number of bytes starting from a given offset.
+    ///
+    /// This is similar to [`std::os::unix::fs::FileExt::read_at`].
+    #[inline]
+    pub fn read_at<Filelike: AsFilelike>(
+        &mut self,
+        file: &Filelike,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> io::Result<usize> {
+        self.inner.read_at(file.as_filelike(), buf, offset)
+    }
+
+    /// Writes a number of bytes starting from a given offset.
+    ///
+    /// This is similar to [`std::os::unix::fs::FileExt::write_at`].
+    #[inline]
+    pub fn write_at<Filelike: AsFilelike>(
+        &mut self,
+        file: &Filelike,
+        buf: &[u8],
+        offset: u64,
+    ) -> io::Result<usize> {
+        self.inner.write_at(file.as_filelike(), buf, offset)
+    }
+}
+
+impl AsFd for Uring {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+impl fmt::Debug for Uring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
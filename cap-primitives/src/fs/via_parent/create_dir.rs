@@ -1,4 +1,4 @@
-use super::open_parent;
+use super::{open_parent, open_parent_no_xdev};
 use crate::fs::{create_dir_unchecked, strip_dir_suffix, DirOptions, MaybeOwnedFile};
 use std::path::Path;
 use std::{fs, io};
@@ -17,3 +17,20 @@ pub(crate) fn create_dir(start: &fs::File, path: &Path, options: &DirOptions) ->
 
     create_dir_unchecked(&dir, basename.as_ref(), options)
 }
+
+/// Like `create_dir`, but fail if resolving the parent would cross into a
+/// different filesystem than the one `start` is on.
+pub(crate) fn create_dir_no_xdev(
+    start: &fs::File,
+    path: &Path,
+    options: &DirOptions,
+) -> io::Result<()> {
+    let start = MaybeOwnedFile::borrowed(start);
+
+    // As in `create_dir`, strip any trailing slashes.
+    let path = strip_dir_suffix(path);
+
+    let (dir, basename) = open_parent_no_xdev(start, &path)?;
+
+    create_dir_unchecked(&dir, basename.as_ref(), options)
+}
@@ -0,0 +1,30 @@
+use super::open_parent;
+use crate::fs::{create_fifo_unchecked, mknod_unchecked, MaybeOwnedFile, NodeKind};
+use std::path::Path;
+use std::{fs, io};
+
+/// Implement `mknod` by `open`ing up the parent component of the path and
+/// then calling `mknod_unchecked` on the last component.
+pub(crate) fn mknod(
+    start: &fs::File,
+    path: &Path,
+    kind: NodeKind,
+    mode: u32,
+    dev: u64,
+) -> io::Result<()> {
+    let start = MaybeOwnedFile::borrowed(start);
+
+    let (dir, basename) = open_parent(start, path)?;
+
+    mknod_unchecked(&dir, basename.as_ref(), kind, mode, dev)
+}
+
+/// Implement `create_fifo` by `open`ing up the parent component of the path
+/// and then calling `create_fifo_unchecked` on the last component.
+pub(crate) fn create_fifo(start: &fs::File, path: &Path, mode: u32) -> io::Result<()> {
+    let start = MaybeOwnedFile::borrowed(start);
+
+    let (dir, basename) = open_parent(start, path)?;
+
+    create_fifo_unchecked(&dir, basename.as_ref(), mode)
+}
@@ -5,6 +5,8 @@
 mod access;
 mod create_dir;
 mod hard_link;
+#[cfg(not(any(windows, target_os = "wasi")))]
+mod mknod;
 mod open_parent;
 #[cfg(not(windows))] // doesn't work on windows; use a windows-specific impl
 mod read_link;
@@ -13,24 +15,30 @@ mod remove_file;
 mod rename;
 #[cfg(windows)]
 mod set_permissions;
+#[cfg(not(any(windows, target_os = "wasi")))]
+mod set_symlink_owner;
 #[cfg(not(target_os = "wasi"))]
 mod set_symlink_permissions;
 #[cfg(not(windows))]
 mod set_times_nofollow;
 mod symlink;
 
-use open_parent::open_parent;
+use open_parent::{open_parent, open_parent_no_xdev};
 
 pub(crate) use access::access;
-pub(crate) use create_dir::create_dir;
+pub(crate) use create_dir::{create_dir, create_dir_no_xdev};
 pub(crate) use hard_link::hard_link;
+#[cfg(not(any(windows, target_os = "wasi")))]
+pub(crate) use mknod::{create_fifo, mknod};
 #[cfg(not(windows))] // doesn't work on windows; use a windows-specific impl
 pub(crate) use read_link::read_link;
-pub(crate) use remove_dir::remove_dir;
-pub(crate) use remove_file::remove_file;
-pub(crate) use rename::rename;
+pub(crate) use remove_dir::{remove_dir, remove_dir_no_xdev};
+pub(crate) use remove_file::{remove_file, remove_file_no_xdev};
+pub(crate) use rename::{rename, rename_with, rename_with_no_xdev};
 #[cfg(windows)]
 pub(crate) use set_permissions::set_permissions;
+#[cfg(not(any(windows, target_os = "wasi")))]
+pub(crate) use set_symlink_owner::set_symlink_owner;
 #[cfg(not(target_os = "wasi"))]
 pub(crate) use set_symlink_permissions::set_symlink_permissions;
 #[cfg(not(windows))]
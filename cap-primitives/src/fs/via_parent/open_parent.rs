@@ -2,10 +2,10 @@
 /// It opens the parent directory of the given path, and returns the basename,
 /// so that all the `via_parent` functions need to do is make sure they
 /// don't follow symlinks in the basename.
-use crate::fs::{errors, open_dir, path_requires_dir, MaybeOwnedFile};
+use crate::fs::{errors, open_dir, open_dir_no_xdev, path_requires_dir, MaybeOwnedFile};
 use std::ffi::OsStr;
-use std::io;
 use std::path::{Component, Path};
+use std::{fs, io};
 
 /// Open the "parent" of `path`, relative to `start`. The return value on
 /// success is a tuple of the newly opened directory and an `OsStr` referencing
@@ -15,6 +15,26 @@ use std::path::{Component, Path};
 pub(super) fn open_parent<'path, 'borrow>(
     start: MaybeOwnedFile<'borrow>,
     path: &'path Path,
+) -> io::Result<(MaybeOwnedFile<'borrow>, &'path OsStr)> {
+    open_parent_with(start, path, open_dir)
+}
+
+/// Like `open_parent`, but fail if resolving the parent would cross into a
+/// different filesystem than the one `start` is on.
+///
+/// The last component isn't checked, however callers don't follow it, and
+/// the OS refuses to remove or rename a mount point.
+pub(super) fn open_parent_no_xdev<'path, 'borrow>(
+    start: MaybeOwnedFile<'borrow>,
+    path: &'path Path,
+) -> io::Result<(MaybeOwnedFile<'borrow>, &'path OsStr)> {
+    open_parent_with(start, path, open_dir_no_xdev)
+}
+
+fn open_parent_with<'path, 'borrow>(
+    start: MaybeOwnedFile<'borrow>,
+    path: &'path Path,
+    open_dir: fn(&fs::File, &Path) -> io::Result<fs::File>,
 ) -> io::Result<(MaybeOwnedFile<'borrow>, &'path OsStr)> {
     let (dirname, basename) = split_parent(path).ok_or_else(errors::no_such_file_or_directory)?;
 
@@ -1,4 +1,4 @@
-use super::open_parent;
+use super::{open_parent, open_parent_no_xdev};
 use crate::fs::{remove_dir_unchecked, MaybeOwnedFile};
 use std::path::Path;
 use std::{fs, io};
@@ -12,3 +12,13 @@ pub(crate) fn remove_dir(start: &fs::File, path: &Path) -> io::Result<()> {
 
     remove_dir_unchecked(&dir, basename.as_ref())
 }
+
+/// Like `remove_dir`, but fail if resolving the parent would cross into a different
+/// filesystem than the one `start` is on.
+pub(crate) fn remove_dir_no_xdev(start: &fs::File, path: &Path) -> io::Result<()> {
+    let start = MaybeOwnedFile::borrowed(start);
+
+    let (dir, basename) = open_parent_no_xdev(start, path)?;
+
+    remove_dir_unchecked(&dir, basename.as_ref())
+}
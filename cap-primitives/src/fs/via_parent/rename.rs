@@ -1,18 +1,68 @@
-use super::open_parent;
+use super::{open_parent, open_parent_no_xdev};
 #[cfg(unix)]
 use crate::fs::{append_dir_suffix, path_has_trailing_slash};
-use crate::fs::{rename_unchecked, strip_dir_suffix, MaybeOwnedFile};
+use crate::fs::{rename_with_unchecked, strip_dir_suffix, MaybeOwnedFile, RenameOptions};
 use std::path::Path;
 use std::{fs, io};
 
 /// Implement `rename` by `open`ing up the parent component of the path and
 /// then calling `rename_unchecked` on the last component.
+#[inline]
 pub(crate) fn rename(
     old_start: &fs::File,
     old_path: &Path,
     new_start: &fs::File,
     new_path: &Path,
 ) -> io::Result<()> {
+    rename_with(
+        old_start,
+        old_path,
+        new_start,
+        new_path,
+        &RenameOptions::new(),
+    )
+}
+
+/// Implement `rename_with` by `open`ing up the parent component of the path
+/// and then calling `rename_with_unchecked` on the last component.
+#[inline]
+pub(crate) fn rename_with(
+    old_start: &fs::File,
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    options: &RenameOptions,
+) -> io::Result<()> {
+    rename_via_parents(old_start, old_path, new_start, new_path, options, false)
+}
+
+/// Like `rename_with`, but fail if resolving either parent would cross into a
+/// different filesystem than the one its start is on.
+#[inline]
+pub(crate) fn rename_with_no_xdev(
+    old_start: &fs::File,
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    options: &RenameOptions,
+) -> io::Result<()> {
+    rename_via_parents(old_start, old_path, new_start, new_path, options, true)
+}
+
+fn rename_via_parents(
+    old_start: &fs::File,
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    options: &RenameOptions,
+    no_xdev: bool,
+) -> io::Result<()> {
+    let open_parent = if no_xdev {
+        open_parent_no_xdev
+    } else {
+        open_parent
+    };
+
     let old_start = MaybeOwnedFile::borrowed(old_start);
     let new_start = MaybeOwnedFile::borrowed(new_start);
 
@@ -41,10 +91,11 @@ pub(crate) fn rename(
         old_basename
     };
 
-    rename_unchecked(
+    rename_with_unchecked(
         &old_dir,
         old_basename.as_ref(),
         &new_dir,
         new_basename.as_ref(),
+        options,
     )
 }
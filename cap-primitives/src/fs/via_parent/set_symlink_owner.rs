@@ -0,0 +1,21 @@
+use super::open_parent;
+use crate::fs::{set_symlink_owner_unchecked, MaybeOwnedFile};
+use std::path::Path;
+use std::{fs, io};
+
+/// Implement `set_owner` with `FollowSymlinks::No` by `open`ing up the parent
+/// component of the path and then calling `set_symlink_owner_unchecked` on
+/// the last component.
+#[inline]
+pub(crate) fn set_symlink_owner(
+    start: &fs::File,
+    path: &Path,
+    owner: Option<u32>,
+    group: Option<u32>,
+) -> io::Result<()> {
+    let start = MaybeOwnedFile::borrowed(start);
+
+    let (dir, basename) = open_parent(start, path)?;
+
+    set_symlink_owner_unchecked(&dir, basename.as_ref(), owner, group)
+}
@@ -0,0 +1,72 @@
+//! This defines the sandboxed directory watcher.
+
+use crate::fs::WatcherInner;
+use io_lifetimes::{AsFd, BorrowedFd};
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+/// Start watching the directory at `path` for changes, ensuring that the
+/// resolution of the path never escapes the directory tree rooted at `start`.
+///
+/// Only changes to the entries of the directory itself are reported, not
+/// changes within its subdirectories.
+#[inline]
+pub fn watch(start: &fs::File, path: &Path) -> io::Result<Watcher> {
+    Ok(Watcher {
+        inner: WatcherInner::new(start, path)?,
+    })
+}
+
+/// A change to an entry in a watched directory.
+///
+/// Paths are relative to the `start` directory passed to [`watch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// An entry was created, or renamed into the directory.
+    Created(PathBuf),
+    /// A file's contents or metadata were modified.
+    Modified(PathBuf),
+    /// An entry was removed, or renamed out of the directory.
+    Removed(PathBuf),
+    /// An entry was renamed within the directory.
+    Renamed {
+        /// The old path of the entry.
+        from: PathBuf,
+        /// The new path of the entry.
+        to: PathBuf,
+    },
+    /// Events were lost because too many were queued; the directory should
+    /// be rescanned.
+    Overflow,
+}
+
+/// Iterator over the changes to a watched directory.
+///
+/// Iterating blocks until the next change is available. The iterator ends
+/// once the watched directory itself is removed or renamed, since its entries
+/// may no longer be within the `start` directory after that.
+pub struct Watcher {
+    inner: WatcherInner,
+}
+
+impl Iterator for Watcher {
+    type Item = io::Result<WatchEvent>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_event()
+    }
+}
+
+impl AsFd for Watcher {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+impl fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
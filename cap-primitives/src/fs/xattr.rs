@@ -0,0 +1,85 @@
+//! This defines the sandboxed extended attribute functions.
+
+use crate::fs::{
+    get_file_xattr_impl, get_xattr_impl, list_file_xattrs_impl, list_xattrs_impl,
+    remove_file_xattr_impl, remove_xattr_impl, set_file_xattr_impl, set_xattr_impl, FollowSymlinks,
+};
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+use std::{fs, io};
+
+/// Perform a `getxattr`-like operation, ensuring that the resolution of the
+/// path never escapes the directory tree rooted at `start`.
+///
+/// Returns `None` if the file has no attribute named `name`.
+#[inline]
+pub fn get_xattr(
+    start: &fs::File,
+    path: &Path,
+    name: &OsStr,
+    follow: FollowSymlinks,
+) -> io::Result<Option<Vec<u8>>> {
+    get_xattr_impl(start, path, name, follow)
+}
+
+/// Perform a `setxattr`-like operation, ensuring that the resolution of the
+/// path never escapes the directory tree rooted at `start`.
+#[inline]
+pub fn set_xattr(
+    start: &fs::File,
+    path: &Path,
+    name: &OsStr,
+    value: &[u8],
+    follow: FollowSymlinks,
+) -> io::Result<()> {
+    set_xattr_impl(start, path, name, value, follow)
+}
+
+/// Perform a `listxattr`-like operation, ensuring that the resolution of the
+/// path never escapes the directory tree rooted at `start`.
+#[inline]
+pub fn list_xattrs(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+) -> io::Result<Vec<OsString>> {
+    list_xattrs_impl(start, path, follow)
+}
+
+/// Perform a `removexattr`-like operation, ensuring that the resolution of
+/// the path never escapes the directory tree rooted at `start`.
+#[inline]
+pub fn remove_xattr(
+    start: &fs::File,
+    path: &Path,
+    name: &OsStr,
+    follow: FollowSymlinks,
+) -> io::Result<()> {
+    remove_xattr_impl(start, path, name, follow)
+}
+
+/// Perform an `fgetxattr`-like operation on `file`.
+///
+/// Returns `None` if the file has no attribute named `name`.
+#[inline]
+pub fn get_file_xattr(file: &fs::File, name: &OsStr) -> io::Result<Option<Vec<u8>>> {
+    get_file_xattr_impl(file, name)
+}
+
+/// Perform an `fsetxattr`-like operation on `file`.
+#[inline]
+pub fn set_file_xattr(file: &fs::File, name: &OsStr, value: &[u8]) -> io::Result<()> {
+    set_file_xattr_impl(file, name, value)
+}
+
+/// Perform an `flistxattr`-like operation on `file`.
+#[inline]
+pub fn list_file_xattrs(file: &fs::File) -> io::Result<Vec<OsString>> {
+    list_file_xattrs_impl(file)
+}
+
+/// Perform an `fremovexattr`-like operation on `file`.
+#[inline]
+pub fn remove_file_xattr(file: &fs::File, name: &OsStr) -> io::Result<()> {
+    remove_file_xattr_impl(file, name)
+}
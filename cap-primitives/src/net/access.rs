@@ -0,0 +1,131 @@
+use std::ops::{BitAnd, BitOr};
+
+/// A set of network operations, which a [`Rule`] applies to.
+///
+/// Binding covers `bind_tcp_listener` and `bind_udp_socket`. Connecting
+/// covers `connect_tcp_stream`, `connect_udp_socket`, and sending UDP
+/// datagrams with `send_to_udp_socket_addr`.
+///
+/// [`Rule`]: crate::net::Rule
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Access(u8);
+
+impl Access {
+    /// Binding TCP listeners.
+    pub const TCP_BIND: Self = Self(0b0001);
+    /// Connecting TCP streams.
+    pub const TCP_CONNECT: Self = Self(0b0010);
+    /// Binding UDP sockets.
+    pub const UDP_BIND: Self = Self(0b0100);
+    /// Connecting UDP sockets, and sending datagrams to addresses.
+    pub const UDP_CONNECT: Self = Self(0b1000);
+
+    /// All TCP operations.
+    pub const TCP: Self = Self::TCP_BIND.union(Self::TCP_CONNECT);
+    /// All UDP operations.
+    pub const UDP: Self = Self::UDP_BIND.union(Self::UDP_CONNECT);
+    /// Binding, with either protocol.
+    pub const BIND: Self = Self::TCP_BIND.union(Self::UDP_BIND);
+    /// Connecting, with either protocol.
+    pub const CONNECT: Self = Self::TCP_CONNECT.union(Self::UDP_CONNECT);
+    /// All operations.
+    pub const ALL: Self = Self::TCP.union(Self::UDP);
+
+    /// The empty set of operations.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns `true` if this set contains no operations.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if this set contains all the operations in `other`.
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the operations in either set.
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Returns the operations in both sets.
+    #[inline]
+    pub const fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// Iterate over the individual operations in this set.
+    pub(crate) fn operations(self) -> impl Iterator<Item = Self> {
+        [
+            Self::TCP_BIND,
+            Self::TCP_CONNECT,
+            Self::UDP_BIND,
+            Self::UDP_CONNECT,
+        ]
+        .into_iter()
+        .filter(move |op| self.contains(*op))
+    }
+
+    /// Split this set into sets which each have the form "these protocols
+    /// with these operations", so that each can be described by a protocol
+    /// and an operation in the textual rule format.
+    pub(crate) fn split(self) -> impl Iterator<Item = Self> {
+        let tcp = self.intersection(Self::TCP);
+        let udp = self.intersection(Self::UDP);
+        let (first, second) = if tcp.0 == udp.0 >> 2 {
+            (self, Self::empty())
+        } else {
+            (tcp, udp)
+        };
+        [first, second].into_iter().filter(|set| !set.is_empty())
+    }
+
+    /// The protocol word for this set in the textual rule format, if it's
+    /// limited to one protocol.
+    pub(crate) fn protocol_name(self) -> Option<&'static str> {
+        if Self::TCP.contains(self) {
+            Some("tcp")
+        } else if Self::UDP.contains(self) {
+            Some("udp")
+        } else {
+            None
+        }
+    }
+
+    /// The operation word for this set in the textual rule format, if it's
+    /// limited to one operation.
+    pub(crate) fn operation_name(self) -> Option<&'static str> {
+        if Self::BIND.contains(self) {
+            Some("bind")
+        } else if Self::CONNECT.contains(self) {
+            Some("connect")
+        } else {
+            None
+        }
+    }
+}
+
+impl BitOr for Access {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd for Access {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self {
+        self.intersection(rhs)
+    }
+}
@@ -1,5 +1,9 @@
 //! Networking utilities.
 
+mod access;
 mod pool;
+mod resolver;
 
+pub use access::Access;
 pub use pool::*;
+pub use resolver::*;
@@ -1,42 +1,117 @@
 #[cfg(test)]
 use crate::ambient_authority;
 use crate::net::pool::net::ToSocketAddrs;
+use crate::net::resolver::normalize_host;
+use crate::net::{Access, Resolver};
 use crate::AmbientAuthority;
-use ipnet::IpNet;
-#[cfg(test)]
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
 use std::str::FromStr;
-use std::{io, net};
+use std::{fmt, io, net};
 
-// TODO: Perhaps we should have our own version of `ToSocketAddrs` which
-// returns hostnames rather than parsing them, so we can add unresolved
-// hostnames to the pool.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 enum AddrSet {
     Net(IpNet),
+    /// A host name, normalized with `normalize_host`. This matches by name,
+    /// and never matches a bare address.
+    Host(String),
 }
 
 impl AddrSet {
     fn contains(&self, addr: net::IpAddr) -> bool {
         match self {
             Self::Net(ip_net) => ip_net.contains(&addr),
+            Self::Host(_) => false,
+        }
+    }
+
+    fn contains_host(&self, host: &str) -> bool {
+        match self {
+            Self::Net(_) => false,
+            Self::Host(name) => name == host,
+        }
+    }
+
+    /// Returns the set of addresses or host names in both `self` and
+    /// `other`, if there are any. CIDR ranges are either nested or disjoint,
+    /// so this is always one of the two, or nothing.
+    fn intersect(&self, other: &Self) -> Option<Self> {
+        match (self, other) {
+            (Self::Net(a), Self::Net(b)) if a.contains(b) => Some(Self::Net(*b)),
+            (Self::Net(a), Self::Net(b)) if b.contains(a) => Some(Self::Net(*a)),
+            (Self::Host(a), Self::Host(b)) if a == b => Some(Self::Host(a.clone())),
+            _ => None,
         }
     }
 }
 
-#[derive(Clone)]
-struct IpGrant {
+/// A rule in a [`Pool`], which allows or denies a set of operations on a set
+/// of addresses or a host name, with a range of ports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    deny: bool,
+    access: Access,
     set: AddrSet,
     ports_start: u16,
     ports_end: Option<u16>,
 }
 
-impl IpGrant {
-    fn contains(&self, addr: &net::SocketAddr) -> bool {
-        if !self.set.contains(addr.ip()) {
-            return false;
+impl Rule {
+    /// Returns `true` if this is a deny rule, and `false` if it's an allow
+    /// rule.
+    #[inline]
+    pub fn is_deny(&self) -> bool {
+        self.deny
+    }
+
+    /// Returns the operations this rule applies to.
+    #[inline]
+    pub fn access(&self) -> Access {
+        self.access
+    }
+
+    /// Returns the range of network addresses this rule applies to, if it
+    /// applies to addresses.
+    #[inline]
+    pub fn ip_net(&self) -> Option<IpNet> {
+        match &self.set {
+            AddrSet::Net(ip_net) => Some(*ip_net),
+            AddrSet::Host(_) => None,
+        }
+    }
+
+    /// Returns the host name this rule applies to, if it applies to a host
+    /// name.
+    #[inline]
+    pub fn host(&self) -> Option<&str> {
+        match &self.set {
+            AddrSet::Net(_) => None,
+            AddrSet::Host(host) => Some(host),
         }
+    }
+
+    /// Returns the first port this rule applies to.
+    #[inline]
+    pub fn ports_start(&self) -> u16 {
+        self.ports_start
+    }
+
+    /// Returns the port which ends the range of ports this rule applies to,
+    /// or `None` if the range extends to the highest port.
+    #[inline]
+    pub fn ports_end(&self) -> Option<u16> {
+        self.ports_end
+    }
+
+    fn contains(&self, addr: &net::SocketAddr) -> bool {
+        self.set.contains(addr.ip()) && self.contains_port(addr.port())
+    }
 
-        let port = addr.port();
+    /// `host` must already be normalized with `normalize_host`.
+    fn contains_host(&self, host: &str, port: u16) -> bool {
+        self.set.contains_host(host) && self.contains_port(port)
+    }
+
+    fn contains_port(&self, port: u16) -> bool {
         if port < self.ports_start {
             return false;
         }
@@ -50,10 +125,229 @@ impl IpGrant {
     }
 }
 
+/// Rules are printed and parsed in a small textual form:
+///
+/// ```text
+/// allow tcp connect 10.0.0.0/8:443
+/// deny 10.0.5.0/24:*
+/// allow udp bind [::1]:5000-5010
+/// allow connect api.internal:443
+/// ```
+///
+/// Each rule is `allow` or `deny`, optionally followed by `tcp` or `udp`,
+/// optionally followed by `bind` or `connect`, and then an address, a range
+/// of addresses in CIDR notation, or a host name, and then a port, an
+/// inclusive range of ports, or `*` for any port. IPv6 addresses are enclosed
+/// in brackets. Omitting the protocol or the operation makes the rule apply
+/// to all of them.
+///
+/// A rule with an empty range of ports, which matches nothing, is printed
+/// with `none` in place of the port, which is rejected when parsing, so that
+/// it can't be read back as a rule which matches something.
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.deny { "deny " } else { "allow " })?;
+        if let Some(protocol) = self.access.protocol_name() {
+            write!(f, "{} ", protocol)?;
+        }
+        if let Some(operation) = self.access.operation_name() {
+            write!(f, "{} ", operation)?;
+        }
+        match &self.set {
+            AddrSet::Net(IpNet::V4(net)) if net.prefix_len() == 32 => write!(f, "{}", net.addr())?,
+            AddrSet::Net(IpNet::V4(net)) => write!(f, "{}", net)?,
+            AddrSet::Net(IpNet::V6(net)) if net.prefix_len() == 128 => {
+                write!(f, "[{}]", net.addr())?
+            }
+            AddrSet::Net(IpNet::V6(net)) => write!(f, "[{}]", net)?,
+            AddrSet::Host(host) => f.write_str(host)?,
+        }
+        let last = match self.ports_end {
+            Some(ports_end) if ports_end <= self.ports_start => return f.write_str(":none"),
+            Some(ports_end) => ports_end - 1,
+            None => u16::MAX,
+        };
+        if self.ports_start == 0 && self.ports_end.is_none() {
+            f.write_str(":*")
+        } else if self.ports_start == last {
+            write!(f, ":{}", self.ports_start)
+        } else {
+            write!(f, ":{}-{}", self.ports_start, last)
+        }
+    }
+}
+
+/// Parse a rule in the form printed by its `Display` implementation.
+impl FromStr for Rule {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        let mut words = s.split_whitespace();
+        let deny = match words.next() {
+            Some("allow") => false,
+            Some("deny") => true,
+            _ => return Err(invalid_rule("expected `allow` or `deny`")),
+        };
+        let mut word = words.next();
+        let protocols = match word {
+            Some("tcp") => Access::TCP,
+            Some("udp") => Access::UDP,
+            _ => Access::ALL,
+        };
+        if protocols != Access::ALL {
+            word = words.next();
+        }
+        let operations = match word {
+            Some("bind") => Access::BIND,
+            Some("connect") => Access::CONNECT,
+            _ => Access::ALL,
+        };
+        if operations != Access::ALL {
+            word = words.next();
+        }
+        let target = word.ok_or_else(|| invalid_rule("expected an address or host name"))?;
+        if words.next().is_some() {
+            return Err(invalid_rule("unexpected text after the port"));
+        }
+
+        let (set, ports) = parse_target(target)?;
+        let (ports_start, ports_end) = parse_ports(ports)?;
+        Ok(Self {
+            deny,
+            access: protocols & operations,
+            set,
+            ports_start,
+            ports_end,
+        })
+    }
+}
+
+/// Parse an address, range of addresses, or host name, followed by `:` and
+/// a port specification, which is returned unparsed.
+fn parse_target(s: &str) -> io::Result<(AddrSet, &str)> {
+    if let Some(rest) = s.strip_prefix('[') {
+        let (net, ports) = rest
+            .split_once("]:")
+            .ok_or_else(|| invalid_rule("expected `]:` and a port after an IPv6 address"))?;
+        return match parse_ip_net(net) {
+            Some(net @ IpNet::V6(_)) => Ok((AddrSet::Net(net), ports)),
+            _ => Err(invalid_rule("invalid IPv6 address")),
+        };
+    }
+
+    let (target, ports) = s
+        .rsplit_once(':')
+        .ok_or_else(|| invalid_rule("expected `:` and a port"))?;
+    if let Some(net) = parse_ip_net(target) {
+        if let IpNet::V4(_) = net {
+            return Ok((AddrSet::Net(net), ports));
+        }
+    }
+    let set = host_set(target).map_err(|_| invalid_rule("invalid address or host name"))?;
+    Ok((set, ports))
+}
+
+/// Normalize `host` with `normalize_host`, and check that it's a name which
+/// prints as a rule that parses back to the same name: it must be non-empty,
+/// contain only ASCII letters, digits, `-`, `.`, and `_`, and not be an IP
+/// address or range of addresses.
+fn host_set(host: &str) -> io::Result<AddrSet> {
+    let name = normalize_host(host);
+    if name.is_empty()
+        || !name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'.' || b == b'_')
+        || parse_ip_net(&name).is_some()
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid host name",
+        ));
+    }
+    Ok(AddrSet::Host(name))
+}
+
+fn parse_ip_net(s: &str) -> Option<IpNet> {
+    IpNet::from_str(s)
+        .ok()
+        .or_else(|| net::IpAddr::from_str(s).ok().map(IpNet::from))
+}
+
+fn parse_ports(s: &str) -> io::Result<(u16, Option<u16>)> {
+    let parse_port = |s: &str| u16::from_str(s).map_err(|_| invalid_rule("invalid port"));
+    if s == "*" {
+        return Ok((0, None));
+    }
+    match s.split_once('-') {
+        Some((start, last)) => {
+            let (start, last) = (parse_port(start)?, parse_port(last)?);
+            if last < start {
+                return Err(invalid_rule("empty port range"));
+            }
+            Ok((start, last.checked_add(1)))
+        }
+        None => {
+            let port = parse_port(s)?;
+            Ok((port, port.checked_add(1)))
+        }
+    }
+}
+
+#[cold]
+fn invalid_rule(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("invalid pool rule: {}", message),
+    )
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rule {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rule {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The outcome of checking an address or host name against a [`Pool`], with
+/// the rule which decided it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The address or host name is allowed by this rule.
+    Allowed(Rule),
+    /// The address or host name is denied by this rule.
+    Denied(Rule),
+    /// No rule applies to the address or host name, so it isn't allowed.
+    NotGranted,
+}
+
+impl Verdict {
+    /// Returns `true` if the address or host name is allowed.
+    #[inline]
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allowed(_))
+    }
+}
+
 /// A representation of a set of network resources that may be accessed.
 ///
-/// This is presently a very simple concept, though it could grow in
-/// sophistication in the future.
+/// A `Pool` holds allow rules, added with the `insert_*` methods, and deny
+/// rules, added with the `deny_*` methods. Deny rules take precedence: an
+/// address is allowed if no deny rule applies to it and at least one allow
+/// rule does, regardless of the order the rules were added in. Use
+/// [`Pool::explain_addr`] and [`Pool::explain_host`] to find which rule
+/// decided the outcome.
+///
+/// Each rule applies to a set of operations, such as binding TCP listeners or
+/// connecting TCP streams, described by an [`Access`]. Methods which don't
+/// take an `Access` add rules which apply to all operations.
 ///
 /// `Pool` implements `Clone`, which creates new independent entities that
 /// carry the full authority of the originals. This means that in a borrow
@@ -68,13 +362,66 @@ impl IpGrant {
 #[derive(Clone, Default)]
 pub struct Pool {
     // TODO: when compiling for WASI, use WASI-specific handle instead
-    grants: Vec<IpGrant>,
+    rules: Vec<Rule>,
 }
 
 impl Pool {
     /// Construct a new empty pool.
     pub fn new() -> Self {
-        Self { grants: Vec::new() }
+        Self { rules: Vec::new() }
+    }
+
+    /// Construct a new pool containing `rules`.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any IP address or host name.
+    pub fn from_rules<I: IntoIterator<Item = Rule>>(
+        rules: I,
+        ambient_authority: AmbientAuthority,
+    ) -> Self {
+        let _ = ambient_authority;
+
+        Self {
+            rules: rules.into_iter().collect(),
+        }
+    }
+
+    /// Parse a pool from its textual form, which has one [`Rule`] per line.
+    /// Empty lines, and everything after a `#` on a line, are ignored.
+    ///
+    /// The `Display` implementation for `Pool` prints this form.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any IP address or host name.
+    pub fn parse(text: &str, ambient_authority: AmbientAuthority) -> io::Result<Self> {
+        let mut rules = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = match line.split_once('#') {
+                Some((line, _comment)) => line,
+                None => line,
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let rule = Rule::from_str(line).map_err(|err| {
+                io::Error::new(err.kind(), format!("line {}: {}", index + 1, err))
+            })?;
+            rules.push(rule);
+        }
+        Ok(Self::from_rules(rules, ambient_authority))
+    }
+
+    /// Add a rule to the pool.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any IP address or host name.
+    pub fn insert_rule(&mut self, rule: Rule, ambient_authority: AmbientAuthority) {
+        let _ = ambient_authority;
+
+        self.rules.push(rule)
     }
 
     /// Add addresses to the pool.
@@ -135,13 +482,35 @@ impl Pool {
         ports_end: Option<u16>,
         ambient_authority: AmbientAuthority,
     ) {
-        let _ = ambient_authority;
-
-        self.grants.push(IpGrant {
-            set: AddrSet::Net(ip_net),
+        self.insert_ip_net_port_range_access(
+            ip_net,
             ports_start,
             ports_end,
-        })
+            Access::ALL,
+            ambient_authority,
+        )
+    }
+
+    /// Add a range of network addresses, accepting a range of ports, to the
+    /// pool, for only the operations in `access`.
+    ///
+    /// For example, `Access::TCP_CONNECT` grants the ability to connect TCP
+    /// streams to the addresses, but not to bind listeners to them.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any IP address.
+    pub fn insert_ip_net_port_range_access(
+        &mut self,
+        ip_net: ipnet::IpNet,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        access: Access,
+        ambient_authority: AmbientAuthority,
+    ) {
+        let _ = ambient_authority;
+
+        self.push_rules(false, access, AddrSet::Net(ip_net), ports_start, ports_end)
     }
 
     /// Add a range of network addresses with a specific port to the pool.
@@ -158,17 +527,476 @@ impl Pool {
         self.insert_ip_net_port_range(ip_net, port, port.checked_add(1), ambient_authority)
     }
 
-    /// Check whether the given address is within the pool.
+    /// Add a host name with a specific port to the pool.
+    ///
+    /// Unlike [`Pool::insert`], this doesn't resolve the name. The grant
+    /// matches the name itself, when it's passed to [`Pool::resolve_host`],
+    /// and doesn't match any address directly.
+    ///
+    /// This fails if `host` isn't a host name: it must be non-empty, contain
+    /// only ASCII letters, digits, `-`, `.`, and `_`, and not be an IP
+    /// address.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any host name.
+    pub fn insert_host(
+        &mut self,
+        host: &str,
+        port: u16,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<()> {
+        self.insert_host_port_range(host, port, port.checked_add(1), ambient_authority)
+    }
+
+    /// Add a host name, accepting any port, to the pool.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any host name.
+    pub fn insert_host_port_any(
+        &mut self,
+        host: &str,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<()> {
+        self.insert_host_port_range(host, 0, None, ambient_authority)
+    }
+
+    /// Add a host name, accepting a range of ports, to the pool.
+    ///
+    /// This grants access to the port range starting at `ports_start` and,
+    /// if `ports_end` is provided, ending before `ports_end`.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any host name.
+    pub fn insert_host_port_range(
+        &mut self,
+        host: &str,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<()> {
+        self.insert_host_port_range_access(
+            host,
+            ports_start,
+            ports_end,
+            Access::ALL,
+            ambient_authority,
+        )
+    }
+
+    /// Add a host name, accepting a range of ports, to the pool, for only the
+    /// operations in `access`.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any host name.
+    pub fn insert_host_port_range_access(
+        &mut self,
+        host: &str,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        access: Access,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<()> {
+        let _ = ambient_authority;
+
+        let set = host_set(host)?;
+        self.push_rules(false, access, set, ports_start, ports_end);
+        Ok(())
+    }
+
+    /// Deny a range of network addresses with a specific port.
+    ///
+    /// Deny rules take precedence over allow rules.
+    pub fn deny_ip_net(&mut self, ip_net: ipnet::IpNet, port: u16) {
+        self.deny_ip_net_port_range(ip_net, port, port.checked_add(1))
+    }
+
+    /// Deny a range of network addresses, with any port.
+    ///
+    /// Deny rules take precedence over allow rules.
+    pub fn deny_ip_net_port_any(&mut self, ip_net: ipnet::IpNet) {
+        self.deny_ip_net_port_range(ip_net, 0, None)
+    }
+
+    /// Deny a range of network addresses, with a range of ports.
+    ///
+    /// This denies the port range starting at `ports_start` and, if
+    /// `ports_end` is provided, ending before `ports_end`. Deny rules take
+    /// precedence over allow rules.
+    pub fn deny_ip_net_port_range(
+        &mut self,
+        ip_net: ipnet::IpNet,
+        ports_start: u16,
+        ports_end: Option<u16>,
+    ) {
+        self.deny_ip_net_port_range_access(ip_net, ports_start, ports_end, Access::ALL)
+    }
+
+    /// Deny the operations in `access` on a range of network addresses, with
+    /// a range of ports.
+    ///
+    /// Deny rules take precedence over allow rules.
+    pub fn deny_ip_net_port_range_access(
+        &mut self,
+        ip_net: ipnet::IpNet,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        access: Access,
+    ) {
+        self.push_rules(true, access, AddrSet::Net(ip_net), ports_start, ports_end)
+    }
+
+    /// Deny a host name with a specific port.
+    ///
+    /// Deny rules take precedence over allow rules. As with
+    /// [`Pool::insert_host`], this fails if `host` isn't a host name.
+    pub fn deny_host(&mut self, host: &str, port: u16) -> io::Result<()> {
+        self.deny_host_port_range(host, port, port.checked_add(1))
+    }
+
+    /// Deny a host name, with any port.
+    ///
+    /// Deny rules take precedence over allow rules.
+    pub fn deny_host_port_any(&mut self, host: &str) -> io::Result<()> {
+        self.deny_host_port_range(host, 0, None)
+    }
+
+    /// Deny a host name, with a range of ports.
+    ///
+    /// This denies the port range starting at `ports_start` and, if
+    /// `ports_end` is provided, ending before `ports_end`. Deny rules take
+    /// precedence over allow rules.
+    pub fn deny_host_port_range(
+        &mut self,
+        host: &str,
+        ports_start: u16,
+        ports_end: Option<u16>,
+    ) -> io::Result<()> {
+        self.deny_host_port_range_access(host, ports_start, ports_end, Access::ALL)
+    }
+
+    /// Deny the operations in `access` on a host name, with a range of ports.
+    ///
+    /// Deny rules take precedence over allow rules.
+    pub fn deny_host_port_range_access(
+        &mut self,
+        host: &str,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        access: Access,
+    ) -> io::Result<()> {
+        let set = host_set(host)?;
+        self.push_rules(true, access, set, ports_start, ports_end);
+        Ok(())
+    }
+
+    /// Add rules for `access`, split so that each rule's access can be
+    /// described in the textual rule format.
+    fn push_rules(
+        &mut self,
+        deny: bool,
+        access: Access,
+        set: AddrSet,
+        ports_start: u16,
+        ports_end: Option<u16>,
+    ) {
+        for access in access.split() {
+            self.rules.push(Rule {
+                deny,
+                access,
+                set: set.clone(),
+                ports_start,
+                ports_end,
+            })
+        }
+    }
+
+    /// Construct a new pool with the authority of this pool, limited to the
+    /// operations in `access` on the range of network addresses `ip_net`,
+    /// with ports starting at `ports_start` and, if `ports_end` is provided,
+    /// ending before `ports_end`.
+    ///
+    /// Host name rules are dropped, since they don't match any addresses.
+    /// This doesn't need ambient authority, since the new pool is never
+    /// stronger than this one.
+    pub fn restrict(
+        &self,
+        ip_net: ipnet::IpNet,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        access: Access,
+    ) -> Self {
+        let mut limit = Self::new();
+        limit.push_rules(false, access, AddrSet::Net(ip_net), ports_start, ports_end);
+        self.intersect(&limit)
+    }
+
+    /// Construct a new pool with the authority of this pool, limited to the
+    /// operations in `access`.
+    pub fn restrict_access(&self, access: Access) -> Self {
+        let mut pool = Self::new();
+        for rule in &self.rules {
+            if rule.deny {
+                pool.rules.push(rule.clone());
+            } else {
+                pool.push_rules(
+                    false,
+                    rule.access & access,
+                    rule.set.clone(),
+                    rule.ports_start,
+                    rule.ports_end,
+                );
+            }
+        }
+        pool
+    }
+
+    /// Construct a new pool which allows exactly what both this pool and
+    /// `other` allow.
+    pub fn intersect(&self, other: &Self) -> Self {
+        let mut pool = Self::new();
+        for a in self.rules.iter().filter(|rule| !rule.deny) {
+            for b in other.rules.iter().filter(|rule| !rule.deny) {
+                let set = match a.set.intersect(&b.set) {
+                    Some(set) => set,
+                    None => continue,
+                };
+                let ports_start = a.ports_start.max(b.ports_start);
+                let ports_end = match (a.ports_end, b.ports_end) {
+                    (Some(a_end), Some(b_end)) => Some(a_end.min(b_end)),
+                    (a_end, b_end) => a_end.or(b_end),
+                };
+                if ports_end.is_some_and(|ports_end| ports_start >= ports_end) {
+                    continue;
+                }
+                pool.push_rules(false, a.access & b.access, set, ports_start, ports_end);
+            }
+        }
+        // Anything either pool denies stays denied.
+        pool.rules.extend(
+            self.rules
+                .iter()
+                .chain(&other.rules)
+                .filter(|rule| rule.deny)
+                .cloned(),
+        );
+        pool
+    }
+
+    /// Returns `true` if everything this pool allows is also allowed by
+    /// `other`.
+    ///
+    /// This is exact: the address and port ranges of both pools' rules are
+    /// split at every boundary, and each resulting range is checked.
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        let mut ports = vec![0];
+        let mut v4 = Vec::new();
+        let mut v6 = Vec::new();
+        let mut hosts = Vec::new();
+        for rule in self.rules.iter().chain(&other.rules) {
+            ports.push(rule.ports_start);
+            ports.extend(rule.ports_end);
+            match &rule.set {
+                AddrSet::Net(IpNet::V4(net)) => {
+                    let (start, end) = v4_range(net);
+                    v4.push(start);
+                    v4.extend(end.checked_add(1));
+                }
+                AddrSet::Net(IpNet::V6(net)) => {
+                    let (start, end) = v6_range(net);
+                    v6.push(start);
+                    v6.extend(end.checked_add(1));
+                }
+                AddrSet::Host(host) => hosts.push(host.as_str()),
+            }
+        }
+        ports.sort_unstable();
+        ports.dedup();
+        v4.sort_unstable();
+        v4.dedup();
+        v6.sort_unstable();
+        v6.dedup();
+        hosts.sort_unstable();
+        hosts.dedup();
+
+        let addrs = v4
+            .into_iter()
+            .map(|addr| net::IpAddr::V4(addr.into()))
+            .chain(v6.into_iter().map(|addr| net::IpAddr::V6(addr.into())))
+            .collect::<Vec<_>>();
+        for operation in Access::ALL.operations() {
+            for port in &ports {
+                for addr in &addrs {
+                    let addr = net::SocketAddr::new(*addr, *port);
+                    if self.explain_addr(&addr, operation).is_allowed()
+                        && !other.explain_addr(&addr, operation).is_allowed()
+                    {
+                        return false;
+                    }
+                }
+                for host in &hosts {
+                    if self.explain_host(host, *port, operation).is_allowed()
+                        && !other.explain_host(host, *port, operation).is_allowed()
+                    {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the rules in the pool, in the order they were added.
+    #[inline]
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Determine whether the operations in `access` on the given address
+    /// are allowed by the pool, and which rule decided it.
+    ///
+    /// When `access` contains several operations, each of them must be
+    /// allowed, and the verdict is for the first one which isn't.
+    pub fn explain_addr(&self, addr: &net::SocketAddr, access: Access) -> Verdict {
+        self.explain(access, |rule| rule.contains(addr))
+    }
+
+    /// Determine whether the operations in `access` on the given host name
+    /// and port are allowed by the pool, and which rule decided it.
+    ///
+    /// When `access` contains several operations, each of them must be
+    /// allowed, and the verdict is for the first one which isn't.
+    pub fn explain_host(&self, host: &str, port: u16, access: Access) -> Verdict {
+        let host = normalize_host(host);
+        self.explain(access, |rule| rule.contains_host(&host, port))
+    }
+
+    fn explain(&self, access: Access, applies: impl Fn(&Rule) -> bool) -> Verdict {
+        let mut verdict = Verdict::NotGranted;
+        for operation in access.operations() {
+            let applies = |rule: &Rule| rule.access.contains(operation) && applies(rule);
+            if let Some(rule) = self.rules.iter().find(|rule| rule.deny && applies(rule)) {
+                return Verdict::Denied(rule.clone());
+            }
+            match self.rules.iter().find(|rule| !rule.deny && applies(rule)) {
+                Some(rule) => {
+                    if !verdict.is_allowed() {
+                        verdict = Verdict::Allowed(rule.clone());
+                    }
+                }
+                None => return Verdict::NotGranted,
+            }
+        }
+        verdict
+    }
+
+    /// Check whether the given address is within the pool, for all
+    /// operations.
     pub fn check_addr(&self, addr: &net::SocketAddr) -> io::Result<()> {
-        if self.grants.iter().any(|grant| grant.contains(addr)) {
-            Ok(())
-        } else {
-            Err(io::Error::new(
+        self.check_addr_access(addr, Access::ALL)
+    }
+
+    /// Check whether the operations in `access` on the given address are
+    /// allowed by the pool.
+    pub fn check_addr_access(&self, addr: &net::SocketAddr, access: Access) -> io::Result<()> {
+        match self.explain_addr(addr, access) {
+            Verdict::Allowed(_) => Ok(()),
+            Verdict::Denied(_) => Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "An address was denied by the pool",
+            )),
+            Verdict::NotGranted => Err(io::Error::new(
                 io::ErrorKind::PermissionDenied,
                 "An address was outside the pool",
-            ))
+            )),
+        }
+    }
+
+    /// Check whether the given host name and port are within the pool, for
+    /// all operations.
+    pub fn check_host(&self, host: &str, port: u16) -> io::Result<()> {
+        self.check_host_access(host, port, Access::ALL)
+    }
+
+    /// Check whether the operations in `access` on the given host name and
+    /// port are allowed by the pool.
+    pub fn check_host_access(&self, host: &str, port: u16, access: Access) -> io::Result<()> {
+        match self.explain_host(host, port, access) {
+            Verdict::Allowed(_) => Ok(()),
+            Verdict::Denied(_) => Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "A host name was denied by the pool",
+            )),
+            Verdict::NotGranted => Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "A host name was outside the pool",
+            )),
         }
     }
+
+    /// Resolve a host name which is within the pool, for the operations in
+    /// `access`, using `resolver`.
+    ///
+    /// The host name and port must be granted by [`Pool::insert_host`] or
+    /// similar, and each address produced by the resolver must have a port
+    /// which the grant covers. Addresses which a deny rule applies to are
+    /// rejected, even though the host name is allowed.
+    pub fn resolve_host(
+        &self,
+        resolver: &Resolver,
+        host: &str,
+        port: u16,
+        access: Access,
+    ) -> io::Result<Vec<net::SocketAddr>> {
+        self.check_host_access(host, port, access)?;
+        let addrs = resolver.resolve(host, port)?;
+        for addr in &addrs {
+            self.check_host_access(host, addr.port(), access)?;
+            if let Verdict::Denied(_) = self.explain_addr(addr, access) {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "An address was denied by the pool",
+                ));
+            }
+        }
+        if addrs.is_empty() {
+            return Err(no_socket_addrs());
+        }
+        Ok(addrs)
+    }
+}
+
+fn v4_range(net: &Ipv4Net) -> (u32, u32) {
+    (net.network().into(), net.broadcast().into())
+}
+
+fn v6_range(net: &Ipv6Net) -> (u128, u128) {
+    (net.network().into(), net.broadcast().into())
+}
+
+/// Prints the rules in the pool, one per line, in the form accepted by
+/// [`Pool::parse`].
+impl fmt::Display for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rule in &self.rules {
+            writeln!(f, "{}", rule)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serializes the pool as a sequence of [`Rule`]s. There's no corresponding
+/// `Deserialize` implementation, because constructing a pool requires
+/// ambient authority; deserialize a `Vec<Rule>` and use [`Pool::from_rules`]
+/// instead.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Pool {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.rules)
+    }
 }
 
 /// An empty array of `SocketAddr`s.
@@ -259,6 +1087,373 @@ fn test_port_one() {
         .unwrap_err();
 }
 
+#[test]
+fn test_host() {
+    use crate::net::StaticResolver;
+
+    let mut p = Pool::new();
+    p.insert_host("Api.Internal.", 443, ambient_authority())
+        .unwrap();
+
+    p.check_host("api.internal", 443).unwrap();
+    p.check_host("API.INTERNAL.", 443).unwrap();
+    p.check_host("api.internal", 80).unwrap_err();
+    p.check_host("other.internal", 443).unwrap_err();
+
+    let mut table = StaticResolver::new();
+    table.insert("api.internal", net::IpAddr::from_str("10.0.0.1").unwrap());
+    let resolver = Resolver::new(table);
+
+    let addrs = p
+        .resolve_host(&resolver, "api.internal", 443, Access::CONNECT)
+        .unwrap();
+    assert_eq!(addrs, [net::SocketAddr::from_str("10.0.0.1:443").unwrap()]);
+    p.resolve_host(&resolver, "api.internal", 80, Access::CONNECT)
+        .unwrap_err();
+    p.resolve_host(&resolver, "other.internal", 443, Access::CONNECT)
+        .unwrap_err();
+
+    // A host grant doesn't grant the addresses the host resolves to.
+    p.check_addr(&addrs[0]).unwrap_err();
+}
+
+#[test]
+fn test_deny() {
+    let mut p = Pool::new();
+    p.deny_ip_net_port_any(IpNet::from_str("10.0.5.0/24").unwrap());
+    p.insert_ip_net_port_any(IpNet::from_str("10.0.0.0/8").unwrap(), ambient_authority());
+    p.deny_ip_net(IpNet::from_str("10.0.0.0/8").unwrap(), 22);
+
+    p.check_addr(&net::SocketAddr::from_str("10.1.2.3:80").unwrap())
+        .unwrap();
+    p.check_addr(&net::SocketAddr::from_str("10.1.2.3:22").unwrap())
+        .unwrap_err();
+    p.check_addr(&net::SocketAddr::from_str("10.0.5.1:80").unwrap())
+        .unwrap_err();
+    p.check_addr(&net::SocketAddr::from_str("11.0.0.1:80").unwrap())
+        .unwrap_err();
+
+    assert_eq!(
+        p.explain_addr(
+            &net::SocketAddr::from_str("10.1.2.3:80").unwrap(),
+            Access::ALL
+        ),
+        Verdict::Allowed(p.rules()[1].clone())
+    );
+    assert_eq!(
+        p.explain_addr(
+            &net::SocketAddr::from_str("10.0.5.1:22").unwrap(),
+            Access::ALL
+        ),
+        Verdict::Denied(p.rules()[0].clone())
+    );
+    assert_eq!(
+        p.explain_addr(
+            &net::SocketAddr::from_str("10.1.2.3:22").unwrap(),
+            Access::ALL
+        ),
+        Verdict::Denied(p.rules()[2].clone())
+    );
+    assert_eq!(
+        p.explain_addr(
+            &net::SocketAddr::from_str("11.0.0.1:80").unwrap(),
+            Access::ALL
+        ),
+        Verdict::NotGranted
+    );
+}
+
+#[test]
+fn test_deny_host() {
+    use crate::net::StaticResolver;
+
+    let mut p = Pool::new();
+    p.insert_host_port_any("api.internal", ambient_authority())
+        .unwrap();
+    p.deny_host("api.internal", 22).unwrap();
+    p.deny_ip_net_port_any(IpNet::from_str("10.0.5.0/24").unwrap());
+
+    p.check_host("api.internal", 443).unwrap();
+    p.check_host("api.internal", 22).unwrap_err();
+    assert!(matches!(
+        p.explain_host("API.internal", 22, Access::ALL),
+        Verdict::Denied(rule) if rule.host() == Some("api.internal")
+    ));
+
+    // Deny rules for addresses also apply to resolved host names.
+    let mut table = StaticResolver::new();
+    table.insert("api.internal", net::IpAddr::from_str("10.0.5.1").unwrap());
+    p.resolve_host(&Resolver::new(table), "api.internal", 443, Access::CONNECT)
+        .unwrap_err();
+}
+
+#[test]
+fn test_text() {
+    let text = "\
+allow 10.0.0.0/8:443
+deny 10.0.5.0/24:*
+deny 10.0.0.0/8:22
+allow [::1]:5000-5010
+allow [fd00::/8]:1024-65535
+allow 127.0.0.1:0
+allow api.internal:65535
+";
+    let p = Pool::parse(text, ambient_authority()).unwrap();
+    assert_eq!(p.rules().len(), 7);
+    assert_eq!(p.to_string(), text);
+
+    p.check_addr(&net::SocketAddr::from_str("10.1.2.3:443").unwrap())
+        .unwrap();
+    p.check_addr(&net::SocketAddr::from_str("10.0.5.1:443").unwrap())
+        .unwrap_err();
+    p.check_addr(&net::SocketAddr::from_str("[::1]:5010").unwrap())
+        .unwrap();
+    p.check_addr(&net::SocketAddr::from_str("[::1]:5011").unwrap())
+        .unwrap_err();
+    p.check_host("API.internal", 65535).unwrap();
+
+    // Comments, blank lines, and uppercase host names are accepted.
+    let p = Pool::parse(
+        "# comment\n\n  allow Example.COM.:80  # web\n",
+        ambient_authority(),
+    )
+    .unwrap();
+    assert_eq!(p.to_string(), "allow example.com:80\n");
+
+    for bad in [
+        "permit 10.0.0.0/8:443",
+        "allow",
+        "allow 10.0.0.0/8",
+        "allow 10.0.0.0/33:443",
+        "allow ::1:443",
+        "allow [10.0.0.1]:443",
+        "allow 10.0.0.1:65536",
+        "allow 10.0.0.1:1-x",
+        "allow 10.0.0.1:443 extra",
+        "allow 10.0.0.1:2-1",
+        "allow 10.0.0.1:none",
+        "allow .:443",
+        "allow 10.0.0.1.:443",
+    ] {
+        Rule::from_str(bad).unwrap_err();
+    }
+    let err = Pool::parse("allow 10.0.0.1:80\nallow nope", ambient_authority())
+        .err()
+        .unwrap();
+    assert!(err.to_string().starts_with("line 2: "), "{}", err);
+}
+
+#[test]
+fn test_text_host_names() {
+    // Names which wouldn't parse back as the same host name are rejected.
+    for bad in [
+        "",
+        ".",
+        "10.0.0.1",
+        "10.0.0.1.",
+        "::1",
+        "a:b",
+        "a b",
+        "a#b",
+        "a/b",
+        "10.0.0.0/8",
+    ] {
+        let mut p = Pool::new();
+        assert!(
+            p.insert_host(bad, 443, ambient_authority()).is_err(),
+            "{}",
+            bad
+        );
+        assert!(p.insert_host_port_any(bad, ambient_authority()).is_err());
+        assert!(p.deny_host(bad, 443).is_err());
+        assert!(p.deny_host_port_any(bad).is_err());
+        assert!(p.rules().is_empty());
+    }
+
+    // Valid names print as rules which parse back to the same rules.
+    let mut p = Pool::new();
+    p.insert_host("Api.Internal.", 443, ambient_authority())
+        .unwrap();
+    p.insert_host_port_range_access(
+        "1.2.3",
+        80,
+        Some(90),
+        Access::TCP_CONNECT,
+        ambient_authority(),
+    )
+    .unwrap();
+    p.deny_host_port_any("db_1.internal").unwrap();
+    let parsed = Pool::parse(&p.to_string(), ambient_authority()).unwrap();
+    assert_eq!(parsed.rules(), p.rules());
+}
+
+#[test]
+fn test_text_empty_port_range() {
+    // Empty ranges of ports match nothing, so they mustn't print as something
+    // which parses back as a rule which matches a port.
+    for (ports_start, ports_end) in [(0, Some(0)), (80, Some(80)), (443, Some(80))] {
+        let mut p = Pool::new();
+        p.insert_ip_net_port_range(
+            IpNet::from_str("10.0.0.0/8").unwrap(),
+            ports_start,
+            ports_end,
+            ambient_authority(),
+        );
+        let text = p.to_string();
+        assert_eq!(text, "allow 10.0.0.0/8:none\n");
+        assert!(Pool::parse(&text, ambient_authority()).is_err());
+        p.check_addr(&net::SocketAddr::from_str("10.0.0.1:0").unwrap())
+            .unwrap_err();
+    }
+}
+
+#[test]
+fn test_access() {
+    let mut p = Pool::new();
+    p.insert_ip_net_port_range_access(
+        IpNet::from_str("10.0.0.0/8").unwrap(),
+        443,
+        Some(444),
+        Access::TCP_CONNECT,
+        ambient_authority(),
+    );
+    p.insert_ip_net_port_range_access(
+        IpNet::from_str("127.0.0.0/8").unwrap(),
+        0,
+        None,
+        Access::TCP | Access::UDP_BIND,
+        ambient_authority(),
+    );
+    p.deny_ip_net_port_range_access(
+        IpNet::from_str("127.0.0.2/32").unwrap(),
+        0,
+        None,
+        Access::BIND,
+    );
+
+    let backend = net::SocketAddr::from_str("10.0.0.1:443").unwrap();
+    p.check_addr_access(&backend, Access::TCP_CONNECT).unwrap();
+    p.check_addr_access(&backend, Access::TCP_BIND).unwrap_err();
+    p.check_addr_access(&backend, Access::UDP_CONNECT)
+        .unwrap_err();
+    p.check_addr(&backend).unwrap_err();
+
+    let local = net::SocketAddr::from_str("127.0.0.1:80").unwrap();
+    p.check_addr_access(&local, Access::TCP | Access::UDP_BIND)
+        .unwrap();
+    p.check_addr_access(&local, Access::UDP_CONNECT)
+        .unwrap_err();
+    let denied = net::SocketAddr::from_str("127.0.0.2:80").unwrap();
+    p.check_addr_access(&denied, Access::TCP_CONNECT).unwrap();
+    assert!(matches!(
+        p.explain_addr(&denied, Access::TCP_BIND),
+        Verdict::Denied(rule) if rule.access() == Access::BIND
+    ));
+
+    // Access sets which aren't a product of protocols and operations are
+    // split into several rules, so that each can be printed.
+    assert_eq!(
+        p.to_string(),
+        "allow tcp connect 10.0.0.0/8:443\n\
+         allow tcp 127.0.0.0/8:*\n\
+         allow udp bind 127.0.0.0/8:*\n\
+         deny bind 127.0.0.2:*\n"
+    );
+    let reparsed = Pool::parse(&p.to_string(), ambient_authority()).unwrap();
+    assert_eq!(reparsed.rules(), p.rules());
+
+    let rule = Rule::from_str("allow connect api.internal:443").unwrap();
+    assert_eq!(rule.access(), Access::CONNECT);
+    assert_eq!(rule.host(), Some("api.internal"));
+    Rule::from_str("allow connect tcp 10.0.0.1:443").unwrap_err();
+}
+
+#[test]
+fn test_intersect() {
+    let a = Pool::parse(
+        "allow 10.0.0.0/8:1000-2000\nallow api.internal:*\ndeny 10.0.5.0/24:*\n",
+        ambient_authority(),
+    )
+    .unwrap();
+    let b = Pool::parse(
+        "allow tcp 10.1.0.0/16:1500-3000\nallow connect api.internal:443\ndeny 10.1.2.3:*\n",
+        ambient_authority(),
+    )
+    .unwrap();
+
+    let both = a.intersect(&b);
+    assert_eq!(
+        both.to_string(),
+        "allow tcp 10.1.0.0/16:1500-2000\n\
+         allow connect api.internal:443\n\
+         deny 10.0.5.0/24:*\n\
+         deny 10.1.2.3:*\n"
+    );
+    assert!(both.is_subset_of(&a));
+    assert!(both.is_subset_of(&b));
+    assert!(!a.is_subset_of(&both));
+    assert!(!b.is_subset_of(&both));
+
+    let restricted = a.restrict(
+        IpNet::from_str("10.0.0.0/16").unwrap(),
+        1000,
+        Some(1001),
+        Access::TCP_CONNECT,
+    );
+    assert_eq!(
+        restricted.to_string(),
+        "allow tcp connect 10.0.0.0/16:1000\ndeny 10.0.5.0/24:*\n"
+    );
+    assert!(restricted.is_subset_of(&a));
+
+    let connect_only = a.restrict_access(Access::CONNECT);
+    assert!(connect_only.is_subset_of(&a));
+    assert!(!a.is_subset_of(&connect_only));
+    connect_only
+        .check_addr_access(
+            &net::SocketAddr::from_str("10.0.0.1:1000").unwrap(),
+            Access::UDP_CONNECT,
+        )
+        .unwrap();
+    connect_only
+        .check_addr_access(
+            &net::SocketAddr::from_str("10.0.0.1:1000").unwrap(),
+            Access::UDP_BIND,
+        )
+        .unwrap_err();
+}
+
+#[test]
+fn test_subset() {
+    let parse = |text| Pool::parse(text, ambient_authority()).unwrap();
+
+    assert!(Pool::new().is_subset_of(&Pool::new()));
+    assert!(Pool::new().is_subset_of(&parse("allow 10.0.0.1:80")));
+    assert!(!parse("allow 10.0.0.1:80").is_subset_of(&Pool::new()));
+
+    // Covering a range with several smaller ones.
+    assert!(parse("allow 10.0.0.0/8:80")
+        .is_subset_of(&parse("allow 10.0.0.0/9:80\nallow 10.128.0.0/9:*")));
+    assert!(!parse("allow 10.0.0.0/8:80").is_subset_of(&parse("allow 10.0.0.0/9:80")));
+    assert!(
+        parse("allow [::/0]:10-20").is_subset_of(&parse("allow [::/1]:*\nallow [8000::/1]:0-20"))
+    );
+    assert!(!parse("allow 10.0.0.1:10-20").is_subset_of(&parse("allow 10.0.0.1:10-19")));
+
+    // Deny rules are taken into account on both sides.
+    assert!(
+        !parse("allow 10.0.0.0/8:80").is_subset_of(&parse("allow 10.0.0.0/8:80\ndeny 10.0.0.7:80"))
+    );
+    assert!(parse("allow 10.0.0.0/8:80\ndeny 10.0.0.0/9:*")
+        .is_subset_of(&parse("allow 10.128.0.0/9:80")));
+    assert!(parse("allow 255.255.255.255:65535").is_subset_of(&parse("allow 0.0.0.0/0:*")));
+
+    // Host names and addresses are distinct.
+    assert!(!parse("allow api.internal:443").is_subset_of(&parse("allow 0.0.0.0/0:*")));
+    assert!(parse("allow tcp api.internal:443").is_subset_of(&parse("allow API.internal.:*")));
+    assert!(!parse("allow api.internal:443").is_subset_of(&parse("allow tcp api.internal:*")));
+}
+
 #[test]
 fn test_addrs() {
     let mut p = Pool::new();
@@ -0,0 +1,114 @@
+use crate::AmbientAuthority;
+use std::collections::HashMap;
+use std::net::ToSocketAddrs;
+use std::sync::Arc;
+use std::{fmt, io, net};
+
+/// A name resolution service, which can be wrapped in a [`Resolver`].
+pub trait Resolve: Send + Sync {
+    /// Resolve `host` to a list of socket addresses with port `port`.
+    fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<net::SocketAddr>>;
+}
+
+/// A capability to resolve host names into addresses.
+///
+/// A `Resolver` is passed explicitly to the [`Pool`] methods which connect to
+/// hosts by name. The addresses it produces are still checked against the
+/// pool's grant for the name.
+///
+/// `Resolver` implements `Clone`, which creates new handles to the same
+/// underlying resolution service.
+///
+/// [`Pool`]: crate::net::Pool
+#[derive(Clone)]
+pub struct Resolver {
+    inner: Arc<dyn Resolve>,
+}
+
+impl Resolver {
+    /// Constructs a new `Resolver` which uses `resolve`.
+    pub fn new<R: Resolve + 'static>(resolve: R) -> Self {
+        Self {
+            inner: Arc::new(resolve),
+        }
+    }
+
+    /// Constructs a new `Resolver` which uses the system's resolver.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to the system's name resolution
+    /// configuration and to DNS.
+    pub fn system(ambient_authority: AmbientAuthority) -> Self {
+        let _ = ambient_authority;
+
+        Self::new(SystemResolver)
+    }
+
+    /// Resolve `host` to a list of socket addresses with port `port`.
+    #[inline]
+    pub fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<net::SocketAddr>> {
+        self.inner.resolve(host, port)
+    }
+}
+
+impl fmt::Debug for Resolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resolver").finish_non_exhaustive()
+    }
+}
+
+struct SystemResolver;
+
+impl Resolve for SystemResolver {
+    fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<net::SocketAddr>> {
+        Ok((host, port).to_socket_addrs()?.collect())
+    }
+}
+
+/// A [`Resolve`] implementation which looks names up in a fixed table, for
+/// use in tests and in environments without DNS.
+///
+/// Names are matched case-insensitively, and ignoring any trailing `.`.
+#[derive(Clone, Debug, Default)]
+pub struct StaticResolver {
+    table: HashMap<String, Vec<net::IpAddr>>,
+}
+
+impl StaticResolver {
+    /// Construct a new empty table.
+    pub fn new() -> Self {
+        Self {
+            table: HashMap::new(),
+        }
+    }
+
+    /// Add an address for `host` to the table.
+    pub fn insert(&mut self, host: &str, addr: net::IpAddr) {
+        self.table
+            .entry(normalize_host(host))
+            .or_default()
+            .push(addr);
+    }
+}
+
+impl Resolve for StaticResolver {
+    fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<net::SocketAddr>> {
+        match self.table.get(&normalize_host(host)) {
+            Some(addrs) => Ok(addrs
+                .iter()
+                .map(|addr| net::SocketAddr::new(*addr, port))
+                .collect()),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "host name not found in table",
+            )),
+        }
+    }
+}
+
+/// Host names are compared case-insensitively, and a trailing `.` denoting
+/// a fully-qualified name is ignored.
+pub(crate) fn normalize_host(host: &str) -> String {
+    host.strip_suffix('.').unwrap_or(host).to_ascii_lowercase()
+}
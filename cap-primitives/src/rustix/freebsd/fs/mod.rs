@@ -8,6 +8,7 @@ mod set_times_impl;
 mod stat_impl;
 
 pub(crate) use crate::fs::manually::canonicalize as canonicalize_impl;
+pub(crate) use crate::fs::manually::stat_no_xdev as stat_no_xdev_impl;
 pub(crate) use check::beneath_supported;
 pub(crate) use open_entry_impl::open_entry_impl;
 pub(crate) use open_impl::open_impl;
@@ -10,7 +10,9 @@ pub(crate) fn open_impl(
     path: &Path,
     options: &OpenOptions,
 ) -> io::Result<fs::File> {
-    if !super::beneath_supported() {
+    // `O_RESOLVE_BENEATH` has no equivalent of the other `openat2`
+    // `RESOLVE_*` flags, so use manual resolution for those.
+    if !super::beneath_supported() || options.no_xdev || options.no_symlinks || options.cached {
         return manually::open(start, path, options);
     }
 
@@ -2,9 +2,11 @@
 // library/std/src/sys/unix/fs.rs at revision
 // 108e90ca78f052c0c1c49c42a22c85620be19712.
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::fs::{errors, seek_data_impl, seek_hole_impl};
 use crate::fs::{open, OpenOptions};
 #[cfg(any(target_os = "android", target_os = "linux"))]
-use rustix::fs::copy_file_range;
+use rustix::fs::{copy_file_range, ioctl_ficlone};
 #[cfg(any(
     target_os = "macos",
     target_os = "ios",
@@ -16,7 +18,11 @@ use rustix::fs::{
     copyfile_state_alloc, copyfile_state_free, copyfile_state_get_copied, copyfile_state_t,
     fclonefileat, fcopyfile, CloneFlags, CopyfileFlags,
 };
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use std::cmp;
 use std::path::Path;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::{fs, io};
 
 fn open_from(start: &fs::File, path: &Path) -> io::Result<(fs::File, fs::Metadata)> {
@@ -101,6 +107,11 @@ pub(crate) fn copy_impl(
     io::copy(&mut reader, &mut writer)
 }
 
+// Kernel prior to 4.5 don't have copy_file_range
+// We store the availability in a global to avoid unnecessary syscalls
+#[cfg(any(target_os = "android", target_os = "linux"))]
+static HAS_COPY_FILE_RANGE: AtomicBool = AtomicBool::new(true);
+
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub(crate) fn copy_impl(
     from_start: &fs::File,
@@ -108,16 +119,19 @@ pub(crate) fn copy_impl(
     to_start: &fs::File,
     to_path: &Path,
 ) -> io::Result<u64> {
-    use std::cmp;
-    use std::sync::atomic::{AtomicBool, Ordering};
-
-    // Kernel prior to 4.5 don't have copy_file_range
-    // We store the availability in a global to avoid unnecessary syscalls
-    static HAS_COPY_FILE_RANGE: AtomicBool = AtomicBool::new(true);
+    use std::os::unix::fs::MetadataExt;
 
     let (mut reader, reader_metadata) = open_from(from_start, from_path)?;
     let len = reader_metadata.len();
-    let (mut writer, _) = open_to_and_set_permissions(to_start, to_path, reader_metadata)?;
+    // If fewer blocks are allocated than the length needs, the file has
+    // holes.
+    let sparse = reader_metadata.blocks().saturating_mul(512) < len;
+    let (mut writer, writer_metadata) =
+        open_to_and_set_permissions(to_start, to_path, reader_metadata)?;
+
+    if sparse && writer_metadata.is_file() {
+        return copy_sparse(&reader, &writer, len);
+    }
 
     let has_copy_file_range = HAS_COPY_FILE_RANGE.load(Ordering::Relaxed);
     let mut written = 0_u64;
@@ -168,6 +182,112 @@ pub(crate) fn copy_impl(
     Ok(written)
 }
 
+/// Copy only the ranges of `reader` which contain data, leaving holes in
+/// `writer`, which must be an empty regular file, where `reader` has holes.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn copy_sparse(reader: &fs::File, writer: &fs::File, len: u64) -> io::Result<u64> {
+    let mut offset = 0;
+    while offset < len {
+        let start = match seek_data_impl(reader, offset)? {
+            Some(start) if start < len => start,
+            _ => break,
+        };
+        let end = cmp::min(seek_hole_impl(reader, start)?, len);
+        copy_range(reader, writer, start, end - start)?;
+        offset = end;
+    }
+
+    // Extend `writer` to the full length, leaving a hole at the end if
+    // `reader` has one.
+    writer.set_len(len)?;
+    Ok(len)
+}
+
+/// Copy `len` bytes at `offset` in `reader` to the same offset in `writer`.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn copy_range(
+    reader: &fs::File,
+    writer: &fs::File,
+    mut offset: u64,
+    mut len: u64,
+) -> io::Result<()> {
+    use std::os::unix::fs::FileExt;
+
+    while len > 0 && HAS_COPY_FILE_RANGE.load(Ordering::Relaxed) {
+        let bytes_to_copy = usize::try_from(len).unwrap_or(usize::MAX);
+        let (mut off_in, mut off_out) = (offset, offset);
+        match copy_file_range(
+            reader,
+            Some(&mut off_in),
+            writer,
+            Some(&mut off_out),
+            bytes_to_copy,
+        ) {
+            // The file was truncated while we were copying it.
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(copied) => {
+                offset += copied as u64;
+                len -= copied as u64;
+            }
+            Err(rustix::io::Errno::NOSYS | rustix::io::Errno::PERM) => {
+                HAS_COPY_FILE_RANGE.store(false, Ordering::Relaxed);
+            }
+            // Fall back to reading and writing, as in `copy_impl`.
+            Err(rustix::io::Errno::XDEV | rustix::io::Errno::INVAL) => break,
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    let mut buf = vec![0; cmp::min(len, 64 * 1024) as usize];
+    while len > 0 {
+        let chunk = cmp::min(len, buf.len() as u64) as usize;
+        let read = reader.read_at(&mut buf[..chunk], offset)?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        writer.write_all_at(&buf[..read], offset)?;
+        offset += read as u64;
+        len -= read as u64;
+    }
+    Ok(())
+}
+
+/// Make a copy-on-write clone of a file using `FICLONE`.
+///
+/// `to_path` is created or truncated before cloning, so it may be left empty
+/// if cloning fails.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn reflink_impl(
+    from_start: &fs::File,
+    from_path: &Path,
+    to_start: &fs::File,
+    to_path: &Path,
+) -> io::Result<u64> {
+    let (reader, reader_metadata) = open_from(from_start, from_path)?;
+    let len = reader_metadata.len();
+    let (writer, writer_metadata) =
+        open_to_and_set_permissions(to_start, to_path, reader_metadata)?;
+    if !writer_metadata.is_file() {
+        return Err(errors::reflink_unsupported());
+    }
+
+    match ioctl_ficlone(&writer, &reader) {
+        Ok(()) => Ok(len),
+        // `FICLONE` fails with `EOPNOTSUPP` if the filesystem doesn't support
+        // it, `EXDEV` if the files are on different filesystems, `ENOTTY` if
+        // the ioctl isn't known, and `EINVAL` if the filesystem can't clone
+        // these particular files.
+        Err(
+            rustix::io::Errno::OPNOTSUPP
+            | rustix::io::Errno::XDEV
+            | rustix::io::Errno::NOTTY
+            | rustix::io::Errno::INVAL
+            | rustix::io::Errno::NOSYS,
+        ) => Err(errors::reflink_unsupported()),
+        Err(err) => Err(err.into()),
+    }
+}
+
 #[cfg(any(
     target_os = "macos",
     target_os = "ios",
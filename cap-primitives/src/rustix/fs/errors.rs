@@ -25,3 +25,13 @@ pub(crate) fn is_not_directory() -> io::Error {
 pub(crate) fn too_many_symlinks() -> io::Error {
     rustix::io::Errno::LOOP.into()
 }
+
+#[cold]
+pub(crate) fn crosses_devices() -> io::Error {
+    rustix::io::Errno::XDEV.into()
+}
+
+#[cold]
+pub(crate) fn would_block() -> io::Error {
+    rustix::io::Errno::AGAIN.into()
+}
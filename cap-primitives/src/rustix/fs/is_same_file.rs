@@ -14,6 +14,11 @@ pub(crate) fn is_same_file_metadata(a: &Metadata, b: &Metadata) -> io::Result<bo
     Ok(a.dev() == b.dev() && a.ino() == b.ino())
 }
 
+/// Return an identifier for the device containing `file`.
+pub(crate) fn device_id(file: &fs::File) -> io::Result<u64> {
+    Ok(Metadata::from_file(file)?.dev())
+}
+
 /// Determine if `a` and `b` definitely refer to different inodes.
 ///
 /// This is similar to `is_same_file`, but is conservative, and doesn't depend
@@ -0,0 +1,22 @@
+use crate::fs::LockKind;
+use rustix::fs::{flock, FlockOperation};
+use rustix::io::Errno;
+use std::{fs, io};
+
+pub(crate) fn lock_impl(file: &fs::File, kind: LockKind, wait: bool) -> io::Result<bool> {
+    let operation = match (kind, wait) {
+        (LockKind::Shared, true) => FlockOperation::LockShared,
+        (LockKind::Exclusive, true) => FlockOperation::LockExclusive,
+        (LockKind::Shared, false) => FlockOperation::NonBlockingLockShared,
+        (LockKind::Exclusive, false) => FlockOperation::NonBlockingLockExclusive,
+    };
+    match flock(file, operation) {
+        Ok(()) => Ok(true),
+        Err(Errno::WOULDBLOCK) if !wait => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub(crate) fn unlock_impl(file: &fs::File) -> io::Result<()> {
+    Ok(flock(file, FlockOperation::Unlock)?)
+}
@@ -0,0 +1,84 @@
+use crate::fs::NodeKind;
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "tvos",
+    target_os = "watchos",
+    target_os = "visionos",
+)))]
+use rustix::fs::{mkfifoat, mknodat, FileType, Mode, RawMode};
+use std::path::Path;
+use std::{fs, io};
+
+/// *Unsandboxed* function similar to `mknod`, but which does not perform
+/// sandboxing.
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "tvos",
+    target_os = "watchos",
+    target_os = "visionos",
+)))]
+pub(crate) fn mknod_unchecked(
+    start: &fs::File,
+    path: &Path,
+    kind: NodeKind,
+    mode: u32,
+    dev: u64,
+) -> io::Result<()> {
+    let file_type = match kind {
+        NodeKind::RegularFile => FileType::RegularFile,
+        NodeKind::Fifo => FileType::Fifo,
+        NodeKind::CharacterDevice => FileType::CharacterDevice,
+        NodeKind::BlockDevice => FileType::BlockDevice,
+        NodeKind::Socket => FileType::Socket,
+    };
+    let mode = Mode::from_raw_mode(mode as RawMode);
+    Ok(mknodat(start, path, file_type, mode, dev as _)?)
+}
+
+/// *Unsandboxed* function similar to `create_fifo`, but which does not
+/// perform sandboxing.
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "tvos",
+    target_os = "watchos",
+    target_os = "visionos",
+)))]
+pub(crate) fn create_fifo_unchecked(start: &fs::File, path: &Path, mode: u32) -> io::Result<()> {
+    Ok(mkfifoat(start, path, Mode::from_raw_mode(mode as RawMode))?)
+}
+
+/// Darwin has no `mknodat` or `mkfifoat` before macOS 13, so these aren't
+/// supported there.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "tvos",
+    target_os = "watchos",
+    target_os = "visionos",
+))]
+pub(crate) fn mknod_unchecked(
+    _start: &fs::File,
+    _path: &Path,
+    _kind: NodeKind,
+    _mode: u32,
+    _dev: u64,
+) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "creating special files is not supported on this platform",
+    ))
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "tvos",
+    target_os = "watchos",
+    target_os = "visionos",
+))]
+pub(crate) fn create_fifo_unchecked(start: &fs::File, path: &Path, mode: u32) -> io::Result<()> {
+    mknod_unchecked(start, path, NodeKind::Fifo, mode, 0)
+}
@@ -12,7 +12,11 @@ mod hard_link_unchecked;
 mod is_file_read_write_impl;
 mod is_root_dir;
 mod is_same_file;
+#[cfg(not(target_os = "wasi"))]
+mod lock_impl;
 mod metadata_ext;
+#[cfg(not(target_os = "wasi"))]
+mod mknod_unchecked;
 mod oflags;
 mod open_options_ext;
 mod open_unchecked;
@@ -25,6 +29,8 @@ mod remove_file_unchecked;
 mod remove_open_dir_by_searching;
 mod rename_unchecked;
 mod reopen_impl;
+#[cfg(not(target_os = "wasi"))]
+mod set_owner_impl;
 #[cfg(not(any(target_os = "android", target_os = "linux", target_os = "wasi")))]
 mod set_permissions_impl;
 #[cfg(not(target_os = "wasi"))]
@@ -60,6 +66,7 @@ pub(crate) use crate::fs::{
     manually::open_entry as open_entry_impl,
     manually::open as open_impl,
     manually::stat as stat_impl,
+    manually::stat_no_xdev as stat_no_xdev_impl,
     manually::canonicalize as canonicalize_impl,
     via_parent::set_times_nofollow as set_times_nofollow_impl,
 };
@@ -81,6 +88,8 @@ pub(super) use file_path::file_path_by_ttyname_or_seaching;
     target_os = "visionos",
 )))]
 pub(crate) use file_path::file_path_by_ttyname_or_seaching as file_path;
+#[cfg(not(target_os = "wasi"))]
+pub(crate) use set_owner_impl::{set_file_owner_impl, set_owner_impl, set_symlink_owner_unchecked};
 #[cfg(not(any(
     target_os = "android",
     target_os = "linux",
@@ -103,11 +112,18 @@ pub(crate) use crate::fs::{
     via_parent::create_dir as create_dir_impl,
     via_parent::read_link as read_link_impl,
     via_parent::rename as rename_impl,
+    via_parent::rename_with as rename_with_impl,
     via_parent::symlink as symlink_impl,
     remove_open_dir_by_searching as remove_open_dir_impl,
 };
 #[cfg(not(target_os = "wasi"))]
 pub(crate) use crate::fs::via_parent::set_symlink_permissions as set_symlink_permissions_impl;
+#[cfg(not(target_os = "wasi"))]
+#[rustfmt::skip]
+pub(crate) use crate::fs::{
+    via_parent::create_fifo as create_fifo_impl,
+    via_parent::mknod as mknod_impl,
+};
 #[cfg(not(target_os = "freebsd"))]
 #[rustfmt::skip]
 pub(crate) use crate::fs::{
@@ -117,6 +133,8 @@ pub(crate) use crate::fs::{
 
 pub(crate) use access_unchecked::access_unchecked;
 pub(crate) use copy_impl::copy_impl;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) use copy_impl::reflink_impl;
 pub(crate) use create_dir_unchecked::create_dir_unchecked;
 pub(crate) use dir_entry_inner::DirEntryInner;
 #[cfg(not(target_os = "wasi"))]
@@ -127,8 +145,14 @@ pub(crate) use hard_link_unchecked::hard_link_unchecked;
 pub(crate) use is_file_read_write_impl::is_file_read_write_impl;
 pub(crate) use is_root_dir::is_root_dir;
 #[allow(unused_imports)]
-pub(crate) use is_same_file::{is_different_file, is_different_file_metadata, is_same_file};
+pub(crate) use is_same_file::{
+    device_id, is_different_file, is_different_file_metadata, is_same_file,
+};
+#[cfg(not(target_os = "wasi"))]
+pub(crate) use lock_impl::{lock_impl, unlock_impl};
 pub(crate) use metadata_ext::ImplMetadataExt;
+#[cfg(not(target_os = "wasi"))]
+pub(crate) use mknod_unchecked::{create_fifo_unchecked, mknod_unchecked};
 pub(crate) use open_options_ext::ImplOpenOptionsExt;
 pub(crate) use open_unchecked::{open_ambient_impl, open_unchecked};
 pub(crate) use permissions_ext::ImplPermissionsExt;
@@ -138,7 +162,8 @@ pub(crate) use remove_dir_all_impl::{remove_dir_all_impl, remove_open_dir_all_im
 pub(crate) use remove_dir_unchecked::remove_dir_unchecked;
 pub(crate) use remove_file_unchecked::remove_file_unchecked;
 pub(crate) use remove_open_dir_by_searching::remove_open_dir_by_searching;
-pub(crate) use rename_unchecked::rename_unchecked;
+#[allow(unused_imports)]
+pub(crate) use rename_unchecked::{rename_unchecked, rename_with_unchecked};
 pub(crate) use reopen_impl::reopen_impl;
 pub(crate) use stat_unchecked::stat_unchecked;
 pub(crate) use symlink_unchecked::symlink_unchecked;
@@ -1,3 +1,5 @@
+use rustix::fs::OFlags;
+
 #[derive(Debug, Clone)]
 pub(crate) struct ImplOpenOptionsExt {
     pub(crate) mode: u32,
@@ -21,4 +23,15 @@ impl ImplOpenOptionsExt {
         self.custom_flags = flags;
         self
     }
+
+    /// Test whether the custom flags may create or modify a file, even though
+    /// they can't change the access mode.
+    pub(crate) fn may_modify(&self) -> bool {
+        let flags = OFlags::from_bits_retain(self.custom_flags as _);
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        if flags.contains(OFlags::TMPFILE) {
+            return true;
+        }
+        flags.intersects(OFlags::CREATE | OFlags::TRUNC | OFlags::APPEND)
+    }
 }
@@ -1,4 +1,25 @@
+use crate::fs::RenameOptions;
 use rustix::fs::renameat;
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "tvos",
+    target_os = "watchos",
+    target_os = "visionos",
+)))]
+use rustix::fs::{linkat, statat, unlinkat, AtFlags, FileType};
+#[cfg(any(
+    target_os = "android",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "tvos",
+    target_os = "watchos",
+    target_os = "visionos",
+))]
+use rustix::fs::{renameat_with, RenameFlags};
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use rustix::io::Errno;
 use std::path::Path;
 use std::{fs, io};
 
@@ -12,3 +33,110 @@ pub(crate) fn rename_unchecked(
 ) -> io::Result<()> {
     Ok(renameat(old_start, old_path, new_start, new_path)?)
 }
+
+/// *Unsandboxed* function similar to `rename_with`, but which does not
+/// perform sandboxing.
+pub(crate) fn rename_with_unchecked(
+    old_start: &fs::File,
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    options: &RenameOptions,
+) -> io::Result<()> {
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    let whiteout = options.whiteout;
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    let whiteout = false;
+
+    if !options.noreplace && !options.exchange && !whiteout {
+        return rename_unchecked(old_start, old_path, new_start, new_path);
+    }
+
+    #[cfg(any(
+        target_os = "android",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "tvos",
+        target_os = "watchos",
+        target_os = "visionos",
+    ))]
+    {
+        let mut flags = RenameFlags::empty();
+        if options.noreplace {
+            flags |= RenameFlags::NOREPLACE;
+        }
+        if options.exchange {
+            flags |= RenameFlags::EXCHANGE;
+        }
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        if whiteout {
+            flags |= RenameFlags::WHITEOUT;
+        }
+
+        match renameat_with(old_start, old_path, new_start, new_path, flags) {
+            // `renameat2` is missing on Linux before 3.15, and some
+            // filesystems don't support its flags.
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            Err(err @ (Errno::NOSYS | Errno::INVAL)) if flags == RenameFlags::NOREPLACE => {
+                rename_noreplace_by_linking(old_start, old_path, new_start, new_path, err.into())
+            }
+            otherwise => Ok(otherwise?),
+        }
+    }
+
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "tvos",
+        target_os = "watchos",
+        target_os = "visionos",
+    )))]
+    {
+        if options.exchange {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "exchanging entries is not supported on this platform",
+            ));
+        }
+        rename_noreplace_by_linking(
+            old_start,
+            old_path,
+            new_start,
+            new_path,
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                "renaming directories without replacement is not supported on this platform",
+            ),
+        )
+    }
+}
+
+/// Emulate a `RENAME_NOREPLACE` rename by creating a hard link, which fails
+/// if the destination exists, and then removing the old name. This doesn't
+/// work for directories, which can't be hard-linked, so for them fail with
+/// `dir_error`.
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "tvos",
+    target_os = "watchos",
+    target_os = "visionos",
+)))]
+fn rename_noreplace_by_linking(
+    old_start: &fs::File,
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    dir_error: io::Error,
+) -> io::Result<()> {
+    let stat = statat(old_start, old_path, AtFlags::SYMLINK_NOFOLLOW)?;
+    if FileType::from_raw_mode(stat.st_mode as _) == FileType::Directory {
+        return Err(dir_error);
+    }
+
+    linkat(old_start, old_path, new_start, new_path, AtFlags::empty())?;
+    Ok(unlinkat(old_start, old_path, AtFlags::empty())?)
+}
@@ -0,0 +1,104 @@
+use crate::fs::{open, FollowSymlinks, OpenOptions, OpenOptionsExt};
+use rustix::fs::{chownat, fchown, AtFlags, Gid, OFlags, Uid};
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+use rustix::io::Errno;
+use std::path::Path;
+use std::{fs, io};
+
+/// `fchownat` without `AT_SYMLINK_NOFOLLOW` follows symlinks without
+/// guaranteeing to stay in the sandbox, so when following symlinks, open the
+/// file with a sandboxed `open` and then change the owner of the handle.
+pub(crate) fn set_owner_impl(
+    start: &fs::File,
+    path: &Path,
+    owner: Option<u32>,
+    group: Option<u32>,
+    follow: FollowSymlinks,
+) -> io::Result<()> {
+    if follow == FollowSymlinks::No {
+        return crate::fs::via_parent::set_symlink_owner(start, path, owner, group);
+    }
+
+    // On Linux, `O_PATH` lets us open the file without needing any access to
+    // it, and `AT_EMPTY_PATH` lets us change the owner of such a handle.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        let file = open(
+            start,
+            path,
+            OpenOptions::new()
+                .read(true)
+                .custom_flags(OFlags::PATH.bits() as i32),
+        )?;
+        Ok(chownat(
+            &file,
+            "",
+            owner.map(Uid::from_raw),
+            group.map(Gid::from_raw),
+            AtFlags::EMPTY_PATH,
+        )?)
+    }
+
+    // Elsewhere, normal handles need some kind of access, so first try read,
+    // and then write. Use `O_NONBLOCK` so that opening a FIFO doesn't block.
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    {
+        match open(
+            start,
+            path,
+            OpenOptions::new()
+                .read(true)
+                .custom_flags(OFlags::NONBLOCK.bits() as i32),
+        ) {
+            Ok(file) => return set_file_owner_impl(&file, owner, group),
+            Err(err) => match Errno::from_io_error(&err) {
+                Some(Errno::ACCESS) => (),
+                _ => return Err(err),
+            },
+        }
+
+        match open(
+            start,
+            path,
+            OpenOptions::new()
+                .write(true)
+                .custom_flags(OFlags::NONBLOCK.bits() as i32),
+        ) {
+            Ok(file) => set_file_owner_impl(&file, owner, group),
+            Err(err) => match Errno::from_io_error(&err) {
+                Some(Errno::ACCESS) | Some(Errno::ISDIR) => Err(Errno::NOTSUP.into()),
+                _ => Err(err),
+            },
+        }
+    }
+}
+
+pub(crate) fn set_file_owner_impl(
+    file: &fs::File,
+    owner: Option<u32>,
+    group: Option<u32>,
+) -> io::Result<()> {
+    Ok(fchown(
+        file,
+        owner.map(Uid::from_raw),
+        group.map(Gid::from_raw),
+    )?)
+}
+
+/// *Unsandboxed* function similar to `set_owner` with
+/// `FollowSymlinks::No`, but which does not perform sandboxing. This can just
+/// use `AT_SYMLINK_NOFOLLOW`.
+pub(crate) fn set_symlink_owner_unchecked(
+    start: &fs::File,
+    path: &Path,
+    owner: Option<u32>,
+    group: Option<u32>,
+) -> io::Result<()> {
+    Ok(chownat(
+        start,
+        path,
+        owner.map(Uid::from_raw),
+        group.map(Gid::from_raw),
+        AtFlags::SYMLINK_NOFOLLOW,
+    )?)
+}
@@ -0,0 +1,51 @@
+//! Copy-on-write cloning of file ranges using `FICLONERANGE`, which rustix
+//! doesn't wrap, so we issue the ioctl ourselves.
+
+use crate::fs::errors;
+use io_lifetimes::AsFd;
+use rustix::io::Errno;
+use rustix::ioctl::{ioctl, opcode, Setter};
+use std::{fs, io};
+
+/// `struct file_clone_range` from <linux/fs.h>.
+#[repr(C)]
+struct FileCloneRange {
+    src_fd: i64,
+    src_offset: u64,
+    src_length: u64,
+    dest_offset: u64,
+}
+
+/// `FICLONERANGE` is `_IOW(0x94, 13, struct file_clone_range)`.
+const FICLONERANGE: rustix::ioctl::Opcode = opcode::write::<FileCloneRange>(0x94, 13);
+
+#[allow(unsafe_code)]
+pub(crate) fn clone_range_impl(
+    src: &fs::File,
+    src_offset: u64,
+    len: u64,
+    dst: &fs::File,
+    dst_offset: u64,
+) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let range = FileCloneRange {
+        src_fd: i64::from(src.as_fd().as_raw_fd()),
+        src_offset,
+        src_length: len,
+        dest_offset: dst_offset,
+    };
+
+    // SAFETY: `FICLONERANGE` is a pointer setter opcode taking a
+    // `struct file_clone_range`, and `src` outlives the call.
+    match unsafe { ioctl(dst, Setter::<FICLONERANGE, FileCloneRange>::new(range)) } {
+        Ok(()) => Ok(()),
+        // Unlike with `FICLONE`, `EINVAL` here usually means the offsets or
+        // length aren't aligned to the filesystem's block size, which is the
+        // caller's error, so report it as is.
+        Err(Errno::OPNOTSUPP | Errno::XDEV | Errno::NOTTY | Errno::NOSYS) => {
+            Err(errors::reflink_unsupported())
+        }
+        Err(err) => Err(err.into()),
+    }
+}
@@ -0,0 +1,37 @@
+//! Preallocation and sparse file support, using `fallocate` and
+//! `SEEK_DATA`/`SEEK_HOLE`.
+
+use rustix::fs::{fallocate, seek, FallocateFlags, SeekFrom};
+use rustix::io::Errno;
+use std::{fs, io};
+
+pub(crate) fn allocate_impl(file: &fs::File, offset: u64, len: u64) -> io::Result<()> {
+    Ok(fallocate(file, FallocateFlags::empty(), offset, len)?)
+}
+
+pub(crate) fn punch_hole_impl(file: &fs::File, offset: u64, len: u64) -> io::Result<()> {
+    // `FALLOC_FL_PUNCH_HOLE` must be combined with `FALLOC_FL_KEEP_SIZE`.
+    Ok(fallocate(
+        file,
+        FallocateFlags::PUNCH_HOLE | FallocateFlags::KEEP_SIZE,
+        offset,
+        len,
+    )?)
+}
+
+pub(crate) fn zero_range_impl(file: &fs::File, offset: u64, len: u64) -> io::Result<()> {
+    Ok(fallocate(file, FallocateFlags::ZERO_RANGE, offset, len)?)
+}
+
+pub(crate) fn seek_data_impl(file: &fs::File, offset: u64) -> io::Result<Option<u64>> {
+    match seek(file, SeekFrom::Data(offset)) {
+        Ok(pos) => Ok(Some(pos)),
+        // `ENXIO` means there's no data at or after `offset`.
+        Err(Errno::NXIO) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub(crate) fn seek_hole_impl(file: &fs::File, offset: u64) -> io::Result<u64> {
+    Ok(seek(file, SeekFrom::Hole(offset))?)
+}
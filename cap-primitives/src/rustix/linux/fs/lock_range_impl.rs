@@ -0,0 +1,65 @@
+//! Open file description locks. rustix only exposes whole-file,
+//! process-associated `fcntl` locks, so use libc for `F_OFD_SETLK`.
+
+#![allow(unsafe_code)]
+
+use crate::fs::LockKind;
+use std::os::unix::io::AsRawFd;
+use std::{fs, io};
+
+pub(crate) fn lock_range_impl(
+    file: &fs::File,
+    kind: LockKind,
+    offset: u64,
+    len: u64,
+    wait: bool,
+) -> io::Result<bool> {
+    let type_ = match kind {
+        LockKind::Shared => libc::F_RDLCK,
+        LockKind::Exclusive => libc::F_WRLCK,
+    };
+    set_lock(file, type_, offset, len, wait)
+}
+
+pub(crate) fn unlock_range_impl(file: &fs::File, offset: u64, len: u64) -> io::Result<()> {
+    set_lock(file, libc::F_UNLCK, offset, len, false).map(|_| ())
+}
+
+fn set_lock(
+    file: &fs::File,
+    type_: libc::c_int,
+    offset: u64,
+    len: u64,
+    wait: bool,
+) -> io::Result<bool> {
+    // SAFETY: `flock` is a plain C struct, for which all zeros is valid.
+    let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+    lock.l_type = type_ as _;
+    lock.l_whence = libc::SEEK_SET as _;
+    lock.l_start = offset.try_into().map_err(|_| overflow())?;
+    lock.l_len = len.try_into().map_err(|_| overflow())?;
+    // `l_pid` must be 0 for open file description locks.
+    lock.l_pid = 0;
+
+    let cmd = if wait {
+        libc::F_OFD_SETLKW
+    } else {
+        libc::F_OFD_SETLK
+    };
+    loop {
+        // SAFETY: `lock` is a valid `flock` which outlives the call.
+        if unsafe { libc::fcntl(file.as_raw_fd(), cmd, &lock) } == 0 {
+            return Ok(true);
+        }
+        let err = io::Error::last_os_error();
+        match err.raw_os_error() {
+            Some(libc::EINTR) => continue,
+            Some(libc::EAGAIN) | Some(libc::EACCES) if !wait => return Ok(false),
+            _ => return Err(err),
+        }
+    }
+}
+
+fn overflow() -> io::Error {
+    rustix::io::Errno::OVERFLOW.into()
+}
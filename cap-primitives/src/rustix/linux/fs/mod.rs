@@ -1,8 +1,12 @@
 #[cfg(target_os = "linux")]
 mod canonicalize_impl;
+mod clone_range_impl;
 #[cfg(target_os = "linux")]
 mod file_metadata;
 mod file_path;
+mod file_space_impl;
+#[cfg(target_os = "linux")]
+mod lock_range_impl;
 #[cfg(target_os = "linux")]
 mod open_entry_impl;
 mod open_impl;
@@ -11,6 +15,10 @@ mod set_permissions_impl;
 mod set_times_impl;
 #[cfg(target_os = "linux")]
 mod stat_impl;
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+mod uring_impl;
+mod watch_impl;
+mod xattr_impl;
 
 #[cfg(target_os = "android")]
 pub(crate) use crate::fs::manually::canonicalize as canonicalize_impl;
@@ -18,10 +26,16 @@ pub(crate) use crate::fs::manually::canonicalize as canonicalize_impl;
 pub(crate) use crate::fs::manually::open_entry as open_entry_impl;
 #[cfg(target_os = "android")]
 pub(crate) use crate::fs::manually::stat as stat_impl;
+#[cfg(target_os = "android")]
+pub(crate) use crate::fs::manually::stat_no_xdev as stat_no_xdev_impl;
 pub(crate) use crate::fs::via_parent::set_times_nofollow as set_times_nofollow_impl;
 #[cfg(target_os = "linux")]
 pub(crate) use canonicalize_impl::canonicalize_impl;
+pub(crate) use clone_range_impl::clone_range_impl;
 pub(crate) use file_path::file_path;
+pub(crate) use file_space_impl::*;
+#[cfg(target_os = "linux")]
+pub(crate) use lock_range_impl::*;
 #[cfg(target_os = "linux")]
 pub(crate) use open_entry_impl::open_entry_impl;
 #[cfg(target_os = "linux")]
@@ -30,7 +44,11 @@ pub(crate) use open_impl::open_impl;
 pub(crate) use set_permissions_impl::set_permissions_impl;
 pub(crate) use set_times_impl::set_times_impl;
 #[cfg(target_os = "linux")]
-pub(crate) use stat_impl::stat_impl;
+pub(crate) use stat_impl::{file_statx_impl, stat_impl, stat_no_xdev_impl, statx_impl};
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+pub(crate) use uring_impl::UringInner;
+pub(crate) use watch_impl::WatcherInner;
+pub(crate) use xattr_impl::*;
 
 // In theory we could optimize `link` using `openat2` with `O_PATH` and
 // `linkat` with `AT_EMPTY_PATH`, however that requires `CAP_DAC_READ_SEARCH`,
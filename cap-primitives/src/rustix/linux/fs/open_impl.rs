@@ -15,7 +15,7 @@ use std::{fs, io};
 #[cfg(target_os = "linux")]
 use {
     super::super::super::fs::compute_oflags,
-    crate::fs::errors,
+    crate::fs::{errors, FollowSymlinks},
     io_lifetimes::FromFd,
     rustix::fs::{openat2, Mode, OFlags, RawMode, ResolveFlags},
     rustix::path::Arg,
@@ -49,6 +49,23 @@ pub(crate) fn open_impl(
     manually::open(start, path, options)
 }
 
+/// Compute the `RESOLVE_*` flags for `openat2`. `RESOLVE_BENEATH` provides
+/// the sandboxing, and we never follow magic links, as `manually::open`
+/// can't follow them either.
+pub(crate) fn resolve_flags(options: &OpenOptions) -> ResolveFlags {
+    let mut resolve = ResolveFlags::BENEATH | ResolveFlags::NO_MAGICLINKS;
+    if options.no_xdev {
+        resolve |= ResolveFlags::NO_XDEV;
+    }
+    if options.no_symlinks {
+        resolve |= ResolveFlags::NO_SYMLINKS;
+    }
+    if options.cached {
+        resolve |= ResolveFlags::CACHED;
+    }
+    resolve
+}
+
 /// Call the `openat2` system call with `RESOLVE_BENEATH`. If the syscall is
 /// unavailable, mark it so for future calls. If `openat2` is unavailable
 /// either permanently or temporarily, return `ENOSYS`.
@@ -73,6 +90,7 @@ pub(crate) fn open_beneath(
     } else {
         Mode::empty()
     };
+    let resolve = resolve_flags(options);
 
     // We know `openat2` needs a `&CStr` internally; to avoid allocating on
     // each iteration of the loop below, allocate the `CString` now.
@@ -82,13 +100,7 @@ pub(crate) fn open_beneath(
         // times, because there's no limit on how often this can happen. The actual
         // number here is currently an arbitrarily chosen guess.
         for _ in 0..4 {
-            match openat2(
-                start,
-                path_c_str,
-                oflags,
-                mode,
-                ResolveFlags::BENEATH | ResolveFlags::NO_MAGICLINKS,
-            ) {
+            match openat2(start, path_c_str, oflags, mode, resolve) {
                 Ok(file) => {
                     let file = fs::File::from_into_fd(file);
 
@@ -98,6 +110,14 @@ pub(crate) fn open_beneath(
                     return Ok(file);
                 }
                 Err(err) => match err {
+                    // With `RESOLVE_CACHED`, `EAGAIN` means the lookup
+                    // couldn't be done from the cache. Kernels older than
+                    // 5.12 don't know `RESOLVE_CACHED` and fail with
+                    // `EINVAL`, which we report the same way.
+                    rustix::io::Errno::AGAIN | rustix::io::Errno::INVAL if options.cached => {
+                        return Err(rustix::io::Errno::AGAIN)
+                    }
+
                     // A rename or similar happened. Try again.
                     rustix::io::Errno::AGAIN => continue,
 
@@ -126,11 +146,28 @@ pub(crate) fn open_beneath(
         Err(rustix::io::Errno::NOSYS)
     })
     .map_err(|err| match err {
+        rustix::io::Errno::XDEV if options.no_xdev => xdev_error(start, path, options),
         rustix::io::Errno::XDEV => errors::escape_attempt(),
         err => err.into(),
     })
 }
 
+/// With `RESOLVE_NO_XDEV`, `EXDEV` may mean either that the path crossed a
+/// mount point or that it led outside of `start`. Tell them apart by
+/// resolving the path again without `RESOLVE_NO_XDEV`, using `O_PATH` so that
+/// nothing is created or truncated.
+fn xdev_error(start: &fs::File, path: &Path, options: &OpenOptions) -> io::Error {
+    let mut oflags = OFlags::PATH | OFlags::CLOEXEC;
+    if options.follow == FollowSymlinks::No {
+        oflags |= OFlags::NOFOLLOW;
+    }
+    let resolve = resolve_flags(options) - ResolveFlags::NO_XDEV - ResolveFlags::CACHED;
+    match openat2(start, path, oflags, Mode::empty(), resolve) {
+        Err(rustix::io::Errno::XDEV) => errors::escape_attempt(),
+        _ => errors::crosses_devices(),
+    }
+}
+
 #[cfg(racy_asserts)]
 fn check_open(start: &fs::File, path: &Path, options: &OpenOptions, file: &fs::File) {
     let check = manually::open(
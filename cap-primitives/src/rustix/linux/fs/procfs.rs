@@ -8,10 +8,14 @@
 
 use crate::fs::OpenOptionsExt;
 use crate::fs::{
-    errors, open, read_link_unchecked, set_times_follow_unchecked, OpenOptions, SystemTimeSpec,
+    errors, open, read_link_unchecked, set_times_follow_unchecked, FollowSymlinks, OpenOptions,
+    SystemTimeSpec,
 };
-use io_lifetimes::{AsFd, AsFilelike};
-use rustix::fs::{chmodat, AtFlags, Mode, OFlags, RawMode};
+use io_lifetimes::{AsFd, AsFilelike, BorrowedFd};
+use rustix::fs::{
+    chmodat, fstat, fstatfs, openat, AtFlags, FileType, Mode, OFlags, RawMode, PROC_SUPER_MAGIC,
+};
+use rustix::io::Errno;
 use rustix::path::DecInt;
 use rustix_linux_procfs::proc_self_fd;
 use std::os::unix::fs::PermissionsExt;
@@ -80,3 +84,66 @@ pub(crate) fn set_times_through_proc_self_fd(
         mtime,
     )
 }
+
+/// Open `path` with `O_PATH`, for use with [`reopen_through_proc_self_fd`].
+pub(crate) fn open_o_path(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+) -> io::Result<fs::File> {
+    open(
+        start,
+        path,
+        OpenOptions::new()
+            .read(true)
+            .follow(follow)
+            .custom_flags(OFlags::PATH.bits() as i32),
+    )
+}
+
+/// Reopen `fd`, which may be an `O_PATH` file descriptor, with `access`
+/// (`O_RDONLY` or `O_WRONLY`), for syscalls such as `fgetxattr` which don't
+/// work on `O_PATH` file descriptors.
+///
+/// The open is relative to the `/proc/self/fd` handle from [`proc_self_fd`],
+/// which has been checked to be real `procfs`, so the only lookup is of the
+/// magic link itself, which leads directly to the file `fd` refers to. A
+/// symlink can't be opened this way, so this fails with `ELOOP` if `fd`
+/// refers to one.
+///
+/// This is a real open of the file, so callers should check the file's type
+/// first to avoid opening devices or FIFOs.
+pub(crate) fn reopen_through_proc_self_fd(
+    fd: BorrowedFd<'_>,
+    access: OFlags,
+) -> io::Result<fs::File> {
+    let reopened = openat(
+        proc_self_fd()?,
+        DecInt::from_fd(fd),
+        access | OFlags::NONBLOCK | OFlags::NOCTTY | OFlags::CLOEXEC,
+        Mode::empty(),
+    )?;
+    Ok(fs::File::from(reopened))
+}
+
+/// Open the `/proc/self/fdinfo/*` entry for `fd`.
+///
+/// `rustix_linux_procfs::proc_self_fdinfo_fd` rejects the `fdinfo` directory
+/// on kernels which make it world-readable, so this instead opens the entry
+/// relative to the verified `/proc/self/fd` handle from [`proc_self_fd`], and
+/// checks that the result is a regular file on `procfs`.
+pub(crate) fn proc_self_fdinfo(fd: BorrowedFd<'_>) -> io::Result<fs::File> {
+    let name = Path::new("../fdinfo").join(DecInt::from_fd(fd));
+    let fdinfo = openat(
+        proc_self_fd()?,
+        &name,
+        OFlags::RDONLY | OFlags::NOFOLLOW | OFlags::NOCTTY | OFlags::CLOEXEC,
+        Mode::empty(),
+    )?;
+    if fstatfs(&fdinfo)?.f_type != PROC_SUPER_MAGIC
+        || FileType::from_raw_mode(fstat(&fdinfo)?.st_mode) != FileType::RegularFile
+    {
+        return Err(Errno::NOTSUP.into());
+    }
+    Ok(fs::File::from(fdinfo))
+}
@@ -1,10 +1,15 @@
 //! Linux has an `O_PATH` flag which allows opening a file without necessary
 //! having read or write access to it; we can use that with `openat2` and
-//! `fstat` to perform a fast sandboxed `stat`.
+//! `fstat` to perform a fast sandboxed `stat`, and with `statx` to fetch
+//! extended metadata.
 
 use super::file_metadata::file_metadata;
-use crate::fs::{manually, open_beneath, FollowSymlinks, Metadata, OpenOptions};
-use rustix::fs::OFlags;
+use super::procfs::open_o_path;
+use crate::fs::{
+    manually, open_beneath, ExtendedMetadata, FollowSymlinks, ImplMetadataExt, Metadata,
+    OpenOptions,
+};
+use rustix::fs::{statx, AtFlags, OFlags, StatxAttributes, StatxFlags};
 use std::path::Path;
 use std::{fs, io};
 
@@ -54,3 +59,93 @@ pub(crate) fn stat_impl(
         },
     }
 }
+
+/// Like `stat_impl`, but use `RESOLVE_NO_XDEV` so that the lookup fails if it
+/// would cross a mount point. If that's not available, fallback to
+/// `manually::stat_no_xdev`.
+pub(crate) fn stat_no_xdev_impl(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+) -> io::Result<Metadata> {
+    use crate::fs::OpenOptionsExt;
+
+    let result = open_beneath(
+        start,
+        path,
+        OpenOptions::new()
+            .read(true)
+            .follow(follow)
+            .no_xdev(true)
+            .custom_flags(OFlags::PATH.bits() as i32),
+    );
+
+    match result {
+        Ok(file) => file_metadata(&file),
+        Err(err) => match rustix::io::Errno::from_io_error(&err) {
+            Some(rustix::io::Errno::NOSYS) => manually::stat_no_xdev(start, path, follow),
+            _ => Err(err),
+        },
+    }
+}
+
+/// Use `statx` to fetch the metadata that `stat` doesn't provide. `statx`
+/// doesn't support `RESOLVE_BENEATH`, so open the path with `O_PATH` first
+/// and then use `AT_EMPTY_PATH`.
+pub(crate) fn statx_impl(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+) -> io::Result<ExtendedMetadata> {
+    let file = open_o_path(start, path, follow)?;
+    file_statx_impl(&file)
+}
+
+/// Like `statx_impl`, but for an already-open file.
+pub(crate) fn file_statx_impl(file: &fs::File) -> io::Result<ExtendedMetadata> {
+    let mask = StatxFlags::BTIME | StatxFlags::MNT_ID | StatxFlags::DIOALIGN;
+    let statx = match statx(file, "", AtFlags::EMPTY_PATH, mask) {
+        Ok(statx) => statx,
+        // Before Linux 4.11 there is no `statx`, so none of the fields are
+        // available.
+        Err(rustix::io::Errno::NOSYS) => {
+            return Ok(ExtendedMetadata {
+                created: None,
+                mount_id: None,
+                dio_mem_align: None,
+                dio_offset_align: None,
+                immutable: None,
+                append_only: None,
+                encrypted: None,
+                verity: None,
+                dax: None,
+            })
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let has = |flag: StatxFlags| statx.stx_mask & flag.bits() != 0;
+    let attribute = |attr: StatxAttributes| {
+        if statx.stx_attributes_mask.contains(attr) {
+            Some(statx.stx_attributes.contains(attr))
+        } else {
+            None
+        }
+    };
+
+    Ok(ExtendedMetadata {
+        created: if has(StatxFlags::BTIME) {
+            ImplMetadataExt::from_rustix_statx(statx).created().ok()
+        } else {
+            None
+        },
+        mount_id: has(StatxFlags::MNT_ID).then_some(statx.stx_mnt_id),
+        dio_mem_align: has(StatxFlags::DIOALIGN).then_some(statx.stx_dio_mem_align),
+        dio_offset_align: has(StatxFlags::DIOALIGN).then_some(statx.stx_dio_offset_align),
+        immutable: attribute(StatxAttributes::IMMUTABLE),
+        append_only: attribute(StatxAttributes::APPEND),
+        encrypted: attribute(StatxAttributes::ENCRYPTED),
+        verity: attribute(StatxAttributes::VERITY),
+        dax: attribute(StatxAttributes::DAX),
+    })
+}
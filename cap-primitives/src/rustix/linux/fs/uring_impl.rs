@@ -0,0 +1,344 @@
+//! Sandboxed opens and file I/O using io_uring. Opens are submitted as
+//! `IORING_OP_OPENAT2` with the same `RESOLVE_BENEATH` flags that
+//! `open_beneath` uses, so the sandboxing guarantee is the same.
+//!
+//! If the kernel doesn't support `IORING_OP_OPENAT2`, or an individual open
+//! can't be completed by it, fall back to the regular sandboxed `open`.
+//!
+//! Submission queue entries refer to heap memory which every operation owns
+//! until all of its completions arrive. If the ring fails while entries may
+//! still be in flight, that memory is leaked rather than freed, since the
+//! kernel may still access it, and the ring is marked as poisoned.
+
+#![allow(unsafe_code)]
+
+use super::super::super::fs::compute_oflags;
+use super::open_impl::resolve_flags;
+use crate::fs::{errors, open, OpenOptions};
+use io_lifetimes::{AsFd, BorrowedFd};
+use io_uring::{cqueue, opcode, squeue, types, IoUring, Probe};
+use rustix::fs::{Mode, OFlags, RawMode};
+use rustix::io::Errno;
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::Path;
+use std::{fmt, fs, io, mem};
+
+/// How many times to retry an open that failed with `EAGAIN`, matching
+/// `open_beneath`.
+const OPEN_ATTEMPTS: usize = 4;
+
+pub(crate) struct UringInner {
+    ring: IoUring,
+    has_openat2: bool,
+    poisoned: bool,
+}
+
+impl UringInner {
+    pub(crate) fn new(entries: u32) -> io::Result<Self> {
+        let ring = IoUring::new(entries)?;
+
+        let mut probe = Probe::new();
+        let has_openat2 = ring.submitter().register_probe(&mut probe).is_ok()
+            && probe.is_supported(opcode::OpenAt2::CODE);
+
+        Ok(Self {
+            ring,
+            has_openat2,
+            poisoned: false,
+        })
+    }
+
+    pub(crate) fn open_many(
+        &mut self,
+        start: &fs::File,
+        paths: &[&Path],
+        options: &OpenOptions,
+    ) -> Vec<io::Result<fs::File>> {
+        let mut results: Vec<Option<io::Result<fs::File>>> = Vec::with_capacity(paths.len());
+        results.resize_with(paths.len(), || None);
+
+        // If the options can't be expressed as `open_how` flags, or the kernel
+        // can't do the opens for us, let `open` handle and diagnose them.
+        let how = match open_how(options) {
+            Ok(how) if self.has_openat2 => how,
+            _ => {
+                return paths
+                    .iter()
+                    .map(|path| open(start, path, options))
+                    .collect()
+            }
+        };
+
+        // `openat2` needs NUL-terminated paths, and they and the `open_how`
+        // need to stay alive until the opens complete.
+        let mut buffers = OpenBuffers {
+            paths: paths
+                .iter()
+                .map(|path| CString::new(path.as_os_str().as_bytes()).ok())
+                .collect(),
+            how: Box::new(how),
+        };
+
+        let mut pending = (0..paths.len())
+            .filter(|index| buffers.paths[*index].is_some())
+            .collect::<Vec<_>>();
+        let dirfd = types::Fd(start.as_raw_fd());
+
+        // `openat2` fails with `EAGAIN` if a rename happens anywhere on the
+        // host while it's running, so resubmit those a few times.
+        'attempts: for _ in 0..OPEN_ATTEMPTS {
+            if pending.is_empty() {
+                break;
+            }
+            let mut again = Vec::new();
+            let capacity = self.ring.params().sq_entries() as usize;
+            for chunk in pending.chunks(capacity) {
+                let entries = chunk
+                    .iter()
+                    .map(|index| {
+                        let path = buffers.paths[*index].as_ref().unwrap();
+                        opcode::OpenAt2::new(dirfd, path.as_ptr(), &*buffers.how)
+                            .build()
+                            .user_data(*index as u64)
+                    })
+                    .collect();
+                // If the ring itself failed, finish the rest with `open`.
+                let completions = match self.run(entries, buffers) {
+                    Ok((completions, returned)) => {
+                        buffers = returned;
+                        completions
+                    }
+                    Err(_) => break 'attempts,
+                };
+                for (index, result) in completions {
+                    let index = index as usize;
+                    let result = if result >= 0 {
+                        // SAFETY: A successful open returns a new file descriptor.
+                        Ok(unsafe { fs::File::from_raw_fd(result) })
+                    } else {
+                        match Errno::from_raw_os_error(-result) {
+                            // With `RESOLVE_CACHED`, `EAGAIN` means the
+                            // lookup couldn't be done from the cache, and
+                            // older kernels fail with `EINVAL`, as in
+                            // `open_beneath`.
+                            Errno::AGAIN | Errno::INVAL if options.cached => {
+                                Err(Errno::AGAIN.into())
+                            }
+                            Errno::AGAIN => {
+                                again.push(index);
+                                continue;
+                            }
+                            // As in `open_beneath`, `EPERM` may come from a
+                            // `seccomp` sandbox, and `ENOSYS` means `openat2`
+                            // is unavailable; use the fallback for those.
+                            Errno::PERM => continue,
+                            Errno::NOSYS => {
+                                self.has_openat2 = false;
+                                continue;
+                            }
+                            // With `RESOLVE_NO_XDEV`, `EXDEV` is ambiguous;
+                            // let `open` diagnose it.
+                            Errno::XDEV if options.no_xdev => continue,
+                            Errno::XDEV => Err(errors::escape_attempt()),
+                            err => Err(err.into()),
+                        }
+                    };
+                    results[index] = Some(result);
+                }
+            }
+            pending = again;
+        }
+
+        results
+            .into_iter()
+            .zip(paths)
+            .map(|(result, path)| result.unwrap_or_else(|| open(start, path, options)))
+            .collect()
+    }
+
+    pub(crate) fn read_at(
+        &mut self,
+        file: BorrowedFd<'_>,
+        buf: &mut [u8],
+        offset: u64,
+    ) -> io::Result<usize> {
+        // Read into a buffer we own, so that it can be leaked if the ring
+        // fails while the kernel may still write to it.
+        let len = buf.len().min(u32::MAX as usize);
+        let mut owned = vec![0_u8; len].into_boxed_slice();
+        let entry = opcode::Read::new(types::Fd(file.as_raw_fd()), owned.as_mut_ptr(), len as u32)
+            .offset(offset)
+            .build();
+        let (n, owned) = self.run_one(entry, owned)?;
+        buf[..n].copy_from_slice(&owned[..n]);
+        Ok(n)
+    }
+
+    pub(crate) fn write_at(
+        &mut self,
+        file: BorrowedFd<'_>,
+        buf: &[u8],
+        offset: u64,
+    ) -> io::Result<usize> {
+        // As in `read_at`, write from a buffer we own.
+        let len = buf.len().min(u32::MAX as usize);
+        let owned = Box::<[u8]>::from(&buf[..len]);
+        let entry = opcode::Write::new(types::Fd(file.as_raw_fd()), owned.as_ptr(), len as u32)
+            .offset(offset)
+            .build();
+        let (n, _) = self.run_one(entry, owned)?;
+        Ok(n)
+    }
+
+    /// Submit a single read or write and wait for it, retrying if it's
+    /// interrupted.
+    fn run_one<T>(&mut self, entry: squeue::Entry, in_flight: T) -> io::Result<(usize, T)> {
+        let mut in_flight = in_flight;
+        loop {
+            let (mut completions, returned) = self.run(vec![entry.clone()], in_flight)?;
+            in_flight = returned;
+            let (_, result) = completions.pop().unwrap();
+            if result >= 0 {
+                return Ok((result as usize, in_flight));
+            }
+            match Errno::from_raw_os_error(-result) {
+                Errno::INTR => continue,
+                err => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Submit `entries`, which must fit in the submission queue, and wait
+    /// for all of them to complete, returning their `user_data` and results.
+    ///
+    /// `in_flight` owns the heap memory the entries refer to, and is returned
+    /// once they've all completed. If the ring fails while any of them may
+    /// still be in flight, it's leaked instead, and the ring is poisoned.
+    fn run<T>(
+        &mut self,
+        entries: Vec<squeue::Entry>,
+        in_flight: T,
+    ) -> io::Result<(Vec<(u64, i32)>, T)> {
+        if self.poisoned {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "io_uring instance failed and is no longer usable",
+            ));
+        }
+
+        let count = entries.len();
+        {
+            let mut sq = self.ring.submission();
+            if sq.capacity() - sq.len() < count {
+                return Err(submission_queue_full());
+            }
+            for entry in &entries {
+                // SAFETY: The memory the entries refer to is owned by
+                // `in_flight`, which we only drop or return after all of
+                // their completions arrive.
+                if unsafe { sq.push(entry) }.is_err() {
+                    // Some entries may already be queued, so never enter the
+                    // ring again.
+                    drop(sq);
+                    return Err(self.poison(in_flight, submission_queue_full()));
+                }
+            }
+        }
+
+        let mut submitted = 0;
+        while submitted < count {
+            match self.ring.submit() {
+                Ok(n) => submitted += n,
+                Err(err) if is_transient(&err) => {}
+                // Some entries may be in flight and still refer to
+                // `in_flight`, and the rest are still in the submission queue.
+                Err(err) => return Err(self.poison(in_flight, err)),
+            }
+        }
+
+        let mut completions = Vec::with_capacity(count);
+        while completions.len() < count {
+            completions.extend(
+                self.ring
+                    .completion()
+                    .map(|entry: cqueue::Entry| (entry.user_data(), entry.result())),
+            );
+            if completions.len() < count {
+                match self.ring.submit_and_wait(count - completions.len()) {
+                    Ok(_) => {}
+                    Err(err) if is_transient(&err) => {}
+                    // As above, in-flight entries may still refer to
+                    // `in_flight`.
+                    Err(err) => return Err(self.poison(in_flight, err)),
+                }
+            }
+        }
+        Ok((completions, in_flight))
+    }
+
+    /// Mark the ring as unusable after a failure which may have left entries
+    /// in flight, leaking the memory they refer to, and return `err`.
+    fn poison<T>(&mut self, in_flight: T, err: io::Error) -> io::Error {
+        self.poisoned = true;
+        mem::forget(in_flight);
+        err
+    }
+}
+
+/// The memory `IORING_OP_OPENAT2` entries refer to.
+struct OpenBuffers {
+    paths: Vec<Option<CString>>,
+    how: Box<types::OpenHow>,
+}
+
+fn submission_queue_full() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "too many entries for the io_uring submission queue",
+    )
+}
+
+/// Compute the `open_how` for `openat2`, as `open_beneath` does.
+fn open_how(options: &OpenOptions) -> io::Result<types::OpenHow> {
+    let oflags = compute_oflags(options)?;
+
+    // Do two `contains` checks because `TMPFILE` may be represented with
+    // multiple flags and we need to ensure they're all set.
+    let mode = if oflags.contains(OFlags::CREATE) || oflags.contains(OFlags::TMPFILE) {
+        Mode::from_bits((options.ext.mode & 0o7777) as RawMode).unwrap()
+    } else {
+        Mode::empty()
+    };
+
+    Ok(types::OpenHow::new()
+        .flags(oflags.bits() as u64)
+        .mode(mode.bits() as u64)
+        .resolve(resolve_flags(options).bits()))
+}
+
+fn is_transient(err: &io::Error) -> bool {
+    matches!(
+        err.raw_os_error().map(Errno::from_raw_os_error),
+        Some(Errno::INTR | Errno::AGAIN | Errno::BUSY)
+    )
+}
+
+impl AsFd for UringInner {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        // SAFETY: The ring's file descriptor lives as long as `self.ring`.
+        unsafe { BorrowedFd::borrow_raw(self.ring.as_raw_fd()) }
+    }
+}
+
+impl fmt::Debug for UringInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Uring")
+            .field("fd", &self.ring.as_raw_fd())
+            .field("entries", &self.ring.params().sq_entries())
+            .field("openat2", &self.has_openat2)
+            .finish()
+    }
+}
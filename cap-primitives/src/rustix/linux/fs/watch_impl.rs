@@ -0,0 +1,278 @@
+//! Directory change notifications using inotify. `inotify_add_watch` has no
+//! `*at` form, and no form which takes a file descriptor, so as in
+//! `set_permissions_through_proc_self_fd`, we open the directory with `O_PATH`
+//! and then watch its `/proc/self/fd/*` magic link. Since that path is
+//! resolved from the process root, we then check, through the verified
+//! `/proc/self/fdinfo` entry for the inotify file descriptor, that the watch
+//! is on the directory we opened.
+
+use super::procfs::{open_o_path, proc_self_fdinfo};
+use crate::fs::{FollowSymlinks, WatchEvent};
+use io_lifetimes::{AsFd, BorrowedFd, OwnedFd};
+use rustix::event::{poll, PollFd, PollFlags};
+use rustix::fs::inotify::{self, CreateFlags, ReadFlags, WatchFlags};
+use rustix::fs::{fstat, major, minor};
+use rustix::io::Errno;
+use rustix::path::DecInt;
+use std::ffi::OsString;
+use std::io::Read;
+use std::os::unix::ffi::OsStringExt;
+use std::path::{Component, Path, PathBuf};
+use std::{fmt, fs, io};
+
+/// The size of the fixed part of a `struct inotify_event`.
+const EVENT_SIZE: usize = 16;
+
+/// Enough space for at least one event with a maximal file name.
+const BUF_SIZE: usize = 4096 + EVENT_SIZE + 256;
+
+pub(crate) struct WatcherInner {
+    inotify: OwnedFd,
+    wd: i32,
+    base: PathBuf,
+    buf: Vec<u8>,
+    offset: usize,
+    len: usize,
+    pending: Option<RawEvent>,
+    done: bool,
+}
+
+struct RawEvent {
+    wd: i32,
+    mask: ReadFlags,
+    cookie: u32,
+    name: Option<OsString>,
+}
+
+impl WatcherInner {
+    pub(crate) fn new(start: &fs::File, path: &Path) -> io::Result<Self> {
+        let dir = open_o_path(start, path, FollowSymlinks::Yes)?;
+
+        // This is an ambient path, resolved from the process root rather than
+        // from a verified `procfs` handle, and that's unavoidable, since
+        // `inotify_add_watch` only takes a path. A different filesystem
+        // mounted over `/proc` could make it name some other directory, so
+        // it isn't trusted: `check_watch` below confirms, through the
+        // verified `fdinfo` entry, that the watch is on `dir`, and fails
+        // otherwise.
+        let proc_path = Path::new("/proc/self/fd").join(DecInt::from_fd(&dir));
+
+        let inotify = inotify::init(CreateFlags::CLOEXEC)?;
+        let wd = inotify::add_watch(
+            &inotify,
+            proc_path,
+            WatchFlags::CREATE
+                | WatchFlags::DELETE
+                | WatchFlags::MODIFY
+                | WatchFlags::ATTRIB
+                | WatchFlags::MOVED_FROM
+                | WatchFlags::MOVED_TO
+                | WatchFlags::DELETE_SELF
+                | WatchFlags::MOVE_SELF
+                | WatchFlags::ONLYDIR,
+        )?;
+        check_watch(&inotify, wd, &dir)?;
+
+        // Report paths relative to `start`, without any `.` components.
+        let base = path
+            .components()
+            .filter(|component| *component != Component::CurDir)
+            .collect();
+
+        Ok(Self {
+            inotify,
+            wd,
+            base,
+            buf: vec![0; BUF_SIZE],
+            offset: 0,
+            len: 0,
+            pending: None,
+            done: false,
+        })
+    }
+
+    /// Read the next raw event. If `wait` is false and no event is
+    /// immediately available, return `None`.
+    fn next_raw(&mut self, wait: bool) -> io::Result<Option<RawEvent>> {
+        if let Some(event) = self.pending.take() {
+            return Ok(Some(event));
+        }
+
+        if self.offset >= self.len {
+            if !wait {
+                let mut fds = [PollFd::new(&self.inotify, PollFlags::IN)];
+                let timeout = rustix::time::Timespec {
+                    tv_sec: 0,
+                    tv_nsec: 0,
+                };
+                if poll(&mut fds, Some(&timeout))? == 0 {
+                    return Ok(None);
+                }
+            }
+            self.len = loop {
+                match rustix::io::read(&self.inotify, &mut self.buf[..]) {
+                    Ok(len) => break len,
+                    Err(Errno::INTR) => continue,
+                    Err(err) => return Err(err.into()),
+                }
+            };
+            self.offset = 0;
+        }
+
+        // Parse a `struct inotify_event` out of the buffer. The kernel never
+        // returns partial events.
+        let event = &self.buf[self.offset..self.len];
+        let field = |i: usize| u32::from_ne_bytes(event[i * 4..i * 4 + 4].try_into().unwrap());
+        let name_len = field(3) as usize;
+        let name = &event[EVENT_SIZE..EVENT_SIZE + name_len];
+        // The name is padded with NUL bytes.
+        let name = &name[..name.iter().position(|b| *b == 0).unwrap_or(name.len())];
+        self.offset += EVENT_SIZE + name_len;
+
+        Ok(Some(RawEvent {
+            wd: field(0) as i32,
+            mask: ReadFlags::from_bits_retain(field(1)),
+            cookie: field(2),
+            name: if name.is_empty() {
+                None
+            } else {
+                Some(OsString::from_vec(name.to_vec()))
+            },
+        }))
+    }
+
+    fn path(&self, name: OsString) -> PathBuf {
+        self.base.join(name)
+    }
+
+    pub(crate) fn next_event(&mut self) -> Option<io::Result<WatchEvent>> {
+        while !self.done {
+            let event = match self.next_raw(true) {
+                Ok(event) => event.unwrap(),
+                Err(err) => return Some(Err(err)),
+            };
+
+            if event.mask.contains(ReadFlags::QUEUE_OVERFLOW) {
+                return Some(Ok(WatchEvent::Overflow));
+            }
+            if event.wd != self.wd {
+                continue;
+            }
+
+            // Once the directory itself is removed, renamed, or unmounted, we
+            // can no longer say where its entries are, and it may no longer be
+            // within `start` at all, so stop reporting events.
+            if event.mask.intersects(
+                ReadFlags::DELETE_SELF
+                    | ReadFlags::MOVE_SELF
+                    | ReadFlags::UNMOUNT
+                    | ReadFlags::IGNORED,
+            ) {
+                self.done = true;
+                break;
+            }
+
+            let name = match event.name {
+                Some(name) => name,
+                None => continue,
+            };
+
+            if event.mask.contains(ReadFlags::CREATE) {
+                return Some(Ok(WatchEvent::Created(self.path(name))));
+            }
+            if event.mask.intersects(ReadFlags::MODIFY | ReadFlags::ATTRIB) {
+                return Some(Ok(WatchEvent::Modified(self.path(name))));
+            }
+            if event.mask.contains(ReadFlags::DELETE) {
+                return Some(Ok(WatchEvent::Removed(self.path(name))));
+            }
+            if event.mask.contains(ReadFlags::MOVED_TO) {
+                // A rename into the directory from elsewhere.
+                return Some(Ok(WatchEvent::Created(self.path(name))));
+            }
+            if event.mask.contains(ReadFlags::MOVED_FROM) {
+                // The kernel queues both halves of a rename within the
+                // directory together, so if the next event isn't the matching
+                // `MOVED_TO`, this was a rename out of the directory.
+                let next = match self.next_raw(false) {
+                    Ok(next) => next,
+                    Err(err) => return Some(Err(err)),
+                };
+                if let Some(next) = next {
+                    if next.wd == self.wd
+                        && next.cookie == event.cookie
+                        && next.mask.contains(ReadFlags::MOVED_TO)
+                    {
+                        if let Some(to) = next.name {
+                            return Some(Ok(WatchEvent::Renamed {
+                                from: self.path(name),
+                                to: self.path(to),
+                            }));
+                        }
+                    }
+                    self.pending = Some(next);
+                }
+                return Some(Ok(WatchEvent::Removed(self.path(name))));
+            }
+        }
+
+        None
+    }
+}
+
+/// Check that watch `wd` of `inotify` is on `dir`, by comparing the inode and
+/// device the kernel reports for it in `/proc/self/fdinfo` with `dir`'s.
+fn check_watch(inotify: &OwnedFd, wd: i32, dir: &fs::File) -> io::Result<()> {
+    let mut fdinfo = String::new();
+    proc_self_fdinfo(inotify.as_fd())?.read_to_string(&mut fdinfo)?;
+
+    // Lines look like "inotify wd:1 ino:1a2b sdev:800001 mask:...", with
+    // numbers in hex, and the device in the kernel's internal encoding, with
+    // the major number above the low 20 bits.
+    let prefix = format!("inotify wd:{:x} ", wd);
+    let watch = fdinfo
+        .lines()
+        .find_map(|line| line.strip_prefix(&prefix))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "inotify watch not found in fdinfo",
+            )
+        })?;
+    let field = |name: &str| {
+        watch
+            .split(' ')
+            .find_map(|field| field.strip_prefix(name))
+            .and_then(|value| u64::from_str_radix(value, 16).ok())
+    };
+
+    let stat = fstat(dir)?;
+    let expected = (stat.st_ino as u64, major(stat.st_dev), minor(stat.st_dev));
+    match (field("ino:"), field("sdev:")) {
+        (Some(ino), Some(sdev))
+            if (ino, (sdev >> 20) as u32, (sdev & 0xf_ffff) as u32) == expected =>
+        {
+            Ok(())
+        }
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "inotify watch is not on the opened directory",
+        )),
+    }
+}
+
+impl AsFd for WatcherInner {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inotify.as_fd()
+    }
+}
+
+impl fmt::Debug for WatcherInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WatcherInner")
+            .field("inotify", &self.inotify)
+            .field("base", &self.base)
+            .finish()
+    }
+}
@@ -0,0 +1,171 @@
+//! Extended attributes. Linux's xattr syscalls have no `*at` forms, and the
+//! `f*xattr` forms don't work on `O_PATH` file descriptors, so as in
+//! `set_permissions_through_proc_self_fd`, we open the file with `O_PATH`, and
+//! then reopen it through its `/proc/self/fd/*` magic link to use the
+//! `f*xattr` forms. Only regular files and directories are reopened; see
+//! `reopen_for_xattr`.
+
+use super::procfs::{open_o_path, reopen_through_proc_self_fd};
+use crate::fs::FollowSymlinks;
+use io_lifetimes::{AsFd, BorrowedFd};
+use rustix::fs::{fstat, FileType, OFlags, XattrFlags};
+use rustix::io::Errno;
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::OsStringExt;
+use std::path::Path;
+use std::{fs, io};
+
+pub(crate) fn get_xattr_impl(
+    start: &fs::File,
+    path: &Path,
+    name: &OsStr,
+    follow: FollowSymlinks,
+) -> io::Result<Option<Vec<u8>>> {
+    let file = open_for_xattr(start, path, follow)?;
+    optional(read_to_vec(|buf| rustix::fs::fgetxattr(&file, name, buf)))
+}
+
+pub(crate) fn set_xattr_impl(
+    start: &fs::File,
+    path: &Path,
+    name: &OsStr,
+    value: &[u8],
+    follow: FollowSymlinks,
+) -> io::Result<()> {
+    let file = open_for_xattr(start, path, follow)?;
+    Ok(rustix::fs::fsetxattr(
+        &file,
+        name,
+        value,
+        XattrFlags::empty(),
+    )?)
+}
+
+pub(crate) fn list_xattrs_impl(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+) -> io::Result<Vec<OsString>> {
+    let file = open_for_xattr(start, path, follow)?;
+    read_to_vec(|buf| rustix::fs::flistxattr(&file, buf)).map(split_names)
+}
+
+pub(crate) fn remove_xattr_impl(
+    start: &fs::File,
+    path: &Path,
+    name: &OsStr,
+    follow: FollowSymlinks,
+) -> io::Result<()> {
+    let file = open_for_xattr(start, path, follow)?;
+    Ok(rustix::fs::fremovexattr(&file, name)?)
+}
+
+pub(crate) fn get_file_xattr_impl(file: &fs::File, name: &OsStr) -> io::Result<Option<Vec<u8>>> {
+    let reopened = reopen_if_o_path(file)?;
+    let file = reopened.as_ref().unwrap_or(file);
+    optional(read_to_vec(|buf| rustix::fs::fgetxattr(file, name, buf)))
+}
+
+pub(crate) fn set_file_xattr_impl(file: &fs::File, name: &OsStr, value: &[u8]) -> io::Result<()> {
+    let reopened = reopen_if_o_path(file)?;
+    let file = reopened.as_ref().unwrap_or(file);
+    Ok(rustix::fs::fsetxattr(
+        file,
+        name,
+        value,
+        XattrFlags::empty(),
+    )?)
+}
+
+pub(crate) fn list_file_xattrs_impl(file: &fs::File) -> io::Result<Vec<OsString>> {
+    let reopened = reopen_if_o_path(file)?;
+    let file = reopened.as_ref().unwrap_or(file);
+    read_to_vec(|buf| rustix::fs::flistxattr(file, buf)).map(split_names)
+}
+
+pub(crate) fn remove_file_xattr_impl(file: &fs::File, name: &OsStr) -> io::Result<()> {
+    let reopened = reopen_if_o_path(file)?;
+    let file = reopened.as_ref().unwrap_or(file);
+    Ok(rustix::fs::fremovexattr(file, name)?)
+}
+
+/// Open `path` with `O_PATH`, and then reopen it so that the `f*xattr`
+/// functions can be used on it.
+fn open_for_xattr(start: &fs::File, path: &Path, follow: FollowSymlinks) -> io::Result<fs::File> {
+    let opath = open_o_path(start, path, follow)?;
+    reopen_for_xattr(opath.as_fd())
+}
+
+/// If `file` was opened with `O_PATH`, reopen it so that the `f*xattr`
+/// functions can be used on it.
+fn reopen_if_o_path(file: &fs::File) -> io::Result<Option<fs::File>> {
+    if rustix::fs::fcntl_getfl(file)?.contains(OFlags::PATH) {
+        reopen_for_xattr(file.as_fd()).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Reopen the `O_PATH` file descriptor `fd` through `/proc/self/fd`.
+///
+/// Unlike opening with `O_PATH`, reopening really opens the file, which for
+/// devices and FIFOs can have side effects, so only regular files and
+/// directories are reopened. Symlinks themselves can't be reopened at all,
+/// so their attributes can't be accessed, which is why `FollowSymlinks::No`
+/// on a symlink fails.
+///
+/// The attribute syscalls don't need the file to be readable, so if reading
+/// a regular file isn't permitted, reopen it for writing instead.
+fn reopen_for_xattr(fd: BorrowedFd<'_>) -> io::Result<fs::File> {
+    match FileType::from_raw_mode(fstat(fd)?.st_mode) {
+        FileType::Directory => reopen_through_proc_self_fd(fd, OFlags::RDONLY),
+        FileType::RegularFile => match reopen_through_proc_self_fd(fd, OFlags::RDONLY) {
+            Err(err) if Errno::from_io_error(&err) == Some(Errno::ACCESS) => {
+                reopen_through_proc_self_fd(fd, OFlags::WRONLY)
+            }
+            result => result,
+        },
+        FileType::Symlink => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "extended attributes of symlinks can't be accessed",
+        )),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "extended attributes can only be accessed on regular files and directories",
+        )),
+    }
+}
+
+/// Call `f` once to query the size of a value and again to read it, retrying
+/// if the value grows in between.
+fn read_to_vec(mut f: impl FnMut(&mut [u8]) -> rustix::io::Result<usize>) -> io::Result<Vec<u8>> {
+    loop {
+        let len = f(&mut [])?;
+        let mut buf = vec![0; len];
+        match f(&mut buf) {
+            Ok(len) => {
+                buf.truncate(len);
+                return Ok(buf);
+            }
+            Err(Errno::RANGE) => continue,
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// Map `ENODATA`, meaning that an attribute doesn't exist, to `None`.
+fn optional(result: io::Result<Vec<u8>>) -> io::Result<Option<Vec<u8>>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(err) if Errno::from_io_error(&err) == Some(Errno::NODATA) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Split a NUL-terminated list of attribute names.
+fn split_names(list: Vec<u8>) -> Vec<OsString> {
+    list.split(|byte| *byte == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| OsString::from_vec(name.to_vec()))
+        .collect()
+}
@@ -20,3 +20,13 @@ pub(crate) fn is_not_directory() -> io::Error {
 pub(crate) fn too_many_symlinks() -> io::Error {
     io::Error::from_raw_os_error(Foundation::ERROR_TOO_MANY_LINKS as i32)
 }
+
+#[cold]
+pub(crate) fn crosses_devices() -> io::Error {
+    io::Error::from_raw_os_error(Foundation::ERROR_NOT_SAME_DEVICE as i32)
+}
+
+#[cold]
+pub(crate) fn would_block() -> io::Error {
+    io::ErrorKind::WouldBlock.into()
+}
@@ -10,6 +10,11 @@ pub(crate) fn is_same_file(a: &fs::File, b: &fs::File) -> io::Result<bool> {
     Ok(a_metadata.is_same_file(&b_metadata))
 }
 
+/// Return an identifier for the volume containing `file`.
+pub(crate) fn device_id(file: &fs::File) -> io::Result<u64> {
+    Ok(winx::winapi_util::file::information(file)?.volume_serial_number())
+}
+
 /// Determine if `a` and `b` are metadata for the same inode on the same
 /// device.
 #[cfg(windows_by_handle)]
@@ -0,0 +1,93 @@
+#![allow(unsafe_code)]
+
+use crate::fs::LockKind;
+use std::os::windows::io::AsRawHandle;
+use std::{fs, io, mem};
+use windows_sys::Win32::Foundation::ERROR_LOCK_VIOLATION;
+use windows_sys::Win32::Storage::FileSystem::{
+    LockFileEx, UnlockFileEx, LOCKFILE_EXCLUSIVE_LOCK, LOCKFILE_FAIL_IMMEDIATELY,
+};
+use windows_sys::Win32::System::IO::OVERLAPPED;
+
+pub(crate) fn lock_impl(file: &fs::File, kind: LockKind, wait: bool) -> io::Result<bool> {
+    lock_range_impl(file, kind, 0, 0, wait)
+}
+
+pub(crate) fn unlock_impl(file: &fs::File) -> io::Result<()> {
+    unlock_range_impl(file, 0, 0)
+}
+
+pub(crate) fn lock_range_impl(
+    file: &fs::File,
+    kind: LockKind,
+    offset: u64,
+    len: u64,
+    wait: bool,
+) -> io::Result<bool> {
+    let mut flags = 0;
+    if kind == LockKind::Exclusive {
+        flags |= LOCKFILE_EXCLUSIVE_LOCK;
+    }
+    if !wait {
+        flags |= LOCKFILE_FAIL_IMMEDIATELY;
+    }
+    let len = whole_len(len);
+    let mut overlapped = overlapped(offset);
+    // SAFETY: `overlapped` outlives the call, and the handle is synchronous,
+    // so the call completes before returning.
+    let ok = unsafe {
+        LockFileEx(
+            file.as_raw_handle() as _,
+            flags,
+            0,
+            len as u32,
+            (len >> 32) as u32,
+            &mut overlapped,
+        )
+    };
+    if ok != 0 {
+        return Ok(true);
+    }
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(code) if !wait && code == ERROR_LOCK_VIOLATION as i32 => Ok(false),
+        _ => Err(err),
+    }
+}
+
+pub(crate) fn unlock_range_impl(file: &fs::File, offset: u64, len: u64) -> io::Result<()> {
+    let len = whole_len(len);
+    let mut overlapped = overlapped(offset);
+    // SAFETY: `overlapped` outlives the call.
+    let ok = unsafe {
+        UnlockFileEx(
+            file.as_raw_handle() as _,
+            0,
+            len as u32,
+            (len >> 32) as u32,
+            &mut overlapped,
+        )
+    };
+    if ok != 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// A `len` of 0 means the whole file, as with `fcntl` locks.
+fn whole_len(len: u64) -> u64 {
+    if len == 0 {
+        u64::MAX
+    } else {
+        len
+    }
+}
+
+fn overlapped(offset: u64) -> OVERLAPPED {
+    // SAFETY: `OVERLAPPED` is a plain C struct, for which all zeros is valid.
+    let mut overlapped: OVERLAPPED = unsafe { mem::zeroed() };
+    overlapped.Anonymous.Anonymous.Offset = offset as u32;
+    overlapped.Anonymous.Anonymous.OffsetHigh = (offset >> 32) as u32;
+    overlapped
+}
@@ -10,6 +10,7 @@ mod get_path;
 mod hard_link_unchecked;
 mod is_file_read_write_impl;
 mod is_same_file;
+mod lock_impl;
 mod metadata_ext;
 mod oflags;
 mod open_impl;
@@ -39,10 +40,12 @@ pub(crate) use crate::fs::{
     via_parent::hard_link as hard_link_impl,
     via_parent::create_dir as create_dir_impl,
     via_parent::rename as rename_impl,
+    via_parent::rename_with as rename_with_impl,
     via_parent::remove_dir as remove_dir_impl,
     via_parent::set_permissions as set_permissions_impl,
     via_parent::set_symlink_permissions as set_symlink_permissions_impl,
     manually::stat as stat_impl,
+    manually::stat_no_xdev as stat_no_xdev_impl,
     via_parent::symlink_dir as symlink_dir_impl,
     via_parent::symlink_file as symlink_file_impl,
     via_parent::remove_file as remove_file_impl,
@@ -58,6 +61,7 @@ pub(crate) use file_type_ext::*;
 pub(crate) use hard_link_unchecked::*;
 pub(crate) use is_file_read_write_impl::*;
 pub(crate) use is_same_file::*;
+pub(crate) use lock_impl::*;
 pub(crate) use metadata_ext::*;
 pub(crate) use open_impl::open_impl;
 pub(crate) use open_options_ext::*;
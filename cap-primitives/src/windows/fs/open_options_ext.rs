@@ -6,9 +6,9 @@ use std::ptr::null_mut;
 use windows_sys::Win32::Foundation::{ERROR_INVALID_PARAMETER, GENERIC_READ, GENERIC_WRITE};
 use windows_sys::Win32::Security::SECURITY_ATTRIBUTES;
 use windows_sys::Win32::Storage::FileSystem::{
-    CREATE_ALWAYS, CREATE_NEW, FILE_FLAG_OPEN_REPARSE_POINT, FILE_GENERIC_WRITE, FILE_SHARE_DELETE,
-    FILE_SHARE_READ, FILE_SHARE_WRITE, FILE_WRITE_DATA, OPEN_ALWAYS, OPEN_EXISTING,
-    SECURITY_SQOS_PRESENT, TRUNCATE_EXISTING,
+    CREATE_ALWAYS, CREATE_NEW, FILE_FLAG_OPEN_REPARSE_POINT, FILE_GENERIC_READ, FILE_GENERIC_WRITE,
+    FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE, FILE_WRITE_DATA, OPEN_ALWAYS,
+    OPEN_EXISTING, SECURITY_SQOS_PRESENT, TRUNCATE_EXISTING,
 };
 
 #[derive(Debug, Clone)]
@@ -60,6 +60,13 @@ impl ImplOpenOptionsExt {
         self.security_qos_flags = flags | SECURITY_SQOS_PRESENT;
         self
     }
+
+    /// Test whether a custom access mode grants anything beyond reading.
+    pub(crate) fn may_modify(&self) -> bool {
+        self.access_mode.map_or(false, |mode| {
+            mode & !(GENERIC_READ | FILE_GENERIC_READ) != 0
+        })
+    }
 }
 
 pub(crate) fn get_access_mode(options: &OpenOptions) -> io::Result<u32> {
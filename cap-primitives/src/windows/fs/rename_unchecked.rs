@@ -1,6 +1,11 @@
+#![allow(unsafe_code)]
+
 use super::get_path::concatenate;
+use crate::fs::RenameOptions;
+use std::os::windows::ffi::OsStrExt;
 use std::path::Path;
 use std::{fs, io};
+use windows_sys::Win32::Storage::FileSystem::MoveFileExW;
 
 /// *Unsandboxed* function similar to `rename`, but which does not perform
 /// sandboxing.
@@ -14,3 +19,36 @@ pub(crate) fn rename_unchecked(
     let new_full_path = concatenate(new_start, new_path)?;
     fs::rename(old_full_path, new_full_path)
 }
+
+/// *Unsandboxed* function similar to `rename_with`, but which does not
+/// perform sandboxing.
+pub(crate) fn rename_with_unchecked(
+    old_start: &fs::File,
+    old_path: &Path,
+    new_start: &fs::File,
+    new_path: &Path,
+    options: &RenameOptions,
+) -> io::Result<()> {
+    if options.exchange {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "exchanging entries is not supported on this platform",
+        ));
+    }
+    if !options.noreplace {
+        return rename_unchecked(old_start, old_path, new_start, new_path);
+    }
+
+    // Without `MOVEFILE_REPLACE_EXISTING`, `MoveFileExW` fails if the
+    // destination exists.
+    let old_full_path = to_wide(&concatenate(old_start, old_path)?);
+    let new_full_path = to_wide(&concatenate(new_start, new_path)?);
+    if unsafe { MoveFileExW(old_full_path.as_ptr(), new_full_path.as_ptr(), 0) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn to_wide(path: &Path) -> Vec<u16> {
+    path.as_os_str().encode_wide().chain(Some(0)).collect()
+}
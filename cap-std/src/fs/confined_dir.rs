@@ -0,0 +1,437 @@
+use crate::fs::{Dir, DirEntry, File, FileType, Metadata, OpenOptions, ReadDir, RenameOptions};
+use cap_primitives::fs::{
+    create_dir_no_xdev, open_dir_no_xdev, open_no_xdev, remove_dir_all_no_xdev, remove_dir_no_xdev,
+    remove_file_no_xdev, rename_with_no_xdev, stat_no_xdev, DirOptions, FollowSymlinks,
+};
+use io_lifetimes::AsFilelike;
+#[cfg(not(windows))]
+use rustix::fs::DirEntryExt;
+use std::ffi::OsString;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::{fmt, fs};
+
+/// A reference to an open directory which never crosses into a different
+/// filesystem.
+///
+/// This is like [`Dir`], except that resolving a path fails if it would
+/// descend into a mount point or otherwise leave the filesystem the directory
+/// is on. On Linux this uses `openat2`'s `RESOLVE_NO_XDEV` where available,
+/// and fails with `EXDEV`. Directories opened with [`ConfinedDir::open_dir`]
+/// or through [`ConfinedDirEntry::open_dir`] are confined to the same
+/// filesystem.
+///
+/// Unlike `Dir`, this type doesn't expose its underlying file descriptor or
+/// handle, since that would allow the confinement to be bypassed. For the same
+/// reason, directories can only be opened with [`ConfinedDir::open_dir`], and
+/// not as [`File`]s.
+///
+/// To create a `ConfinedDir`, use [`Dir::confine_to_mount`].
+pub struct ConfinedDir {
+    dir: Dir,
+}
+
+impl ConfinedDir {
+    #[inline]
+    pub(crate) fn new(dir: Dir) -> Self {
+        Self { dir }
+    }
+
+    /// Attempts to open a file in read-only mode.
+    ///
+    /// This corresponds to [`Dir::open`], and fails if `path` names a
+    /// directory.
+    #[inline]
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> io::Result<File> {
+        self.open_with(path, OpenOptions::new().read(true))
+    }
+
+    /// Opens a file at `path` with the options specified by `options`.
+    ///
+    /// This corresponds to [`Dir::open_with`], and fails if `path` names a
+    /// directory.
+    #[inline]
+    pub fn open_with<P: AsRef<Path>>(&self, path: P, options: &OpenOptions) -> io::Result<File> {
+        let file = open_no_xdev(
+            &self.dir.as_filelike_view::<fs::File>(),
+            path.as_ref(),
+            options,
+        )?;
+        Ok(File::from_std(file))
+    }
+
+    /// Attempts to open a directory, confined to the same filesystem as
+    /// `self`.
+    #[inline]
+    pub fn open_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<Self> {
+        let dir = open_dir_no_xdev(&self.dir.as_filelike_view::<fs::File>(), path.as_ref())?;
+        Ok(Self::new(Dir::from_std_file(dir)))
+    }
+
+    /// Creates a new, empty directory at the provided path.
+    ///
+    /// This corresponds to [`Dir::create_dir`].
+    #[inline]
+    pub fn create_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self._create_dir_one(path.as_ref(), &DirOptions::new())
+    }
+
+    /// Recursively create a directory and all of its parent components if they
+    /// are missing.
+    ///
+    /// This corresponds to [`Dir::create_dir_all`].
+    #[inline]
+    pub fn create_dir_all<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self._create_dir_all(path.as_ref(), &DirOptions::new())
+    }
+
+    fn _create_dir_one(&self, path: &Path, dir_options: &DirOptions) -> io::Result<()> {
+        create_dir_no_xdev(&self.dir.as_filelike_view::<fs::File>(), path, dir_options)
+    }
+
+    fn _create_dir_all(&self, path: &Path, dir_options: &DirOptions) -> io::Result<()> {
+        if path == Path::new("") {
+            return Ok(());
+        }
+
+        match self._create_dir_one(path, dir_options) {
+            Ok(()) => return Ok(()),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(_) if self.is_dir(path) => return Ok(()),
+            Err(e) => return Err(e),
+        }
+        match path.parent() {
+            Some(p) => self._create_dir_all(p, dir_options)?,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "failed to create whole tree",
+                ))
+            }
+        }
+        match self._create_dir_one(path, dir_options) {
+            Ok(()) => Ok(()),
+            Err(_) if self.is_dir(path) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Opens a file in write-only mode.
+    ///
+    /// This corresponds to [`Dir::create`].
+    #[inline]
+    pub fn create<P: AsRef<Path>>(&self, path: P) -> io::Result<File> {
+        self.open_with(
+            path,
+            OpenOptions::new().write(true).create(true).truncate(true),
+        )
+    }
+
+    /// Given a path, query the file system to get information about a file,
+    /// directory, etc.
+    ///
+    /// This corresponds to [`Dir::metadata`].
+    #[inline]
+    pub fn metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        stat_no_xdev(
+            &self.dir.as_filelike_view::<fs::File>(),
+            path.as_ref(),
+            FollowSymlinks::Yes,
+        )
+    }
+
+    /// Query the metadata about a file without following symlinks.
+    ///
+    /// This corresponds to [`Dir::symlink_metadata`].
+    #[inline]
+    pub fn symlink_metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        stat_no_xdev(
+            &self.dir.as_filelike_view::<fs::File>(),
+            path.as_ref(),
+            FollowSymlinks::No,
+        )
+    }
+
+    /// Queries metadata about the underlying directory.
+    ///
+    /// This corresponds to [`Dir::dir_metadata`].
+    #[inline]
+    pub fn dir_metadata(&self) -> io::Result<Metadata> {
+        self.dir.dir_metadata()
+    }
+
+    /// Returns an iterator over the entries within `self`.
+    #[inline]
+    pub fn entries(&self) -> io::Result<ConfinedReadDir> {
+        Ok(ConfinedReadDir {
+            inner: self.dir.entries()?,
+            dir: Arc::new(self.try_clone()?),
+        })
+    }
+
+    /// Returns an iterator over the entries within a directory.
+    ///
+    /// This corresponds to [`Dir::read_dir`].
+    #[inline]
+    pub fn read_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<ConfinedReadDir> {
+        let dir = self.open_dir(path)?;
+        Ok(ConfinedReadDir {
+            inner: dir.dir.entries()?,
+            dir: Arc::new(dir),
+        })
+    }
+
+    /// Read the entire contents of a file into a bytes vector.
+    ///
+    /// This corresponds to [`Dir::read`].
+    #[inline]
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.open(path)?.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Read the entire contents of a file into a string.
+    ///
+    /// This corresponds to [`Dir::read_to_string`].
+    #[inline]
+    pub fn read_to_string<P: AsRef<Path>>(&self, path: P) -> io::Result<String> {
+        let mut s = String::new();
+        self.open(path)?.read_to_string(&mut s)?;
+        Ok(s)
+    }
+
+    /// Write a slice as the entire contents of a file.
+    ///
+    /// This corresponds to [`Dir::write`].
+    #[inline]
+    pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(&self, path: P, contents: C) -> io::Result<()> {
+        let mut file = self.create(path)?;
+        file.write_all(contents.as_ref())
+    }
+
+    /// Removes an empty directory.
+    ///
+    /// This corresponds to [`Dir::remove_dir`].
+    #[inline]
+    pub fn remove_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        remove_dir_no_xdev(&self.dir.as_filelike_view::<fs::File>(), path.as_ref())
+    }
+
+    /// Removes a directory at this path, after removing all its contents. Use
+    /// carefully!
+    ///
+    /// This corresponds to [`Dir::remove_dir_all`], and fails without
+    /// removing anything on another filesystem if the tree contains a mount
+    /// point.
+    #[inline]
+    pub fn remove_dir_all<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        remove_dir_all_no_xdev(&self.dir.as_filelike_view::<fs::File>(), path.as_ref())
+    }
+
+    /// Removes a file from a filesystem.
+    ///
+    /// This corresponds to [`Dir::remove_file`].
+    #[inline]
+    pub fn remove_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        remove_file_no_xdev(&self.dir.as_filelike_view::<fs::File>(), path.as_ref())
+    }
+
+    /// Rename a file or directory to a new name, replacing the original file
+    /// if to already exists.
+    ///
+    /// This corresponds to [`Dir::rename`].
+    #[inline]
+    pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<()> {
+        self.rename_with(from, to_dir, to, &RenameOptions::new())
+    }
+
+    /// Rename a file or directory to a new name, with the behavior configured
+    /// by `options`.
+    ///
+    /// This corresponds to [`Dir::rename_with`].
+    #[inline]
+    pub fn rename_with<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &RenameOptions,
+    ) -> io::Result<()> {
+        rename_with_no_xdev(
+            &self.dir.as_filelike_view::<fs::File>(),
+            from.as_ref(),
+            &to_dir.dir.as_filelike_view::<fs::File>(),
+            to.as_ref(),
+            options,
+        )
+    }
+
+    /// Creates a new `ConfinedDir` instance that shares the same underlying
+    /// file handle as the existing `ConfinedDir` instance.
+    #[inline]
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self::new(self.dir.try_clone()?))
+    }
+
+    /// Returns `true` if the path points at an existing entity.
+    ///
+    /// This corresponds to [`Dir::exists`].
+    #[inline]
+    pub fn exists<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    /// Returns `true` if the path points at an existing entity.
+    ///
+    /// This corresponds to [`Dir::try_exists`].
+    #[inline]
+    pub fn try_exists<P: AsRef<Path>>(&self, path: P) -> io::Result<bool> {
+        match self.metadata(path) {
+            Ok(_) => Ok(true),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Returns `true` if the path exists on disk and is pointing at a regular
+    /// file.
+    ///
+    /// This corresponds to [`Dir::is_file`].
+    #[inline]
+    pub fn is_file<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.metadata(path).map(|m| m.is_file()).unwrap_or(false)
+    }
+
+    /// Checks if `path` is a directory.
+    ///
+    /// This corresponds to [`Dir::is_dir`].
+    #[inline]
+    pub fn is_dir<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.metadata(path).map(|m| m.is_dir()).unwrap_or(false)
+    }
+}
+
+impl fmt::Debug for ConfinedDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfinedDir")
+            .field("dir", &self.dir)
+            .finish()
+    }
+}
+
+/// Iterator over the entries in a [`ConfinedDir`].
+///
+/// This corresponds to [`ReadDir`].
+pub struct ConfinedReadDir {
+    inner: ReadDir,
+    dir: Arc<ConfinedDir>,
+}
+
+impl Iterator for ConfinedReadDir {
+    type Item = io::Result<ConfinedDirEntry>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let dir = &self.dir;
+        self.inner.next().map(|inner| {
+            inner.map(|inner| ConfinedDirEntry {
+                inner,
+                dir: Arc::clone(dir),
+            })
+        })
+    }
+}
+
+impl fmt::Debug for ConfinedReadDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+/// Entries returned by the [`ConfinedReadDir`] iterator.
+///
+/// This corresponds to [`DirEntry`]. Operations on an entry which is a mount
+/// point fail rather than crossing into the mounted filesystem.
+pub struct ConfinedDirEntry {
+    inner: DirEntry,
+    dir: Arc<ConfinedDir>,
+}
+
+impl ConfinedDirEntry {
+    /// Open the file for reading.
+    #[inline]
+    pub fn open(&self) -> io::Result<File> {
+        self.dir.open(self.inner.file_name())
+    }
+
+    /// Open the file with the given options.
+    #[inline]
+    pub fn open_with(&self, options: &OpenOptions) -> io::Result<File> {
+        self.dir.open_with(self.inner.file_name(), options)
+    }
+
+    /// Open the entry as a directory, confined to the same filesystem as the
+    /// directory it was read from.
+    #[inline]
+    pub fn open_dir(&self) -> io::Result<ConfinedDir> {
+        self.dir.open_dir(self.inner.file_name())
+    }
+
+    /// Removes the file from its filesystem.
+    #[inline]
+    pub fn remove_file(&self) -> io::Result<()> {
+        self.dir.remove_file(self.inner.file_name())
+    }
+
+    /// Removes the directory from its filesystem.
+    #[inline]
+    pub fn remove_dir(&self) -> io::Result<()> {
+        self.dir.remove_dir(self.inner.file_name())
+    }
+
+    /// Returns the metadata for the file that this entry points at.
+    ///
+    /// This corresponds to [`DirEntry::metadata`].
+    #[inline]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.dir.symlink_metadata(self.inner.file_name())
+    }
+
+    /// Returns the file type for the file that this entry points at.
+    ///
+    /// This corresponds to [`DirEntry::file_type`].
+    #[inline]
+    pub fn file_type(&self) -> io::Result<FileType> {
+        self.inner.file_type()
+    }
+
+    /// Returns the bare file name of this directory entry without any other
+    /// leading path component.
+    ///
+    /// This corresponds to [`DirEntry::file_name`].
+    #[inline]
+    pub fn file_name(&self) -> OsString {
+        self.inner.file_name()
+    }
+}
+
+#[cfg(not(windows))]
+impl DirEntryExt for ConfinedDirEntry {
+    #[inline]
+    fn ino(&self) -> u64 {
+        self.inner.ino()
+    }
+}
+
+impl fmt::Debug for ConfinedDirEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
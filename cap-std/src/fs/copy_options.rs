@@ -0,0 +1,66 @@
+/// Options and flags which can be used to configure how a file or directory
+/// tree is copied.
+///
+/// This is to [`Dir::copy_with`] and [`Dir::copy_dir_all`] what
+/// [`OpenOptions`] is to [`Dir::open_with`].
+///
+/// [`Dir::copy_with`]: crate::fs::Dir::copy_with
+/// [`Dir::copy_dir_all`]: crate::fs::Dir::copy_dir_all
+/// [`Dir::open_with`]: crate::fs::Dir::open_with
+/// [`OpenOptions`]: crate::fs::OpenOptions
+#[derive(Debug, Clone)]
+pub struct CopyOptions {
+    pub(crate) preserve_permissions: bool,
+    pub(crate) preserve_times: bool,
+    pub(crate) reflink: bool,
+}
+
+impl CopyOptions {
+    /// Creates a blank new set of options ready for configuration.
+    ///
+    /// All options are initially set to `false`.
+    #[allow(clippy::new_without_default)]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            preserve_permissions: false,
+            preserve_times: false,
+            reflink: false,
+        }
+    }
+
+    /// Sets the option for giving directories the permissions of the
+    /// directories they're copied from.
+    ///
+    /// Regular files always get the permissions of the files they're copied
+    /// from, as with [`Dir::copy`]. Directories' permissions are set after
+    /// their contents have been copied, so read-only directories can be
+    /// copied.
+    ///
+    /// [`Dir::copy`]: crate::fs::Dir::copy
+    #[inline]
+    pub fn preserve_permissions(&mut self, preserve_permissions: bool) -> &mut Self {
+        self.preserve_permissions = preserve_permissions;
+        self
+    }
+
+    /// Sets the option for giving files, directories, and symlinks the last
+    /// access and modification times of the entries they're copied from.
+    #[inline]
+    pub fn preserve_times(&mut self, preserve_times: bool) -> &mut Self {
+        self.preserve_times = preserve_times;
+        self
+    }
+
+    /// Sets the option for first trying to make regular files copy-on-write
+    /// clones of the files they're copied from, as with [`Dir::reflink`],
+    /// and falling back to copying their contents if the filesystem doesn't
+    /// support that.
+    ///
+    /// [`Dir::reflink`]: crate::fs::Dir::reflink
+    #[inline]
+    pub fn reflink(&mut self, reflink: bool) -> &mut Self {
+        self.reflink = reflink;
+        self
+    }
+}
@@ -1,6 +1,11 @@
+#[cfg(not(target_os = "wasi"))]
+use crate::fs::FileLock;
 #[cfg(target_os = "wasi")]
 use crate::fs::OpenOptionsExt;
-use crate::fs::{DirBuilder, File, Metadata, OpenOptions, ReadDir};
+use crate::fs::{
+    ConfinedDir, CopyOptions, DirBuilder, DirRights, File, Metadata, OpenOptions, ReadDir,
+    RestrictedDir, WalkDir,
+};
 #[cfg(feature = "fs_utf8")]
 use crate::fs_utf8::Dir as DirUtf8;
 #[cfg(unix)]
@@ -9,9 +14,10 @@ use crate::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
 use cap_primitives::fs::set_permissions;
 use cap_primitives::fs::{
     canonicalize, copy, create_dir, hard_link, open, open_ambient_dir, open_dir, open_parent_dir,
-    read_base_dir, read_dir, read_link, read_link_contents, remove_dir, remove_dir_all,
-    remove_file, remove_open_dir, remove_open_dir_all, rename, stat, DirOptions, FollowSymlinks,
-    Permissions,
+    read_base_dir, read_dir, read_link, read_link_contents, reflink, remove_dir, remove_dir_all,
+    remove_file, remove_open_dir, remove_open_dir_all, rename, rename_with, set_times,
+    set_times_nofollow, stat, DirOptions, FollowSymlinks, Permissions, RenameOptions,
+    SystemTimeSpec,
 };
 use cap_primitives::AmbientAuthority;
 use io_lifetimes::AsFilelike;
@@ -121,6 +127,30 @@ impl Dir {
         self.open_dir(path).map(DirUtf8::from_cap_std)
     }
 
+    /// Returns an attenuated handle for this directory which only permits
+    /// the operations allowed by `rights`.
+    ///
+    /// This does not correspond to anything in `std`. The returned
+    /// [`RestrictedDir`] refers to the same directory as `self`, and
+    /// directories opened through it inherit the same rights.
+    #[inline]
+    pub fn restrict(&self, rights: DirRights) -> io::Result<RestrictedDir> {
+        Ok(RestrictedDir::new(self.try_clone()?, rights))
+    }
+
+    /// Returns a handle for this directory which refuses to cross into a
+    /// different filesystem than the one it's on.
+    ///
+    /// This does not correspond to anything in `std`. Every operation on the
+    /// returned [`ConfinedDir`], including opening, querying metadata,
+    /// removing, renaming, and reading directories, fails rather than
+    /// traversing a mount point, and directories opened through it are
+    /// confined in the same way.
+    #[inline]
+    pub fn confine_to_mount(&self) -> io::Result<ConfinedDir> {
+        Ok(ConfinedDir::new(self.try_clone()?))
+    }
+
     /// Creates a new, empty directory at the provided path.
     ///
     /// This corresponds to [`std::fs::create_dir`], but only accesses paths
@@ -202,6 +232,20 @@ impl Dir {
         )
     }
 
+    /// Opens a file for reading and writing, creating it if it doesn't exist,
+    /// and acquires an exclusive advisory lock on it, blocking until it's
+    /// available.
+    ///
+    /// The lock is released when the returned [`FileLock`] is dropped. This
+    /// does not correspond to anything in `std`, but only accesses paths
+    /// relative to `self`.
+    #[cfg(not(target_os = "wasi"))]
+    pub fn lock_file<P: AsRef<Path>>(&self, path: P) -> io::Result<FileLock> {
+        let file = self.open_with(path, OpenOptions::new().read(true).write(true).create(true))?;
+        file.lock_exclusive()?;
+        Ok(FileLock::new(file))
+    }
+
     /// Returns the canonical form of a path with all intermediate components
     /// normalized and symbolic links resolved.
     ///
@@ -229,6 +273,184 @@ impl Dir {
         copy(&self.std_file, from.as_ref(), &to_dir.std_file, to.as_ref())
     }
 
+    /// Copies the contents of one file to another, as with [`Dir::copy`],
+    /// configured by `options`.
+    ///
+    /// With [`CopyOptions::reflink`], this first tries to make `to` a
+    /// copy-on-write clone of `from`. With [`CopyOptions::preserve_times`],
+    /// `to` gets the last access and modification times of `from`.
+    ///
+    /// This does not correspond to anything in `std`. It only accesses paths
+    /// relative to `self` and `to_dir`.
+    pub fn copy_with<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &CopyOptions,
+    ) -> io::Result<u64> {
+        let (from, to) = (from.as_ref(), to.as_ref());
+        let copied = if options.reflink {
+            match reflink(&self.std_file, from, &to_dir.std_file, to) {
+                Err(err) if err.kind() == io::ErrorKind::Unsupported => {
+                    self.copy(from, to_dir, to)?
+                }
+                otherwise => otherwise?,
+            }
+        } else {
+            self.copy(from, to_dir, to)?
+        };
+        if options.preserve_times {
+            let (atime, mtime) = times(&self.metadata(from)?);
+            set_times(&to_dir.std_file, to, atime, mtime)?;
+        }
+        Ok(copied)
+    }
+
+    /// Makes `to` a copy-on-write clone of the file at `from`, sharing its
+    /// storage until either is modified.
+    ///
+    /// As with [`Dir::copy`], `to` is created or truncated and gets the
+    /// permission bits of `from`; if cloning fails, `to` may be left empty.
+    /// If the platform, filesystem, or the particular files don't support
+    /// cloning, this fails with [`io::ErrorKind::Unsupported`], so that
+    /// callers can fall back to [`Dir::copy`].
+    ///
+    /// This does not correspond to anything in `std`. It only accesses paths
+    /// relative to `self` and `to_dir`. On success, the length of the file is
+    /// returned.
+    #[inline]
+    pub fn reflink<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<u64> {
+        reflink(&self.std_file, from.as_ref(), &to_dir.std_file, to.as_ref())
+    }
+
+    /// Recursively copies the directory tree at `from` to `to`.
+    ///
+    /// Regular files are copied as with [`Dir::copy`], including their
+    /// permission bits. Symlinks are copied as symlinks with the same
+    /// contents, and are never followed. `to` is created if it doesn't
+    /// already exist, and existing files within it are overwritten. Existing
+    /// files and symlinks where the source has a symlink are replaced, but an
+    /// existing directory there produces an error. Other file types, such as
+    /// FIFOs and device nodes, produce an error.
+    ///
+    /// This does not correspond to anything in `std`. It only accesses paths
+    /// relative to `self` and `to_dir`. On success, the total number of bytes
+    /// copied is returned.
+    pub fn copy_dir_all<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &CopyOptions,
+    ) -> io::Result<u64> {
+        let from = self.open_dir(from)?;
+        to_dir.create_dir_all(to.as_ref())?;
+        let to = to_dir.open_dir(to)?;
+        from._copy_dir_all(&to, options)
+    }
+
+    fn _copy_dir_all(&self, to: &Self, options: &CopyOptions) -> io::Result<u64> {
+        // If `to` is inside the tree being copied, don't copy it into itself.
+        #[cfg(not(windows))]
+        let to_id = {
+            use crate::fs::MetadataExt;
+            let metadata = to.dir_metadata()?;
+            (metadata.dev(), metadata.ino())
+        };
+
+        let mut copied = 0;
+        let mut dirs = vec![(PathBuf::from("."), self.dir_metadata()?)];
+        let mut walk = self.walk_dir(".")?.into_iter();
+        while let Some(entry) = walk.next() {
+            let entry = entry?;
+            let path = entry.rel_path();
+            let file_type = entry.file_type();
+            if file_type.is_dir() {
+                let metadata = entry.metadata()?;
+                #[cfg(not(windows))]
+                {
+                    use crate::fs::MetadataExt;
+                    if (metadata.dev(), metadata.ino()) == to_id {
+                        walk.skip_current_dir();
+                        continue;
+                    }
+                }
+                match to.create_dir(path) {
+                    Ok(()) => (),
+                    Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                        if !to.symlink_metadata(path)?.is_dir() {
+                            return Err(err);
+                        }
+                    }
+                    Err(err) => return Err(err),
+                }
+                // Set permissions and times after the contents are copied.
+                dirs.push((path.to_path_buf(), metadata));
+            } else if file_type.is_symlink() {
+                // Symlinks can't be overwritten in place, so replace any
+                // existing file or symlink, as copying a file would.
+                match self._copy_symlink(path, to) {
+                    Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                        if to.symlink_metadata(path)?.is_dir() {
+                            return Err(err);
+                        }
+                        to.remove_file(path)?;
+                        self._copy_symlink(path, to)?;
+                    }
+                    result => result?,
+                }
+                if options.preserve_times {
+                    let (atime, mtime) = times(&entry.metadata()?);
+                    set_times_nofollow(&to.std_file, path, atime, mtime)?;
+                }
+            } else if file_type.is_file() {
+                copied += self.copy_with(path, to, path, options)?;
+            } else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "copy_dir_all only supports directories, regular files, and symlinks",
+                ));
+            }
+        }
+
+        // Visit the directories in reverse, so that children are finished
+        // before their parents.
+        for (path, metadata) in dirs.into_iter().rev() {
+            #[cfg(not(target_os = "wasi"))]
+            if options.preserve_permissions {
+                to.set_permissions(&path, metadata.permissions())?;
+            }
+            if options.preserve_times {
+                let (atime, mtime) = times(&metadata);
+                set_times(&to.std_file, &path, atime, mtime)?;
+            }
+        }
+
+        Ok(copied)
+    }
+
+    #[cfg(not(windows))]
+    fn _copy_symlink(&self, path: &Path, to: &Self) -> io::Result<()> {
+        let contents = self.read_link_contents(path)?;
+        to.symlink_contents(contents, path)
+    }
+
+    #[cfg(windows)]
+    fn _copy_symlink(&self, path: &Path, to: &Self) -> io::Result<()> {
+        let contents = self.read_link(path)?;
+        if self.is_dir(path) {
+            to.symlink_dir(contents, path)
+        } else {
+            to.symlink_file(contents, path)
+        }
+    }
+
     /// Creates a new hard link on a filesystem.
     ///
     /// This corresponds to [`std::fs::hard_link`], but only accesses paths
@@ -291,6 +513,18 @@ impl Dir {
         read_dir(&self.std_file, path.as_ref()).map(|inner| ReadDir { inner })
     }
 
+    /// Returns a builder for a recursive walk over the directory tree at
+    /// `path`.
+    ///
+    /// This does not correspond to anything in `std`; see [`WalkDir`] for
+    /// details. The walk only accesses paths relative to `self`.
+    #[inline]
+    pub fn walk_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<WalkDir> {
+        let path = path.as_ref();
+        let root = self.open_dir(path)?;
+        Ok(WalkDir::new(root, path.to_path_buf()))
+    }
+
     /// Read the entire contents of a file into a bytes vector.
     ///
     /// This corresponds to [`std::fs::read`], but only accesses paths
@@ -397,6 +631,60 @@ impl Dir {
         rename(&self.std_file, from.as_ref(), &to_dir.std_file, to.as_ref())
     }
 
+    /// Rename a file or directory to a new name, failing if `to` already
+    /// exists.
+    ///
+    /// This does not correspond to anything in `std`. It is like
+    /// [`Dir::rename`], except that the check for an existing destination and
+    /// the rename happen atomically. It only accesses paths relative to
+    /// `self`.
+    #[inline]
+    pub fn rename_noreplace<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<()> {
+        self.rename_with(from, to_dir, to, RenameOptions::new().noreplace(true))
+    }
+
+    /// Atomically exchange two files or directories, which must both exist.
+    ///
+    /// This does not correspond to anything in `std`, and is not supported on
+    /// all platforms. It only accesses paths relative to `self`.
+    #[inline]
+    pub fn exchange<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        a: P,
+        b_dir: &Self,
+        b: Q,
+    ) -> io::Result<()> {
+        self.rename_with(a, b_dir, b, RenameOptions::new().exchange(true))
+    }
+
+    /// Rename a file or directory to a new name, with the behavior configured
+    /// by `options`.
+    ///
+    /// This does not correspond to anything in `std`. With no options set, it
+    /// is equivalent to [`Dir::rename`]. It only accesses paths relative to
+    /// `self`.
+    #[inline]
+    pub fn rename_with<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &RenameOptions,
+    ) -> io::Result<()> {
+        rename_with(
+            &self.std_file,
+            from.as_ref(),
+            &to_dir.std_file,
+            to.as_ref(),
+            options,
+        )
+    }
+
     /// Changes the permissions found on a file or a directory.
     ///
     /// This corresponds to [`std::fs::set_permissions`], but only accesses
@@ -896,6 +1184,14 @@ fn initial_buffer_size(file: &File) -> usize {
     file.metadata().map(|m| m.len() as usize + 1).unwrap_or(0)
 }
 
+/// Returns the access and modification times to copy from `metadata`.
+fn times(metadata: &Metadata) -> (Option<SystemTimeSpec>, Option<SystemTimeSpec>) {
+    (
+        metadata.accessed().ok().map(SystemTimeSpec::from),
+        metadata.modified().ok().map(SystemTimeSpec::from),
+    )
+}
+
 impl fmt::Debug for Dir {
     // Like libstd's version, but doesn't print the path.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -1,5 +1,11 @@
 use crate::fs::{Metadata, OpenOptions, Permissions};
-use cap_primitives::fs::{is_file_read_write, open_ambient};
+#[cfg(unix)]
+use cap_primitives::fs::set_file_owner;
+use cap_primitives::fs::{clone_range, is_file_read_write, open_ambient};
+#[cfg(not(target_os = "wasi"))]
+use cap_primitives::fs::{lock, unlock, LockKind};
+#[cfg(any(target_os = "linux", windows))]
+use cap_primitives::fs::{lock_range, unlock_range};
 use cap_primitives::AmbientAuthority;
 #[cfg(not(windows))]
 use io_extras::os::rustix::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
@@ -103,6 +109,158 @@ impl File {
             .set_permissions(permissions_into_std(&self.std, perm)?)
     }
 
+    /// Changes the owner and group of the underlying file. `None` leaves the
+    /// corresponding ID unchanged.
+    ///
+    /// This corresponds to [`std::os::unix::fs::fchown`].
+    ///
+    /// [`std::os::unix::fs::fchown`]: https://doc.rust-lang.org/std/os/unix/fs/fn.fchown.html
+    #[cfg(unix)]
+    #[inline]
+    pub fn set_owner(&self, owner: Option<u32>, group: Option<u32>) -> io::Result<()> {
+        set_file_owner(&self.std, owner, group)
+    }
+
+    /// Makes the `len` bytes at `dst_offset` in this file a copy-on-write
+    /// clone of the `len` bytes at `src_offset` in `src`.
+    ///
+    /// Offsets and lengths typically need to be multiples of the
+    /// filesystem's block size; `len` may be zero to clone to the end of
+    /// `src`. If the platform, filesystem, or the particular files don't
+    /// support cloning, this fails with [`io::ErrorKind::Unsupported`], so
+    /// that callers can fall back to copying.
+    ///
+    /// This does not correspond to anything in `std`. On Linux it uses the
+    /// `FICLONERANGE` ioctl.
+    #[inline]
+    pub fn clone_range_from(
+        &self,
+        src: &Self,
+        src_offset: u64,
+        len: u64,
+        dst_offset: u64,
+    ) -> io::Result<()> {
+        clone_range(&src.std, src_offset, len, &self.std, dst_offset)
+    }
+
+    /// Acquires a shared advisory lock on the file, blocking until it's
+    /// available.
+    ///
+    /// This corresponds to [`std::fs::File::lock_shared`] in newer versions of
+    /// Rust. On Unix-family platforms this uses `flock`, so the lock is shared
+    /// by all handles cloned from this one, and is released when they're all
+    /// closed.
+    ///
+    /// [`std::fs::File::lock_shared`]: https://doc.rust-lang.org/std/fs/struct.File.html#method.lock_shared
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        lock(&self.std, LockKind::Shared, true).map(|_| ())
+    }
+
+    /// Acquires an exclusive advisory lock on the file, blocking until it's
+    /// available.
+    ///
+    /// This corresponds to [`std::fs::File::lock`] in newer versions of Rust.
+    ///
+    /// [`std::fs::File::lock`]: https://doc.rust-lang.org/std/fs/struct.File.html#method.lock
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        lock(&self.std, LockKind::Exclusive, true).map(|_| ())
+    }
+
+    /// Attempts to acquire a shared advisory lock on the file, returning
+    /// `Ok(false)` if it's locked exclusively elsewhere.
+    ///
+    /// This corresponds to [`std::fs::File::try_lock_shared`] in newer
+    /// versions of Rust.
+    ///
+    /// [`std::fs::File::try_lock_shared`]: https://doc.rust-lang.org/std/fs/struct.File.html#method.try_lock_shared
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        lock(&self.std, LockKind::Shared, false)
+    }
+
+    /// Attempts to acquire an exclusive advisory lock on the file, returning
+    /// `Ok(false)` if it's locked elsewhere.
+    ///
+    /// This corresponds to [`std::fs::File::try_lock`] in newer versions of
+    /// Rust.
+    ///
+    /// [`std::fs::File::try_lock`]: https://doc.rust-lang.org/std/fs/struct.File.html#method.try_lock
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        lock(&self.std, LockKind::Exclusive, false)
+    }
+
+    /// Releases an advisory lock acquired with one of the `lock` methods.
+    ///
+    /// This corresponds to [`std::fs::File::unlock`] in newer versions of
+    /// Rust.
+    ///
+    /// [`std::fs::File::unlock`]: https://doc.rust-lang.org/std/fs/struct.File.html#method.unlock
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn unlock(&self) -> io::Result<()> {
+        unlock(&self.std)
+    }
+
+    /// Acquires a shared advisory lock on the `len` bytes of the file starting
+    /// at `offset`, blocking until it's available. A `len` of 0 extends the
+    /// range to the end of the file and beyond.
+    ///
+    /// On Linux this uses open file description locks (`F_OFD_SETLK`), which
+    /// are independent of the whole-file locks acquired with `flock`. On
+    /// Windows, whole-file and byte-range locks are the same kind of lock.
+    #[cfg(any(target_os = "linux", windows))]
+    #[inline]
+    pub fn lock_range_shared(&self, offset: u64, len: u64) -> io::Result<()> {
+        lock_range(&self.std, LockKind::Shared, offset, len, true).map(|_| ())
+    }
+
+    /// Acquires an exclusive advisory lock on the `len` bytes of the file
+    /// starting at `offset`, blocking until it's available.
+    ///
+    /// See [`File::lock_range_shared`] for details.
+    #[cfg(any(target_os = "linux", windows))]
+    #[inline]
+    pub fn lock_range_exclusive(&self, offset: u64, len: u64) -> io::Result<()> {
+        lock_range(&self.std, LockKind::Exclusive, offset, len, true).map(|_| ())
+    }
+
+    /// Attempts to acquire a shared advisory lock on the `len` bytes of the
+    /// file starting at `offset`, returning `Ok(false)` if any of them are
+    /// locked exclusively elsewhere.
+    ///
+    /// See [`File::lock_range_shared`] for details.
+    #[cfg(any(target_os = "linux", windows))]
+    #[inline]
+    pub fn try_lock_range_shared(&self, offset: u64, len: u64) -> io::Result<bool> {
+        lock_range(&self.std, LockKind::Shared, offset, len, false)
+    }
+
+    /// Attempts to acquire an exclusive advisory lock on the `len` bytes of
+    /// the file starting at `offset`, returning `Ok(false)` if any of them are
+    /// locked elsewhere.
+    ///
+    /// See [`File::lock_range_shared`] for details.
+    #[cfg(any(target_os = "linux", windows))]
+    #[inline]
+    pub fn try_lock_range_exclusive(&self, offset: u64, len: u64) -> io::Result<bool> {
+        lock_range(&self.std, LockKind::Exclusive, offset, len, false)
+    }
+
+    /// Releases an advisory lock on the `len` bytes of the file starting at
+    /// `offset`.
+    #[cfg(any(target_os = "linux", windows))]
+    #[inline]
+    pub fn unlock_range(&self, offset: u64, len: u64) -> io::Result<()> {
+        unlock_range(&self.std, offset, len)
+    }
+
     /// Constructs a new instance of `Self` in read-only mode by opening the
     /// given path as a file using the host process' ambient authority.
     ///
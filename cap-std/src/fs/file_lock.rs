@@ -0,0 +1,44 @@
+use crate::fs::File;
+use std::fmt;
+use std::ops::Deref;
+
+/// A file holding an exclusive advisory lock, which is released when this is
+/// dropped.
+///
+/// This is returned by [`Dir::lock_file`].
+///
+/// [`Dir::lock_file`]: crate::fs::Dir::lock_file
+pub struct FileLock {
+    file: File,
+}
+
+impl FileLock {
+    pub(crate) fn new(file: File) -> Self {
+        Self { file }
+    }
+}
+
+impl Deref for FileLock {
+    type Target = File;
+
+    #[inline]
+    fn deref(&self) -> &File {
+        &self.file
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // Closing the file would release the lock anyway, unless some other
+        // handle shares it, so release it explicitly.
+        let _ = self.file.unlock();
+    }
+}
+
+impl fmt::Debug for FileLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileLock")
+            .field("file", &self.file)
+            .finish()
+    }
+}
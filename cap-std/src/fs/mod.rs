@@ -22,18 +22,35 @@
 //!
 //! [`std::fs`'s free functions]: https://doc.rust-lang.org/std/fs/#functions
 
+mod confined_dir;
+mod copy_options;
 mod dir;
 mod dir_entry;
 mod file;
+#[cfg(not(target_os = "wasi"))]
+mod file_lock;
 mod read_dir;
+mod restricted_dir;
+mod restricted_file;
+mod walk_dir;
 
+pub use confined_dir::{ConfinedDir, ConfinedDirEntry, ConfinedReadDir};
+pub use copy_options::CopyOptions;
 pub use dir::Dir;
 pub use dir_entry::DirEntry;
 pub use file::File;
+#[cfg(not(target_os = "wasi"))]
+pub use file_lock::FileLock;
 pub use read_dir::ReadDir;
+pub use restricted_dir::{RestrictedDir, RestrictedDirEntry, RestrictedReadDir};
+pub use restricted_file::RestrictedFile;
+pub use walk_dir::{FilterEntry, Walk, WalkDir, WalkEntry};
 
 // Re-export types from `cap_primitives`.
-pub use cap_primitives::fs::{DirBuilder, FileType, Metadata, OpenOptions, Permissions};
+pub use cap_primitives::fs::{
+    DirBuilder, DirRights, FileType, FollowSymlinks, Metadata, OpenOptions, Permissions,
+    RenameOptions,
+};
 
 // Re-export conditional types from `cap_primitives`.
 #[cfg(any(unix, target_os = "vxworks", all(windows, windows_file_type_ext)))]
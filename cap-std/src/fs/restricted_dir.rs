@@ -0,0 +1,667 @@
+use crate::fs::{
+    Dir, DirEntry, DirRights, File, FileType, Metadata, OpenOptions, ReadDir, RenameOptions,
+    RestrictedFile,
+};
+#[cfg(not(target_os = "wasi"))]
+use crate::fs::{DirBuilder, Permissions};
+#[cfg(not(target_os = "wasi"))]
+use cap_primitives::fs::set_permissions_with_rights;
+#[cfg(not(windows))]
+use cap_primitives::fs::symlink_with_rights;
+use cap_primitives::fs::{
+    copy_with_rights, create_dir_with_rights, hard_link_with_rights, open_with_rights,
+    reflink_with_rights, remove_dir_all_with_rights, remove_dir_with_rights,
+    remove_file_with_rights, rename_with_rights, DirOptions,
+};
+#[cfg(windows)]
+use cap_primitives::fs::{symlink_dir_with_rights, symlink_file_with_rights};
+use io_lifetimes::AsFilelike;
+#[cfg(not(windows))]
+use rustix::fs::DirEntryExt;
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::{fmt, fs};
+
+/// An attenuated reference to an open directory on a filesystem.
+///
+/// This is like [`Dir`], except that it only permits the operations allowed
+/// by its [`DirRights`]. Operations which aren't permitted fail with
+/// [`io::ErrorKind::PermissionDenied`] before accessing the filesystem.
+/// Directories opened with [`RestrictedDir::open_dir`] or through
+/// [`RestrictedDirEntry::open_dir`] inherit the same rights.
+///
+/// Unlike `Dir`, this type doesn't expose its underlying file descriptor or
+/// handle, since that would allow the restrictions to be bypassed. For the
+/// same reason, files opened through a `RestrictedDir` are
+/// [`RestrictedFile`]s, and directories can only be opened with
+/// [`RestrictedDir::open_dir`]. Without the `write` right, files can only be
+/// opened for reading.
+///
+/// To create a `RestrictedDir`, use [`Dir::restrict`].
+pub struct RestrictedDir {
+    dir: Dir,
+    rights: DirRights,
+}
+
+impl RestrictedDir {
+    #[inline]
+    pub(crate) fn new(dir: Dir, rights: DirRights) -> Self {
+        Self { dir, rights }
+    }
+
+    /// Returns the rights of this directory.
+    #[inline]
+    pub fn rights(&self) -> DirRights {
+        self.rights
+    }
+
+    /// Returns a new `RestrictedDir` for the same directory, with the rights
+    /// permitted by both `self` and `rights`.
+    #[inline]
+    pub fn restrict(&self, rights: DirRights) -> io::Result<Self> {
+        Ok(Self::new(
+            self.dir.try_clone()?,
+            self.rights.intersect(rights),
+        ))
+    }
+
+    /// Attempts to open a file in read-only mode.
+    ///
+    /// This corresponds to [`Dir::open`], and fails if `path` names a
+    /// directory.
+    #[inline]
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> io::Result<RestrictedFile> {
+        self.open_with(path, OpenOptions::new().read(true))
+    }
+
+    /// Opens a file at `path` with the options specified by `options`.
+    ///
+    /// This corresponds to [`Dir::open_with`], and fails if `options` would
+    /// create or write to a file and `self`'s rights don't permit it, or if
+    /// `path` names a directory.
+    #[inline]
+    pub fn open_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &OpenOptions,
+    ) -> io::Result<RestrictedFile> {
+        let file = open_with_rights(
+            &self.dir.as_filelike_view::<fs::File>(),
+            path.as_ref(),
+            options,
+            self.rights,
+        )?;
+        Ok(RestrictedFile::new(File::from_std(file), self.rights))
+    }
+
+    /// Attempts to open a directory, with the same rights as `self`.
+    #[inline]
+    pub fn open_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<Self> {
+        Ok(Self::new(self.dir.open_dir(path)?, self.rights))
+    }
+
+    /// Creates a new, empty directory at the provided path.
+    ///
+    /// This corresponds to [`Dir::create_dir`].
+    #[inline]
+    pub fn create_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self._create_dir_one(path.as_ref(), &DirOptions::new())
+    }
+
+    /// Recursively create a directory and all of its parent components if they
+    /// are missing.
+    ///
+    /// This corresponds to [`Dir::create_dir_all`].
+    #[inline]
+    pub fn create_dir_all<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self._create_dir_all(path.as_ref(), &DirOptions::new())
+    }
+
+    /// Creates the specified directory with the options configured in this
+    /// builder.
+    ///
+    /// This corresponds to [`Dir::create_dir_with`].
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn create_dir_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        dir_builder: &DirBuilder,
+    ) -> io::Result<()> {
+        let options = dir_builder.options();
+        if dir_builder.is_recursive() {
+            self._create_dir_all(path.as_ref(), options)
+        } else {
+            self._create_dir_one(path.as_ref(), options)
+        }
+    }
+
+    fn _create_dir_one(&self, path: &Path, dir_options: &DirOptions) -> io::Result<()> {
+        create_dir_with_rights(
+            &self.dir.as_filelike_view::<fs::File>(),
+            path,
+            dir_options,
+            self.rights,
+        )
+    }
+
+    fn _create_dir_all(&self, path: &Path, dir_options: &DirOptions) -> io::Result<()> {
+        if path == Path::new("") {
+            return Ok(());
+        }
+
+        match self._create_dir_one(path, dir_options) {
+            Ok(()) => return Ok(()),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(_) if self.is_dir(path) => return Ok(()),
+            Err(e) => return Err(e),
+        }
+        match path.parent() {
+            Some(p) => self._create_dir_all(p, dir_options)?,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "failed to create whole tree",
+                ))
+            }
+        }
+        match self._create_dir_one(path, dir_options) {
+            Ok(()) => Ok(()),
+            Err(_) if self.is_dir(path) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Opens a file in write-only mode.
+    ///
+    /// This corresponds to [`Dir::create`].
+    #[inline]
+    pub fn create<P: AsRef<Path>>(&self, path: P) -> io::Result<RestrictedFile> {
+        self.open_with(
+            path,
+            OpenOptions::new().write(true).create(true).truncate(true),
+        )
+    }
+
+    /// Returns the canonical form of a path with all intermediate components
+    /// normalized and symbolic links resolved.
+    ///
+    /// This corresponds to [`Dir::canonicalize`].
+    #[inline]
+    pub fn canonicalize<P: AsRef<Path>>(&self, path: P) -> io::Result<PathBuf> {
+        self.dir.canonicalize(path)
+    }
+
+    /// Copies the contents of one file to another.
+    ///
+    /// This corresponds to [`Dir::copy`], and fails unless `to_dir`'s rights
+    /// permit creating and writing files.
+    #[inline]
+    pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<u64> {
+        copy_with_rights(
+            &self.dir.as_filelike_view::<fs::File>(),
+            from.as_ref(),
+            &to_dir.dir.as_filelike_view::<fs::File>(),
+            to.as_ref(),
+            to_dir.rights,
+        )
+    }
+
+    /// Makes `to` a copy-on-write clone of the file at `from`.
+    ///
+    /// This corresponds to [`Dir::reflink`], and fails unless `to_dir`'s
+    /// rights permit creating and writing files.
+    #[inline]
+    pub fn reflink<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<u64> {
+        reflink_with_rights(
+            &self.dir.as_filelike_view::<fs::File>(),
+            from.as_ref(),
+            &to_dir.dir.as_filelike_view::<fs::File>(),
+            to.as_ref(),
+            to_dir.rights,
+        )
+    }
+
+    /// Creates a new hard link on a filesystem.
+    ///
+    /// This corresponds to [`Dir::hard_link`], and fails unless `dst_dir`'s
+    /// rights permit creating files.
+    #[inline]
+    pub fn hard_link<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        src: P,
+        dst_dir: &Self,
+        dst: Q,
+    ) -> io::Result<()> {
+        hard_link_with_rights(
+            &self.dir.as_filelike_view::<fs::File>(),
+            src.as_ref(),
+            &dst_dir.dir.as_filelike_view::<fs::File>(),
+            dst.as_ref(),
+            dst_dir.rights,
+        )
+    }
+
+    /// Given a path, query the file system to get information about a file,
+    /// directory, etc.
+    ///
+    /// This corresponds to [`Dir::metadata`].
+    #[inline]
+    pub fn metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.dir.metadata(path)
+    }
+
+    /// Queries metadata about the underlying directory.
+    ///
+    /// This corresponds to [`Dir::dir_metadata`].
+    #[inline]
+    pub fn dir_metadata(&self) -> io::Result<Metadata> {
+        self.dir.dir_metadata()
+    }
+
+    /// Returns an iterator over the entries within `self`.
+    #[inline]
+    pub fn entries(&self) -> io::Result<RestrictedReadDir> {
+        Ok(RestrictedReadDir {
+            inner: self.dir.entries()?,
+            rights: self.rights,
+        })
+    }
+
+    /// Returns an iterator over the entries within a directory.
+    ///
+    /// This corresponds to [`Dir::read_dir`].
+    #[inline]
+    pub fn read_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<RestrictedReadDir> {
+        Ok(RestrictedReadDir {
+            inner: self.dir.read_dir(path)?,
+            rights: self.rights,
+        })
+    }
+
+    /// Read the entire contents of a file into a bytes vector.
+    ///
+    /// This corresponds to [`Dir::read`].
+    #[inline]
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> io::Result<Vec<u8>> {
+        self.dir.read(path)
+    }
+
+    /// Reads a symbolic link, returning the file that the link points to.
+    ///
+    /// This corresponds to [`Dir::read_link`].
+    #[inline]
+    pub fn read_link<P: AsRef<Path>>(&self, path: P) -> io::Result<PathBuf> {
+        self.dir.read_link(path)
+    }
+
+    /// Read the entire contents of a file into a string.
+    ///
+    /// This corresponds to [`Dir::read_to_string`].
+    #[inline]
+    pub fn read_to_string<P: AsRef<Path>>(&self, path: P) -> io::Result<String> {
+        self.dir.read_to_string(path)
+    }
+
+    /// Removes an empty directory.
+    ///
+    /// This corresponds to [`Dir::remove_dir`].
+    #[inline]
+    pub fn remove_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        remove_dir_with_rights(
+            &self.dir.as_filelike_view::<fs::File>(),
+            path.as_ref(),
+            self.rights,
+        )
+    }
+
+    /// Removes a directory at this path, after removing all its contents. Use
+    /// carefully!
+    ///
+    /// This corresponds to [`Dir::remove_dir_all`].
+    #[inline]
+    pub fn remove_dir_all<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        remove_dir_all_with_rights(
+            &self.dir.as_filelike_view::<fs::File>(),
+            path.as_ref(),
+            self.rights,
+        )
+    }
+
+    /// Removes a file from a filesystem.
+    ///
+    /// This corresponds to [`Dir::remove_file`].
+    #[inline]
+    pub fn remove_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        remove_file_with_rights(
+            &self.dir.as_filelike_view::<fs::File>(),
+            path.as_ref(),
+            self.rights,
+        )
+    }
+
+    /// Rename a file or directory to a new name, replacing the original file
+    /// if to already exists.
+    ///
+    /// This corresponds to [`Dir::rename`], and fails unless both `self`'s
+    /// and `to_dir`'s rights permit renaming. Since this may replace an
+    /// existing file, it also fails unless `to_dir`'s rights permit removing.
+    #[inline]
+    pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<()> {
+        self.rename_with(from, to_dir, to, &RenameOptions::new())
+    }
+
+    /// Rename a file or directory to a new name, failing if `to` already
+    /// exists.
+    ///
+    /// This corresponds to [`Dir::rename_noreplace`], and fails unless both
+    /// `self`'s and `to_dir`'s rights permit renaming.
+    #[inline]
+    pub fn rename_noreplace<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<()> {
+        self.rename_with(from, to_dir, to, RenameOptions::new().noreplace(true))
+    }
+
+    /// Atomically exchange two files or directories, which must both exist.
+    ///
+    /// This corresponds to [`Dir::exchange`], and fails unless both `self`'s
+    /// and `b_dir`'s rights permit renaming.
+    #[inline]
+    pub fn exchange<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        a: P,
+        b_dir: &Self,
+        b: Q,
+    ) -> io::Result<()> {
+        self.rename_with(a, b_dir, b, RenameOptions::new().exchange(true))
+    }
+
+    /// Rename a file or directory to a new name, with the behavior configured
+    /// by `options`.
+    ///
+    /// This corresponds to [`Dir::rename_with`], and fails unless both
+    /// `self`'s and `to_dir`'s rights permit renaming. Unless `options`
+    /// requests `noreplace` or `exchange`, it also fails unless `to_dir`'s
+    /// rights permit removing.
+    #[inline]
+    pub fn rename_with<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &RenameOptions,
+    ) -> io::Result<()> {
+        rename_with_rights(
+            &self.dir.as_filelike_view::<fs::File>(),
+            from.as_ref(),
+            self.rights,
+            &to_dir.dir.as_filelike_view::<fs::File>(),
+            to.as_ref(),
+            to_dir.rights,
+            options,
+        )
+    }
+
+    /// Changes the permissions found on a file or a directory.
+    ///
+    /// This corresponds to [`Dir::set_permissions`].
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn set_permissions<P: AsRef<Path>>(&self, path: P, perm: Permissions) -> io::Result<()> {
+        set_permissions_with_rights(
+            &self.dir.as_filelike_view::<fs::File>(),
+            path.as_ref(),
+            perm,
+            self.rights,
+        )
+    }
+
+    /// Query the metadata about a file without following symlinks.
+    ///
+    /// This corresponds to [`Dir::symlink_metadata`].
+    #[inline]
+    pub fn symlink_metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.dir.symlink_metadata(path)
+    }
+
+    /// Write a slice as the entire contents of a file.
+    ///
+    /// This corresponds to [`Dir::write`].
+    #[inline]
+    pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(&self, path: P, contents: C) -> io::Result<()> {
+        let mut file = self.create(path)?;
+        file.write_all(contents.as_ref())
+    }
+
+    /// Creates a new symbolic link on a filesystem.
+    ///
+    /// This corresponds to [`Dir::symlink`].
+    #[cfg(not(windows))]
+    #[inline]
+    pub fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(&self, original: P, link: Q) -> io::Result<()> {
+        symlink_with_rights(
+            original.as_ref(),
+            &self.dir.as_filelike_view::<fs::File>(),
+            link.as_ref(),
+            self.rights,
+        )
+    }
+
+    /// Creates a new file symbolic link on a filesystem.
+    ///
+    /// This corresponds to [`Dir::symlink_file`].
+    #[cfg(windows)]
+    #[inline]
+    pub fn symlink_file<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        original: P,
+        link: Q,
+    ) -> io::Result<()> {
+        symlink_file_with_rights(
+            original.as_ref(),
+            &self.dir.as_filelike_view::<fs::File>(),
+            link.as_ref(),
+            self.rights,
+        )
+    }
+
+    /// Creates a new directory symlink on a filesystem.
+    ///
+    /// This corresponds to [`Dir::symlink_dir`].
+    #[cfg(windows)]
+    #[inline]
+    pub fn symlink_dir<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        original: P,
+        link: Q,
+    ) -> io::Result<()> {
+        symlink_dir_with_rights(
+            original.as_ref(),
+            &self.dir.as_filelike_view::<fs::File>(),
+            link.as_ref(),
+            self.rights,
+        )
+    }
+
+    /// Creates a new `RestrictedDir` instance that shares the same underlying
+    /// file handle as the existing `RestrictedDir` instance, with the same
+    /// rights.
+    #[inline]
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self::new(self.dir.try_clone()?, self.rights))
+    }
+
+    /// Returns `true` if the path points at an existing entity.
+    ///
+    /// This corresponds to [`Dir::exists`].
+    #[inline]
+    pub fn exists<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.dir.exists(path)
+    }
+
+    /// Returns `true` if the path points at an existing entity.
+    ///
+    /// This corresponds to [`Dir::try_exists`].
+    #[inline]
+    pub fn try_exists<P: AsRef<Path>>(&self, path: P) -> io::Result<bool> {
+        self.dir.try_exists(path)
+    }
+
+    /// Returns `true` if the path exists on disk and is pointing at a regular
+    /// file.
+    ///
+    /// This corresponds to [`Dir::is_file`].
+    #[inline]
+    pub fn is_file<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.dir.is_file(path)
+    }
+
+    /// Checks if `path` is a directory.
+    ///
+    /// This corresponds to [`Dir::is_dir`].
+    #[inline]
+    pub fn is_dir<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.dir.is_dir(path)
+    }
+}
+
+impl fmt::Debug for RestrictedDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RestrictedDir")
+            .field("dir", &self.dir)
+            .field("rights", &self.rights)
+            .finish()
+    }
+}
+
+/// Iterator over the entries in a [`RestrictedDir`].
+///
+/// This corresponds to [`ReadDir`].
+pub struct RestrictedReadDir {
+    inner: ReadDir,
+    rights: DirRights,
+}
+
+impl Iterator for RestrictedReadDir {
+    type Item = io::Result<RestrictedDirEntry>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let rights = self.rights;
+        self.inner
+            .next()
+            .map(|inner| inner.map(|inner| RestrictedDirEntry { inner, rights }))
+    }
+}
+
+impl fmt::Debug for RestrictedReadDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+/// Entries returned by the [`RestrictedReadDir`] iterator.
+///
+/// This corresponds to [`DirEntry`], with the rights of the [`RestrictedDir`]
+/// it was read from.
+pub struct RestrictedDirEntry {
+    inner: DirEntry,
+    rights: DirRights,
+}
+
+impl RestrictedDirEntry {
+    /// Open the file for reading.
+    ///
+    /// This fails if the entry is a directory.
+    #[inline]
+    pub fn open(&self) -> io::Result<RestrictedFile> {
+        self.open_with(OpenOptions::new().read(true))
+    }
+
+    /// Open the file with the given options.
+    ///
+    /// This fails if the entry is a directory.
+    #[inline]
+    pub fn open_with(&self, options: &OpenOptions) -> io::Result<RestrictedFile> {
+        let file = self.inner.inner.open_with_rights(options, self.rights)?;
+        Ok(RestrictedFile::new(File::from_std(file), self.rights))
+    }
+
+    /// Open the entry as a directory, with the same rights as the directory
+    /// it was read from.
+    #[inline]
+    pub fn open_dir(&self) -> io::Result<RestrictedDir> {
+        Ok(RestrictedDir::new(self.inner.open_dir()?, self.rights))
+    }
+
+    /// Removes the file from its filesystem.
+    #[inline]
+    pub fn remove_file(&self) -> io::Result<()> {
+        self.inner.inner.remove_file_with_rights(self.rights)
+    }
+
+    /// Removes the directory from its filesystem.
+    #[inline]
+    pub fn remove_dir(&self) -> io::Result<()> {
+        self.inner.inner.remove_dir_with_rights(self.rights)
+    }
+
+    /// Returns the metadata for the file that this entry points at.
+    ///
+    /// This corresponds to [`DirEntry::metadata`].
+    #[inline]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.inner.metadata()
+    }
+
+    /// Returns the file type for the file that this entry points at.
+    ///
+    /// This corresponds to [`DirEntry::file_type`].
+    #[inline]
+    pub fn file_type(&self) -> io::Result<FileType> {
+        self.inner.file_type()
+    }
+
+    /// Returns the bare file name of this directory entry without any other
+    /// leading path component.
+    ///
+    /// This corresponds to [`DirEntry::file_name`].
+    #[inline]
+    pub fn file_name(&self) -> OsString {
+        self.inner.file_name()
+    }
+}
+
+#[cfg(not(windows))]
+impl DirEntryExt for RestrictedDirEntry {
+    #[inline]
+    fn ino(&self) -> u64 {
+        self.inner.ino()
+    }
+}
+
+impl fmt::Debug for RestrictedDirEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
@@ -0,0 +1,208 @@
+use crate::fs::{DirRights, File, Metadata, Permissions};
+use std::fmt;
+use std::io::{self, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write};
+
+/// A file opened through a [`RestrictedDir`].
+///
+/// This is like [`File`], except that operations which change the file's
+/// metadata are only permitted as allowed by the [`DirRights`] of the
+/// directory it was opened through.
+///
+/// Unlike `File`, this type doesn't expose its underlying file descriptor or
+/// handle, since the operating system permits changing a file's permissions,
+/// owner, timestamps, and extended attributes through any handle to it, which
+/// would allow the restrictions to be bypassed.
+///
+/// [`RestrictedDir`]: crate::fs::RestrictedDir
+pub struct RestrictedFile {
+    file: File,
+    rights: DirRights,
+}
+
+impl RestrictedFile {
+    #[inline]
+    pub(crate) fn new(file: File, rights: DirRights) -> Self {
+        Self { file, rights }
+    }
+
+    /// Returns the rights of the directory this file was opened through.
+    #[inline]
+    pub fn rights(&self) -> DirRights {
+        self.rights
+    }
+
+    /// Attempts to sync all OS-internal metadata to disk.
+    ///
+    /// This corresponds to [`File::sync_all`].
+    #[inline]
+    pub fn sync_all(&self) -> io::Result<()> {
+        self.file.sync_all()
+    }
+
+    /// This function is similar to `sync_all`, except that it may not
+    /// synchronize file metadata to a filesystem.
+    ///
+    /// This corresponds to [`File::sync_data`].
+    #[inline]
+    pub fn sync_data(&self) -> io::Result<()> {
+        self.file.sync_data()
+    }
+
+    /// Truncates or extends the underlying file, updating the size of this
+    /// file to become size.
+    ///
+    /// This corresponds to [`File::set_len`], and fails unless `self`'s
+    /// rights permit writing.
+    #[inline]
+    pub fn set_len(&self, size: u64) -> io::Result<()> {
+        self.rights.check_write()?;
+        self.file.set_len(size)
+    }
+
+    /// Queries metadata about the underlying file.
+    ///
+    /// This corresponds to [`File::metadata`].
+    #[inline]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.file.metadata()
+    }
+
+    /// Creates a new `RestrictedFile` instance that shares the same
+    /// underlying file handle as the existing `RestrictedFile` instance, with
+    /// the same rights.
+    #[inline]
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self::new(self.file.try_clone()?, self.rights))
+    }
+
+    /// Changes the permissions on the underlying file.
+    ///
+    /// This corresponds to [`File::set_permissions`], and fails unless
+    /// `self`'s rights permit setting permissions.
+    #[inline]
+    pub fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
+        self.rights.check_set_permissions()?;
+        self.file.set_permissions(perm)
+    }
+}
+
+impl Read for RestrictedFile {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.file.read_vectored(bufs)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.file.read_to_end(buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.file.read_to_string(buf)
+    }
+}
+
+impl Read for &RestrictedFile {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&mut &self.file).read(buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        (&mut &self.file).read_vectored(bufs)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        (&mut &self.file).read_to_end(buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        (&mut &self.file).read_to_string(buf)
+    }
+}
+
+impl Write for RestrictedFile {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.file.write_vectored(bufs)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.file.write_all(buf)
+    }
+}
+
+impl Write for &RestrictedFile {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&mut &self.file).write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        (&mut &self.file).flush()
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        (&mut &self.file).write_vectored(bufs)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        (&mut &self.file).write_all(buf)
+    }
+}
+
+impl Seek for RestrictedFile {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.file.seek(pos)
+    }
+
+    #[inline]
+    fn stream_position(&mut self) -> io::Result<u64> {
+        self.file.stream_position()
+    }
+}
+
+impl Seek for &RestrictedFile {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        (&mut &self.file).seek(pos)
+    }
+
+    #[inline]
+    fn stream_position(&mut self) -> io::Result<u64> {
+        (&mut &self.file).stream_position()
+    }
+}
+
+impl fmt::Debug for RestrictedFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RestrictedFile")
+            .field("file", &self.file)
+            .field("rights", &self.rights)
+            .finish()
+    }
+}
@@ -0,0 +1,576 @@
+use crate::fs::{Dir, DirEntry, FileType, FollowSymlinks, Metadata, ReadDir};
+use std::cmp::Ordering;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::{fmt, io, vec};
+
+/// A builder for a recursive walk over a directory tree.
+///
+/// This is created by [`Dir::walk_dir`]. It does not correspond to anything
+/// in `std`; it's modeled after the [`walkdir`] crate.
+///
+/// The walk is confined to the directory it was created for. Directories are
+/// descended into through the entries that name them, and when symlinks are
+/// followed, they're resolved relative to the starting directory with the
+/// same sandboxing as [`Dir::open_dir`], so a symlink which leads outside of
+/// the starting directory produces an error rather than being followed.
+///
+/// The starting directory itself is not yielded, since it has no `DirEntry`.
+/// Entries directly inside it are at depth 1.
+///
+/// [`walkdir`]: https://docs.rs/walkdir/latest/walkdir/struct.WalkDir.html
+pub struct WalkDir {
+    root: Dir,
+    start: PathBuf,
+    opts: WalkDirOptions,
+}
+
+type Sorter = Box<dyn FnMut(&DirEntry, &DirEntry) -> Ordering + Send + Sync + 'static>;
+
+struct WalkDirOptions {
+    min_depth: usize,
+    max_depth: usize,
+    follow: FollowSymlinks,
+    contents_first: bool,
+    sorter: Option<Sorter>,
+}
+
+impl WalkDir {
+    pub(crate) fn new(root: Dir, start: PathBuf) -> Self {
+        Self {
+            root,
+            start,
+            opts: WalkDirOptions {
+                min_depth: 1,
+                max_depth: usize::MAX,
+                follow: FollowSymlinks::No,
+                contents_first: false,
+                sorter: None,
+            },
+        }
+    }
+
+    /// Set the minimum depth of entries yielded.
+    ///
+    /// Entries shallower than this are still descended into, but they aren't
+    /// yielded. The default is 1, meaning all entries are yielded.
+    #[inline]
+    pub fn min_depth(mut self, depth: usize) -> Self {
+        self.opts.min_depth = depth;
+        self
+    }
+
+    /// Set the maximum depth of entries yielded.
+    ///
+    /// Directories at this depth are yielded but not descended into, so this
+    /// also bounds the number of directory handles held open at once. The
+    /// default is `usize::MAX`.
+    #[inline]
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.opts.max_depth = depth;
+        self
+    }
+
+    /// Set whether symlinks are followed.
+    ///
+    /// When `FollowSymlinks::Yes` is used, symlinks are resolved relative to
+    /// the starting directory, and symlinks to directories are descended
+    /// into. A symlink which would lead to one of its own ancestors produces
+    /// an error instead of being descended into. The default is
+    /// `FollowSymlinks::No`, in which case symlinks are yielded as symlinks.
+    #[inline]
+    pub fn follow_symlinks(mut self, follow: FollowSymlinks) -> Self {
+        self.opts.follow = follow;
+        self
+    }
+
+    /// Yield a directory's contents before the directory itself.
+    ///
+    /// By default, directories are yielded before their contents (pre-order).
+    /// With this set, they're yielded after (post-order), which is what's
+    /// needed for operations such as removing a tree.
+    #[inline]
+    pub fn contents_first(mut self, yes: bool) -> Self {
+        self.opts.contents_first = yes;
+        self
+    }
+
+    /// Set a function for sorting the entries of each directory.
+    ///
+    /// By default, entries are yielded in the order the filesystem returns
+    /// them. When a sorting function is set, all the entries of a directory
+    /// are read before any of them are yielded.
+    #[inline]
+    pub fn sort_by<F>(mut self, cmp: F) -> Self
+    where
+        F: FnMut(&DirEntry, &DirEntry) -> Ordering + Send + Sync + 'static,
+    {
+        self.opts.sorter = Some(Box::new(cmp));
+        self
+    }
+
+    /// Sort the entries of each directory by file name.
+    #[inline]
+    pub fn sort_by_file_name(self) -> Self {
+        self.sort_by(|a, b| a.file_name().cmp(&b.file_name()))
+    }
+}
+
+impl IntoIterator for WalkDir {
+    type Item = io::Result<WalkEntry>;
+    type IntoIter = Walk;
+
+    fn into_iter(self) -> Walk {
+        Walk {
+            root: self.root,
+            start: self.start,
+            opts: self.opts,
+            stack: Vec::new(),
+            started: false,
+            descended: false,
+            pending: None,
+        }
+    }
+}
+
+impl fmt::Debug for WalkDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("WalkDir");
+        b.field("root", &self.root);
+        b.field("min_depth", &self.opts.min_depth);
+        b.field("max_depth", &self.opts.max_depth);
+        b.field("follow", &self.opts.follow);
+        b.field("contents_first", &self.opts.contents_first);
+        b.finish()
+    }
+}
+
+/// An iterator over the entries of a directory tree.
+///
+/// This is created by calling `into_iter` on a [`WalkDir`].
+///
+/// At most one directory handle is held open for each level of the tree
+/// between the starting directory and the current entry.
+pub struct Walk {
+    root: Dir,
+    start: PathBuf,
+    opts: WalkDirOptions,
+    stack: Vec<Level>,
+    started: bool,
+    descended: bool,
+    pending: Option<io::Result<WalkEntry>>,
+}
+
+/// A directory on the walk's stack.
+struct Level {
+    entries: LevelEntries,
+
+    /// The path of this directory, relative to the starting directory.
+    rel: PathBuf,
+
+    /// The canonical path of this directory, relative to the starting
+    /// directory, used to detect symlink loops.
+    canonical: PathBuf,
+
+    /// When `contents_first` is set, the entry to yield once this level is
+    /// exhausted.
+    deferred: Option<WalkEntry>,
+}
+
+enum LevelEntries {
+    Open(ReadDir),
+    Sorted(vec::IntoIter<io::Result<DirEntry>>),
+}
+
+impl Iterator for LevelEntries {
+    type Item = io::Result<DirEntry>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Open(read_dir) => read_dir.next(),
+            Self::Sorted(iter) => iter.next(),
+        }
+    }
+}
+
+impl Walk {
+    /// Skip the rest of the current directory.
+    ///
+    /// If the most recently yielded entry is a directory which is being
+    /// descended into, its contents are skipped. Otherwise, the rest of the
+    /// directory containing the most recently yielded entry is skipped.
+    pub fn skip_current_dir(&mut self) {
+        if let Some(level) = self.stack.pop() {
+            // When `contents_first` is set, the directory itself is still
+            // yielded.
+            if let Some(deferred) = level.deferred {
+                if deferred.depth >= self.opts.min_depth {
+                    self.pending = Some(Ok(deferred));
+                }
+            }
+        }
+        self.descended = false;
+    }
+
+    /// Yield only the entries for which `predicate` returns `true`.
+    ///
+    /// Unlike [`Iterator::filter`], when `predicate` returns `false` for a
+    /// directory, the directory is not descended into, so whole subtrees can
+    /// be pruned. When [`WalkDir::contents_first`] is set, a directory's
+    /// contents are yielded before the predicate sees the directory, so
+    /// pruning is only possible in the default order.
+    pub fn filter_entry<P>(self, predicate: P) -> FilterEntry<P>
+    where
+        P: FnMut(&WalkEntry) -> bool,
+    {
+        FilterEntry {
+            walk: self,
+            predicate,
+        }
+    }
+
+    /// If the most recently yielded entry is a directory which is being
+    /// descended into, skip its contents.
+    pub(crate) fn prune_last(&mut self) {
+        if self.descended {
+            self.stack.pop();
+            self.descended = false;
+        }
+    }
+
+    /// Test whether directories are yielded after their contents.
+    #[inline]
+    pub(crate) fn is_contents_first(&self) -> bool {
+        self.opts.contents_first
+    }
+
+    fn push(
+        &mut self,
+        dir: Dir,
+        rel: PathBuf,
+        canonical: PathBuf,
+        deferred: Option<WalkEntry>,
+    ) -> io::Result<()> {
+        let read_dir = dir.entries()?;
+        // Only hold onto the `ReadDir`'s handle; the `Dir` is dropped here.
+        drop(dir);
+        let entries = match &mut self.opts.sorter {
+            Some(sorter) => {
+                let mut entries = read_dir.collect::<Vec<_>>();
+                entries.sort_by(|a, b| match (a, b) {
+                    (Ok(a), Ok(b)) => sorter(a, b),
+                    (Err(_), Err(_)) => Ordering::Equal,
+                    (Ok(_), Err(_)) => Ordering::Greater,
+                    (Err(_), Ok(_)) => Ordering::Less,
+                });
+                LevelEntries::Sorted(entries.into_iter())
+            }
+            None => LevelEntries::Open(read_dir),
+        };
+        self.stack.push(Level {
+            entries,
+            rel,
+            canonical,
+            deferred,
+        });
+        Ok(())
+    }
+
+    /// Open the directory named by `entry` for descending into it, checking
+    /// for symlink loops if it was reached through a symlink.
+    fn open_child(&self, entry: &WalkEntry, canonical: &mut PathBuf) -> io::Result<Dir> {
+        if !entry.follow_link {
+            return entry.entry.open_dir();
+        }
+
+        *canonical = self.root.canonicalize(&entry.rel)?;
+        // `canonicalize` returns "." for the starting directory itself, which
+        // is pushed with an empty canonical path.
+        if *canonical == Path::new(".") {
+            canonical.clear();
+        }
+        if self.stack.iter().any(|level| level.canonical == *canonical) {
+            return Err(filesystem_loop());
+        }
+        self.root.open_dir(&entry.rel)
+    }
+
+    fn next_entry(&mut self) -> Option<io::Result<WalkEntry>> {
+        if let Some(pending) = self.pending.take() {
+            return Some(pending);
+        }
+
+        if !self.started {
+            self.started = true;
+            if self.opts.max_depth == 0 {
+                return None;
+            }
+            let root = match self.root.try_clone() {
+                Ok(root) => root,
+                Err(error) => return Some(Err(error)),
+            };
+            if let Err(error) = self.push(root, PathBuf::new(), PathBuf::new(), None) {
+                return Some(Err(error));
+            }
+        }
+
+        loop {
+            self.descended = false;
+            let depth = self.stack.len();
+            let level = self.stack.last_mut()?;
+            let dir_entry = match level.entries.next() {
+                Some(Ok(dir_entry)) => dir_entry,
+                Some(Err(error)) => return Some(Err(error)),
+                None => {
+                    let level = self.stack.pop().unwrap();
+                    match level.deferred {
+                        Some(deferred) if deferred.depth >= self.opts.min_depth => {
+                            return Some(Ok(deferred))
+                        }
+                        _ => continue,
+                    }
+                }
+            };
+
+            let rel = level.rel.join(dir_entry.file_name());
+            let mut canonical = level.canonical.join(dir_entry.file_name());
+            let entry = match self.make_entry(dir_entry, rel, depth) {
+                Ok(entry) => entry,
+                Err(error) => return Some(Err(error)),
+            };
+
+            if !entry.file_type.is_dir() || depth >= self.opts.max_depth {
+                if depth >= self.opts.min_depth {
+                    return Some(Ok(entry));
+                }
+                continue;
+            }
+
+            let result = self.open_child(&entry, &mut canonical).and_then(|dir| {
+                let rel = entry.rel.clone();
+                self.push(dir, rel, canonical, None)
+            });
+            if let Err(error) = result {
+                if depth >= self.opts.min_depth {
+                    self.pending = Some(Err(error));
+                    return Some(Ok(entry));
+                }
+                return Some(Err(error));
+            }
+
+            if self.opts.contents_first {
+                self.stack.last_mut().unwrap().deferred = Some(entry);
+                continue;
+            }
+            self.descended = true;
+            if depth >= self.opts.min_depth {
+                return Some(Ok(entry));
+            }
+        }
+    }
+
+    fn make_entry(&self, entry: DirEntry, rel: PathBuf, depth: usize) -> io::Result<WalkEntry> {
+        let mut file_type = entry.file_type()?;
+        let mut metadata = None;
+        let mut follow_link = false;
+        if file_type.is_symlink() && self.opts.follow == FollowSymlinks::Yes {
+            let target = self.root.metadata(&rel)?;
+            file_type = target.file_type();
+            metadata = Some(target);
+            follow_link = true;
+        }
+        Ok(WalkEntry {
+            path: self.start.join(&rel),
+            rel,
+            entry,
+            depth,
+            file_type,
+            metadata,
+            follow_link,
+        })
+    }
+}
+
+impl Iterator for Walk {
+    type Item = io::Result<WalkEntry>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_entry()
+    }
+}
+
+impl fmt::Debug for Walk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("Walk");
+        b.field("root", &self.root);
+        b.field("depth", &self.stack.len());
+        b.finish()
+    }
+}
+
+/// An iterator over a directory tree which prunes entries with a predicate.
+///
+/// This is created by [`Walk::filter_entry`].
+pub struct FilterEntry<P> {
+    walk: Walk,
+    predicate: P,
+}
+
+impl<P> FilterEntry<P>
+where
+    P: FnMut(&WalkEntry) -> bool,
+{
+    /// Skip the rest of the current directory.
+    ///
+    /// This corresponds to [`Walk::skip_current_dir`].
+    #[inline]
+    pub fn skip_current_dir(&mut self) {
+        self.walk.skip_current_dir()
+    }
+}
+
+impl<P> Iterator for FilterEntry<P>
+where
+    P: FnMut(&WalkEntry) -> bool,
+{
+    type Item = io::Result<WalkEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entry = match self.walk.next()? {
+                Ok(entry) => entry,
+                Err(error) => return Some(Err(error)),
+            };
+            if (self.predicate)(&entry) {
+                return Some(Ok(entry));
+            }
+            if !self.walk.is_contents_first() {
+                self.walk.prune_last();
+            }
+        }
+    }
+}
+
+impl<P> fmt::Debug for FilterEntry<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.walk.fmt(f)
+    }
+}
+
+/// An entry yielded by a [`Walk`].
+///
+/// This wraps the [`DirEntry`] for the entry, along with its path relative
+/// to the `Dir` the walk was started from.
+pub struct WalkEntry {
+    entry: DirEntry,
+    path: PathBuf,
+    rel: PathBuf,
+    depth: usize,
+    file_type: FileType,
+    metadata: Option<Metadata>,
+    follow_link: bool,
+}
+
+impl WalkEntry {
+    /// Returns the path of this entry, relative to the `Dir` that
+    /// [`Dir::walk_dir`] was called on.
+    ///
+    /// This includes the path passed to `walk_dir`, so it may be used with
+    /// the methods of that `Dir`.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Consumes `self` and returns its path.
+    #[inline]
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+
+    /// Returns the path of this entry, relative to the directory being
+    /// walked.
+    #[inline]
+    pub(crate) fn rel_path(&self) -> &Path {
+        &self.rel
+    }
+
+    /// Returns the depth of this entry, where entries directly inside the
+    /// starting directory have depth 1.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the file type of this entry.
+    ///
+    /// If this entry is a symlink which was followed, this is the type of
+    /// the symlink's target.
+    #[inline]
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// Returns `true` if this entry is a symlink, regardless of whether it
+    /// was followed.
+    #[inline]
+    pub fn path_is_symlink(&self) -> bool {
+        self.follow_link || self.file_type.is_symlink()
+    }
+
+    /// Returns the metadata for this entry.
+    ///
+    /// If this entry is a symlink which was followed, this is the metadata of
+    /// the symlink's target.
+    #[inline]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        match &self.metadata {
+            Some(metadata) => Ok(metadata.clone()),
+            None => self.entry.metadata(),
+        }
+    }
+
+    /// Returns the bare file name of this entry without any other leading
+    /// path component.
+    #[inline]
+    pub fn file_name(&self) -> OsString {
+        self.entry.file_name()
+    }
+
+    /// Returns the underlying `DirEntry`.
+    #[inline]
+    pub fn entry(&self) -> &DirEntry {
+        &self.entry
+    }
+
+    /// Consumes `self` and returns the underlying `DirEntry`.
+    #[inline]
+    pub fn into_entry(self) -> DirEntry {
+        self.entry
+    }
+}
+
+impl fmt::Debug for WalkEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("WalkEntry");
+        b.field("path", &self.path);
+        b.field("depth", &self.depth);
+        b.field("file_type", &self.file_type);
+        b.finish()
+    }
+}
+
+#[cold]
+fn filesystem_loop() -> io::Error {
+    #[cfg(not(windows))]
+    {
+        rustix::io::Errno::LOOP.into()
+    }
+
+    #[cfg(windows)]
+    {
+        io::Error::new(io::ErrorKind::Other, "filesystem loop found")
+    }
+}
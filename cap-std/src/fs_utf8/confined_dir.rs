@@ -0,0 +1,365 @@
+use crate::fs::{OpenOptions, RenameOptions};
+use crate::fs_utf8::{from_utf8, to_utf8, File, FileType, Metadata};
+use camino::Utf8Path;
+#[cfg(not(windows))]
+use rustix::fs::DirEntryExt;
+use std::{fmt, io};
+
+/// A reference to an open directory which never crosses into a different
+/// filesystem.
+///
+/// This corresponds to [`cap_std::fs::ConfinedDir`].
+///
+/// To create a `ConfinedDir`, use [`Dir::confine_to_mount`].
+///
+/// [`cap_std::fs::ConfinedDir`]: crate::fs::ConfinedDir
+/// [`Dir::confine_to_mount`]: crate::fs_utf8::Dir::confine_to_mount
+pub struct ConfinedDir {
+    cap_std: crate::fs::ConfinedDir,
+}
+
+impl ConfinedDir {
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::fs::ConfinedDir`.
+    #[inline]
+    pub fn from_cap_std(cap_std: crate::fs::ConfinedDir) -> Self {
+        Self { cap_std }
+    }
+
+    /// Return a view of this directory as a [`cap_std::fs::ConfinedDir`].
+    ///
+    /// [`cap_std::fs::ConfinedDir`]: crate::fs::ConfinedDir
+    #[inline]
+    pub fn as_cap_std(&self) -> &crate::fs::ConfinedDir {
+        &self.cap_std
+    }
+
+    /// Attempts to open a file in read-only mode.
+    #[inline]
+    pub fn open<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<File> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.open(path).map(File::from_cap_std)
+    }
+
+    /// Opens a file at `path` with the options specified by `options`.
+    #[inline]
+    pub fn open_with<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        options: &OpenOptions,
+    ) -> io::Result<File> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std
+            .open_with(path, options)
+            .map(File::from_cap_std)
+    }
+
+    /// Attempts to open a directory, confined to the same filesystem as
+    /// `self`.
+    #[inline]
+    pub fn open_dir<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<Self> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.open_dir(path).map(Self::from_cap_std)
+    }
+
+    /// Creates a new, empty directory at the provided path.
+    #[inline]
+    pub fn create_dir<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.create_dir(path)
+    }
+
+    /// Recursively create a directory and all of its parent components if they
+    /// are missing.
+    #[inline]
+    pub fn create_dir_all<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.create_dir_all(path)
+    }
+
+    /// Opens a file in write-only mode.
+    #[inline]
+    pub fn create<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<File> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.create(path).map(File::from_cap_std)
+    }
+
+    /// Given a path, query the file system to get information about a file,
+    /// directory, etc.
+    #[inline]
+    pub fn metadata<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<Metadata> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.metadata(path)
+    }
+
+    /// Query the metadata about a file without following symlinks.
+    #[inline]
+    pub fn symlink_metadata<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<Metadata> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.symlink_metadata(path)
+    }
+
+    /// Queries metadata about the underlying directory.
+    #[inline]
+    pub fn dir_metadata(&self) -> io::Result<Metadata> {
+        self.cap_std.dir_metadata()
+    }
+
+    /// Returns an iterator over the entries within `self`.
+    #[inline]
+    pub fn entries(&self) -> io::Result<ConfinedReadDir> {
+        self.cap_std.entries().map(ConfinedReadDir::from_cap_std)
+    }
+
+    /// Returns an iterator over the entries within a directory.
+    #[inline]
+    pub fn read_dir<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<ConfinedReadDir> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std
+            .read_dir(path)
+            .map(ConfinedReadDir::from_cap_std)
+    }
+
+    /// Read the entire contents of a file into a bytes vector.
+    #[inline]
+    pub fn read<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<Vec<u8>> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.read(path)
+    }
+
+    /// Read the entire contents of a file into a string.
+    #[inline]
+    pub fn read_to_string<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<String> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.read_to_string(path)
+    }
+
+    /// Write a slice as the entire contents of a file.
+    #[inline]
+    pub fn write<P: AsRef<Utf8Path>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+    ) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.write(path, contents)
+    }
+
+    /// Removes an empty directory.
+    #[inline]
+    pub fn remove_dir<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.remove_dir(path)
+    }
+
+    /// Removes a directory at this path, after removing all its contents. Use
+    /// carefully!
+    #[inline]
+    pub fn remove_dir_all<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.remove_dir_all(path)
+    }
+
+    /// Removes a file from a filesystem.
+    #[inline]
+    pub fn remove_file<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.remove_file(path)
+    }
+
+    /// Rename a file or directory to a new name, replacing the original file
+    /// if to already exists.
+    #[inline]
+    pub fn rename<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<()> {
+        let from = from_utf8(from.as_ref())?;
+        let to = from_utf8(to.as_ref())?;
+        self.cap_std.rename(from, &to_dir.cap_std, to)
+    }
+
+    /// Rename a file or directory to a new name, with the behavior configured
+    /// by `options`.
+    #[inline]
+    pub fn rename_with<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &RenameOptions,
+    ) -> io::Result<()> {
+        let from = from_utf8(from.as_ref())?;
+        let to = from_utf8(to.as_ref())?;
+        self.cap_std.rename_with(from, &to_dir.cap_std, to, options)
+    }
+
+    /// Creates a new `ConfinedDir` instance that shares the same underlying
+    /// file handle as the existing `ConfinedDir` instance.
+    #[inline]
+    pub fn try_clone(&self) -> io::Result<Self> {
+        self.cap_std.try_clone().map(Self::from_cap_std)
+    }
+
+    /// Returns `true` if the path points at an existing entity.
+    #[inline]
+    pub fn exists<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        match from_utf8(path.as_ref()) {
+            Ok(path) => self.cap_std.exists(path),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns `true` if the path points at an existing entity.
+    #[inline]
+    pub fn try_exists<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<bool> {
+        self.cap_std.try_exists(from_utf8(path.as_ref())?)
+    }
+
+    /// Returns `true` if the path exists on disk and is pointing at a regular
+    /// file.
+    #[inline]
+    pub fn is_file<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        match from_utf8(path.as_ref()) {
+            Ok(path) => self.cap_std.is_file(path),
+            Err(_) => false,
+        }
+    }
+
+    /// Checks if `path` is a directory.
+    #[inline]
+    pub fn is_dir<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        match from_utf8(path.as_ref()) {
+            Ok(path) => self.cap_std.is_dir(path),
+            Err(_) => false,
+        }
+    }
+}
+
+impl fmt::Debug for ConfinedDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cap_std.fmt(f)
+    }
+}
+
+/// Iterator over the entries in a [`ConfinedDir`].
+///
+/// This corresponds to [`cap_std::fs::ConfinedReadDir`].
+///
+/// [`cap_std::fs::ConfinedReadDir`]: crate::fs::ConfinedReadDir
+pub struct ConfinedReadDir {
+    cap_std: crate::fs::ConfinedReadDir,
+}
+
+impl ConfinedReadDir {
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::fs::ConfinedReadDir`.
+    #[inline]
+    pub fn from_cap_std(cap_std: crate::fs::ConfinedReadDir) -> Self {
+        Self { cap_std }
+    }
+}
+
+impl Iterator for ConfinedReadDir {
+    type Item = io::Result<ConfinedDirEntry>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cap_std
+            .next()
+            .map(|result| result.map(ConfinedDirEntry::from_cap_std))
+    }
+}
+
+impl fmt::Debug for ConfinedReadDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cap_std.fmt(f)
+    }
+}
+
+/// Entries returned by the [`ConfinedReadDir`] iterator.
+///
+/// This corresponds to [`cap_std::fs::ConfinedDirEntry`].
+///
+/// [`cap_std::fs::ConfinedDirEntry`]: crate::fs::ConfinedDirEntry
+pub struct ConfinedDirEntry {
+    cap_std: crate::fs::ConfinedDirEntry,
+}
+
+impl ConfinedDirEntry {
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::fs::ConfinedDirEntry`.
+    #[inline]
+    pub fn from_cap_std(cap_std: crate::fs::ConfinedDirEntry) -> Self {
+        Self { cap_std }
+    }
+
+    /// Open the file for reading.
+    #[inline]
+    pub fn open(&self) -> io::Result<File> {
+        self.cap_std.open().map(File::from_cap_std)
+    }
+
+    /// Open the file with the given options.
+    #[inline]
+    pub fn open_with(&self, options: &OpenOptions) -> io::Result<File> {
+        self.cap_std.open_with(options).map(File::from_cap_std)
+    }
+
+    /// Open the entry as a directory, confined to the same filesystem as the
+    /// directory it was read from.
+    #[inline]
+    pub fn open_dir(&self) -> io::Result<ConfinedDir> {
+        self.cap_std.open_dir().map(ConfinedDir::from_cap_std)
+    }
+
+    /// Removes the file from its filesystem.
+    #[inline]
+    pub fn remove_file(&self) -> io::Result<()> {
+        self.cap_std.remove_file()
+    }
+
+    /// Removes the directory from its filesystem.
+    #[inline]
+    pub fn remove_dir(&self) -> io::Result<()> {
+        self.cap_std.remove_dir()
+    }
+
+    /// Returns the metadata for the file that this entry points at.
+    #[inline]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.cap_std.metadata()
+    }
+
+    /// Returns the file type for the file that this entry points at.
+    #[inline]
+    pub fn file_type(&self) -> io::Result<FileType> {
+        self.cap_std.file_type()
+    }
+
+    /// Returns the bare file name of this directory entry without any other
+    /// leading path component.
+    ///
+    /// This function returns an `Err` in the case that the file name isn't
+    /// encodable as UTF-8.
+    #[inline]
+    pub fn file_name(&self) -> io::Result<String> {
+        Ok(to_utf8(self.cap_std.file_name())?.into())
+    }
+}
+
+#[cfg(not(windows))]
+impl DirEntryExt for ConfinedDirEntry {
+    #[inline]
+    fn ino(&self) -> u64 {
+        self.cap_std.ino()
+    }
+}
+
+impl fmt::Debug for ConfinedDirEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cap_std.fmt(f)
+    }
+}
@@ -1,5 +1,9 @@
-use crate::fs::{OpenOptions, Permissions};
-use crate::fs_utf8::{from_utf8, to_utf8, DirBuilder, File, Metadata, ReadDir};
+use crate::fs::{CopyOptions, DirRights, OpenOptions, Permissions, RenameOptions};
+#[cfg(not(target_os = "wasi"))]
+use crate::fs_utf8::FileLock;
+use crate::fs_utf8::{
+    from_utf8, to_utf8, ConfinedDir, DirBuilder, File, Metadata, ReadDir, RestrictedDir, WalkDir,
+};
 #[cfg(unix)]
 use crate::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
 use camino::{Utf8Path, Utf8PathBuf};
@@ -97,6 +101,32 @@ impl Dir {
         self.as_ref().open_dir(path).map(Self::from_cap_std)
     }
 
+    /// Returns an attenuated handle for this directory which only permits
+    /// the operations allowed by `rights`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::restrict`].
+    ///
+    /// [`cap_std::fs::Dir::restrict`]: crate::fs::Dir::restrict
+    #[inline]
+    pub fn restrict(&self, rights: DirRights) -> io::Result<RestrictedDir> {
+        self.cap_std
+            .restrict(rights)
+            .map(RestrictedDir::from_cap_std)
+    }
+
+    /// Returns a handle for this directory which refuses to cross into a
+    /// different filesystem than the one it's on.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::confine_to_mount`].
+    ///
+    /// [`cap_std::fs::Dir::confine_to_mount`]: crate::fs::Dir::confine_to_mount
+    #[inline]
+    pub fn confine_to_mount(&self) -> io::Result<ConfinedDir> {
+        self.cap_std
+            .confine_to_mount()
+            .map(ConfinedDir::from_cap_std)
+    }
+
     /// Creates a new, empty directory at the provided path.
     ///
     /// This corresponds to [`std::fs::create_dir`], but only accesses paths
@@ -143,6 +173,20 @@ impl Dir {
         self.cap_std.create(path).map(File::from_cap_std)
     }
 
+    /// Opens a file for reading and writing, creating it if it doesn't exist,
+    /// and acquires an exclusive advisory lock on it, blocking until it's
+    /// available.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::lock_file`].
+    ///
+    /// [`cap_std::fs::Dir::lock_file`]: crate::fs::Dir::lock_file
+    #[cfg(not(target_os = "wasi"))]
+    pub fn lock_file<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<FileLock> {
+        let file = self.open_with(path, OpenOptions::new().read(true).write(true).create(true))?;
+        file.lock_exclusive()?;
+        Ok(FileLock::new(file))
+    }
+
     /// Returns the canonical form of a path with all intermediate components
     /// normalized and symbolic links resolved.
     ///
@@ -173,6 +217,63 @@ impl Dir {
         self.cap_std.copy(from, &to_dir.cap_std, to)
     }
 
+    /// Copies the contents of one file to another, configured by `options`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::copy_with`], but only
+    /// accesses paths relative to `self` and `to_dir`.
+    ///
+    /// [`cap_std::fs::Dir::copy_with`]: crate::fs::Dir::copy_with
+    #[inline]
+    pub fn copy_with<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &CopyOptions,
+    ) -> io::Result<u64> {
+        let from = from_utf8(from.as_ref())?;
+        let to = from_utf8(to.as_ref())?;
+        self.cap_std.copy_with(from, &to_dir.cap_std, to, options)
+    }
+
+    /// Makes `to` a copy-on-write clone of the file at `from`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::reflink`], but only accesses
+    /// paths relative to `self` and `to_dir`.
+    ///
+    /// [`cap_std::fs::Dir::reflink`]: crate::fs::Dir::reflink
+    #[inline]
+    pub fn reflink<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<u64> {
+        let from = from_utf8(from.as_ref())?;
+        let to = from_utf8(to.as_ref())?;
+        self.cap_std.reflink(from, &to_dir.cap_std, to)
+    }
+
+    /// Recursively copies the directory tree at `from` to `to`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::copy_dir_all`], but only
+    /// accesses paths relative to `self` and `to_dir`.
+    ///
+    /// [`cap_std::fs::Dir::copy_dir_all`]: crate::fs::Dir::copy_dir_all
+    #[inline]
+    pub fn copy_dir_all<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &CopyOptions,
+    ) -> io::Result<u64> {
+        let from = from_utf8(from.as_ref())?;
+        let to = from_utf8(to.as_ref())?;
+        self.cap_std
+            .copy_dir_all(from, &to_dir.cap_std, to, options)
+    }
+
     /// Creates a new hard link on a filesystem.
     ///
     /// This corresponds to [`std::fs::hard_link`], but only accesses paths
@@ -225,6 +326,19 @@ impl Dir {
         self.cap_std.read_dir(path).map(ReadDir::from_cap_std)
     }
 
+    /// Returns a builder for a recursive walk over the directory tree at
+    /// `path`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::walk_dir`], but yields UTF-8
+    /// paths.
+    ///
+    /// [`cap_std::fs::Dir::walk_dir`]: crate::fs::Dir::walk_dir
+    #[inline]
+    pub fn walk_dir<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<WalkDir> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.walk_dir(path).map(WalkDir::from_cap_std)
+    }
+
     /// Read the entire contents of a file into a bytes vector.
     ///
     /// This corresponds to [`std::fs::read`], but only accesses paths
@@ -335,6 +449,63 @@ impl Dir {
         self.cap_std.rename(from, &to_dir.cap_std, to)
     }
 
+    /// Rename a file or directory to a new name, failing if `to` already
+    /// exists.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::rename_noreplace`], but only
+    /// accesses paths relative to `self`.
+    ///
+    /// [`cap_std::fs::Dir::rename_noreplace`]: crate::fs::Dir::rename_noreplace
+    #[inline]
+    pub fn rename_noreplace<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<()> {
+        let from = from_utf8(from.as_ref())?;
+        let to = from_utf8(to.as_ref())?;
+        self.cap_std.rename_noreplace(from, &to_dir.cap_std, to)
+    }
+
+    /// Atomically exchange two files or directories, which must both exist.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::exchange`], but only accesses
+    /// paths relative to `self`.
+    ///
+    /// [`cap_std::fs::Dir::exchange`]: crate::fs::Dir::exchange
+    #[inline]
+    pub fn exchange<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        a: P,
+        b_dir: &Self,
+        b: Q,
+    ) -> io::Result<()> {
+        let a = from_utf8(a.as_ref())?;
+        let b = from_utf8(b.as_ref())?;
+        self.cap_std.exchange(a, &b_dir.cap_std, b)
+    }
+
+    /// Rename a file or directory to a new name, with the behavior configured
+    /// by `options`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::rename_with`], but only
+    /// accesses paths relative to `self`.
+    ///
+    /// [`cap_std::fs::Dir::rename_with`]: crate::fs::Dir::rename_with
+    #[inline]
+    pub fn rename_with<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &RenameOptions,
+    ) -> io::Result<()> {
+        let from = from_utf8(from.as_ref())?;
+        let to = from_utf8(to.as_ref())?;
+        self.cap_std.rename_with(from, &to_dir.cap_std, to, options)
+    }
+
     /// Changes the permissions found on a file or a directory.
     ///
     /// This corresponds to [`std::fs::set_permissions`], but only accesses
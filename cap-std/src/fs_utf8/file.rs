@@ -109,6 +109,154 @@ impl File {
         self.cap_std.set_permissions(perm)
     }
 
+    /// Changes the owner and group of the underlying file. `None` leaves the
+    /// corresponding ID unchanged.
+    ///
+    /// This corresponds to [`std::os::unix::fs::fchown`].
+    ///
+    /// [`std::os::unix::fs::fchown`]: https://doc.rust-lang.org/std/os/unix/fs/fn.fchown.html
+    #[cfg(unix)]
+    #[inline]
+    pub fn set_owner(&self, owner: Option<u32>, group: Option<u32>) -> io::Result<()> {
+        self.cap_std.set_owner(owner, group)
+    }
+
+    /// Makes the `len` bytes at `dst_offset` in this file a copy-on-write
+    /// clone of the `len` bytes at `src_offset` in `src`.
+    ///
+    /// This corresponds to [`cap_std::fs::File::clone_range_from`].
+    ///
+    /// [`cap_std::fs::File::clone_range_from`]: crate::fs::File::clone_range_from
+    #[inline]
+    pub fn clone_range_from(
+        &self,
+        src: &Self,
+        src_offset: u64,
+        len: u64,
+        dst_offset: u64,
+    ) -> io::Result<()> {
+        self.cap_std
+            .clone_range_from(&src.cap_std, src_offset, len, dst_offset)
+    }
+
+    /// Acquires a shared advisory lock on the file, blocking until it's
+    /// available.
+    ///
+    /// This corresponds to [`std::fs::File::lock_shared`] in newer versions of
+    /// Rust. On Unix-family platforms this uses `flock`, so the lock is shared
+    /// by all handles cloned from this one, and is released when they're all
+    /// closed.
+    ///
+    /// [`std::fs::File::lock_shared`]: https://doc.rust-lang.org/std/fs/struct.File.html#method.lock_shared
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.cap_std.lock_shared()
+    }
+
+    /// Acquires an exclusive advisory lock on the file, blocking until it's
+    /// available.
+    ///
+    /// This corresponds to [`std::fs::File::lock`] in newer versions of Rust.
+    ///
+    /// [`std::fs::File::lock`]: https://doc.rust-lang.org/std/fs/struct.File.html#method.lock
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.cap_std.lock_exclusive()
+    }
+
+    /// Attempts to acquire a shared advisory lock on the file, returning
+    /// `Ok(false)` if it's locked exclusively elsewhere.
+    ///
+    /// This corresponds to [`std::fs::File::try_lock_shared`] in newer
+    /// versions of Rust.
+    ///
+    /// [`std::fs::File::try_lock_shared`]: https://doc.rust-lang.org/std/fs/struct.File.html#method.try_lock_shared
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.cap_std.try_lock_shared()
+    }
+
+    /// Attempts to acquire an exclusive advisory lock on the file, returning
+    /// `Ok(false)` if it's locked elsewhere.
+    ///
+    /// This corresponds to [`std::fs::File::try_lock`] in newer versions of
+    /// Rust.
+    ///
+    /// [`std::fs::File::try_lock`]: https://doc.rust-lang.org/std/fs/struct.File.html#method.try_lock
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        self.cap_std.try_lock_exclusive()
+    }
+
+    /// Releases an advisory lock acquired with one of the `lock` methods.
+    ///
+    /// This corresponds to [`std::fs::File::unlock`] in newer versions of
+    /// Rust.
+    ///
+    /// [`std::fs::File::unlock`]: https://doc.rust-lang.org/std/fs/struct.File.html#method.unlock
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.cap_std.unlock()
+    }
+
+    /// Acquires a shared advisory lock on the `len` bytes of the file starting
+    /// at `offset`, blocking until it's available. A `len` of 0 extends the
+    /// range to the end of the file and beyond.
+    ///
+    /// On Linux this uses open file description locks (`F_OFD_SETLK`), which
+    /// are independent of the whole-file locks acquired with `flock`. On
+    /// Windows, whole-file and byte-range locks are the same kind of lock.
+    #[cfg(any(target_os = "linux", windows))]
+    #[inline]
+    pub fn lock_range_shared(&self, offset: u64, len: u64) -> io::Result<()> {
+        self.cap_std.lock_range_shared(offset, len)
+    }
+
+    /// Acquires an exclusive advisory lock on the `len` bytes of the file
+    /// starting at `offset`, blocking until it's available.
+    ///
+    /// See [`File::lock_range_shared`] for details.
+    #[cfg(any(target_os = "linux", windows))]
+    #[inline]
+    pub fn lock_range_exclusive(&self, offset: u64, len: u64) -> io::Result<()> {
+        self.cap_std.lock_range_exclusive(offset, len)
+    }
+
+    /// Attempts to acquire a shared advisory lock on the `len` bytes of the
+    /// file starting at `offset`, returning `Ok(false)` if any of them are
+    /// locked exclusively elsewhere.
+    ///
+    /// See [`File::lock_range_shared`] for details.
+    #[cfg(any(target_os = "linux", windows))]
+    #[inline]
+    pub fn try_lock_range_shared(&self, offset: u64, len: u64) -> io::Result<bool> {
+        self.cap_std.try_lock_range_shared(offset, len)
+    }
+
+    /// Attempts to acquire an exclusive advisory lock on the `len` bytes of
+    /// the file starting at `offset`, returning `Ok(false)` if any of them are
+    /// locked elsewhere.
+    ///
+    /// See [`File::lock_range_shared`] for details.
+    #[cfg(any(target_os = "linux", windows))]
+    #[inline]
+    pub fn try_lock_range_exclusive(&self, offset: u64, len: u64) -> io::Result<bool> {
+        self.cap_std.try_lock_range_exclusive(offset, len)
+    }
+
+    /// Releases an advisory lock on the `len` bytes of the file starting at
+    /// `offset`.
+    #[cfg(any(target_os = "linux", windows))]
+    #[inline]
+    pub fn unlock_range(&self, offset: u64, len: u64) -> io::Result<()> {
+        self.cap_std.unlock_range(offset, len)
+    }
+
     /// Constructs a new instance of `Self` in read-only mode by opening the
     /// given path as a file using the host process' ambient authority.
     ///
@@ -0,0 +1,42 @@
+use crate::fs_utf8::File;
+use std::fmt;
+use std::ops::Deref;
+
+/// A file holding an exclusive advisory lock, which is released when this is
+/// dropped.
+///
+/// This corresponds to [`cap_std::fs::FileLock`].
+///
+/// [`cap_std::fs::FileLock`]: crate::fs::FileLock
+pub struct FileLock {
+    file: File,
+}
+
+impl FileLock {
+    pub(crate) fn new(file: File) -> Self {
+        Self { file }
+    }
+}
+
+impl Deref for FileLock {
+    type Target = File;
+
+    #[inline]
+    fn deref(&self) -> &File {
+        &self.file
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+impl fmt::Debug for FileLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileLock")
+            .field("file", &self.file)
+            .finish()
+    }
+}
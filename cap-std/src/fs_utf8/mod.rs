@@ -11,18 +11,31 @@
 //!
 //! [`cap_std::fs`]: ../fs/
 
+mod confined_dir;
 mod dir;
 mod dir_entry;
 mod file;
+#[cfg(not(target_os = "wasi"))]
+mod file_lock;
 mod read_dir;
+mod restricted_dir;
+mod walk_dir;
 
+pub use confined_dir::{ConfinedDir, ConfinedDirEntry, ConfinedReadDir};
 pub use dir::Dir;
 pub use dir_entry::DirEntry;
 pub use file::File;
+#[cfg(not(target_os = "wasi"))]
+pub use file_lock::FileLock;
 pub use read_dir::ReadDir;
+pub use restricted_dir::{RestrictedDir, RestrictedDirEntry, RestrictedReadDir};
+pub use walk_dir::{FilterEntry, Walk, WalkDir, WalkEntry};
 
 // Re-export things from `cap_std::fs` that we can use as-is.
-pub use crate::fs::{DirBuilder, FileType, Metadata, OpenOptions, Permissions};
+pub use crate::fs::{
+    CopyOptions, DirBuilder, DirRights, FileType, FollowSymlinks, Metadata, OpenOptions,
+    Permissions, RenameOptions, RestrictedFile,
+};
 
 // Re-export conditional types from `cap_primitives`.
 #[cfg(any(unix, target_os = "vxworks", all(windows, windows_file_type_ext)))]
@@ -0,0 +1,529 @@
+use crate::fs::{DirRights, OpenOptions, RenameOptions, RestrictedFile};
+use crate::fs_utf8::{from_utf8, to_utf8, FileType, Metadata};
+#[cfg(not(target_os = "wasi"))]
+use crate::fs_utf8::{DirBuilder, Permissions};
+use camino::{Utf8Path, Utf8PathBuf};
+#[cfg(not(windows))]
+use rustix::fs::DirEntryExt;
+use std::{fmt, io};
+
+/// An attenuated reference to an open directory on a filesystem.
+///
+/// This corresponds to [`cap_std::fs::RestrictedDir`].
+///
+/// To create a `RestrictedDir`, use [`Dir::restrict`].
+///
+/// [`cap_std::fs::RestrictedDir`]: crate::fs::RestrictedDir
+/// [`Dir::restrict`]: crate::fs_utf8::Dir::restrict
+pub struct RestrictedDir {
+    cap_std: crate::fs::RestrictedDir,
+}
+
+impl RestrictedDir {
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::fs::RestrictedDir`.
+    #[inline]
+    pub fn from_cap_std(cap_std: crate::fs::RestrictedDir) -> Self {
+        Self { cap_std }
+    }
+
+    /// Return a view of this directory as a [`cap_std::fs::RestrictedDir`].
+    ///
+    /// [`cap_std::fs::RestrictedDir`]: crate::fs::RestrictedDir
+    #[inline]
+    pub fn as_cap_std(&self) -> &crate::fs::RestrictedDir {
+        &self.cap_std
+    }
+
+    /// Returns the rights of this directory.
+    #[inline]
+    pub fn rights(&self) -> DirRights {
+        self.cap_std.rights()
+    }
+
+    /// Returns a new `RestrictedDir` for the same directory, with the rights
+    /// permitted by both `self` and `rights`.
+    #[inline]
+    pub fn restrict(&self, rights: DirRights) -> io::Result<Self> {
+        self.cap_std.restrict(rights).map(Self::from_cap_std)
+    }
+
+    /// Attempts to open a file in read-only mode.
+    #[inline]
+    pub fn open<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<RestrictedFile> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.open(path)
+    }
+
+    /// Opens a file at `path` with the options specified by `options`.
+    #[inline]
+    pub fn open_with<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        options: &OpenOptions,
+    ) -> io::Result<RestrictedFile> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.open_with(path, options)
+    }
+
+    /// Attempts to open a directory, with the same rights as `self`.
+    #[inline]
+    pub fn open_dir<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<Self> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.open_dir(path).map(Self::from_cap_std)
+    }
+
+    /// Creates a new, empty directory at the provided path.
+    #[inline]
+    pub fn create_dir<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.create_dir(path)
+    }
+
+    /// Recursively create a directory and all of its parent components if they
+    /// are missing.
+    #[inline]
+    pub fn create_dir_all<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.create_dir_all(path)
+    }
+
+    /// Creates the specified directory with the options configured in this
+    /// builder.
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn create_dir_with<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        dir_builder: &DirBuilder,
+    ) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.create_dir_with(path, dir_builder)
+    }
+
+    /// Opens a file in write-only mode.
+    #[inline]
+    pub fn create<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<RestrictedFile> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.create(path)
+    }
+
+    /// Returns the canonical form of a path with all intermediate components
+    /// normalized and symbolic links resolved.
+    #[inline]
+    pub fn canonicalize<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<Utf8PathBuf> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.canonicalize(path).and_then(to_utf8)
+    }
+
+    /// Copies the contents of one file to another.
+    #[inline]
+    pub fn copy<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<u64> {
+        let from = from_utf8(from.as_ref())?;
+        let to = from_utf8(to.as_ref())?;
+        self.cap_std.copy(from, &to_dir.cap_std, to)
+    }
+
+    /// Makes `to` a copy-on-write clone of the file at `from`.
+    #[inline]
+    pub fn reflink<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<u64> {
+        let from = from_utf8(from.as_ref())?;
+        let to = from_utf8(to.as_ref())?;
+        self.cap_std.reflink(from, &to_dir.cap_std, to)
+    }
+
+    /// Creates a new hard link on a filesystem.
+    #[inline]
+    pub fn hard_link<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        src: P,
+        dst_dir: &Self,
+        dst: Q,
+    ) -> io::Result<()> {
+        let src = from_utf8(src.as_ref())?;
+        let dst = from_utf8(dst.as_ref())?;
+        self.cap_std.hard_link(src, &dst_dir.cap_std, dst)
+    }
+
+    /// Given a path, query the file system to get information about a file,
+    /// directory, etc.
+    #[inline]
+    pub fn metadata<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<Metadata> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.metadata(path)
+    }
+
+    /// Queries metadata about the underlying directory.
+    #[inline]
+    pub fn dir_metadata(&self) -> io::Result<Metadata> {
+        self.cap_std.dir_metadata()
+    }
+
+    /// Returns an iterator over the entries within `self`.
+    #[inline]
+    pub fn entries(&self) -> io::Result<RestrictedReadDir> {
+        self.cap_std.entries().map(RestrictedReadDir::from_cap_std)
+    }
+
+    /// Returns an iterator over the entries within a directory.
+    #[inline]
+    pub fn read_dir<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<RestrictedReadDir> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std
+            .read_dir(path)
+            .map(RestrictedReadDir::from_cap_std)
+    }
+
+    /// Read the entire contents of a file into a bytes vector.
+    #[inline]
+    pub fn read<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<Vec<u8>> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.read(path)
+    }
+
+    /// Reads a symbolic link, returning the file that the link points to.
+    #[inline]
+    pub fn read_link<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<Utf8PathBuf> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.read_link(path).and_then(to_utf8)
+    }
+
+    /// Read the entire contents of a file into a string.
+    #[inline]
+    pub fn read_to_string<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<String> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.read_to_string(path)
+    }
+
+    /// Removes an empty directory.
+    #[inline]
+    pub fn remove_dir<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.remove_dir(path)
+    }
+
+    /// Removes a directory at this path, after removing all its contents. Use
+    /// carefully!
+    #[inline]
+    pub fn remove_dir_all<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.remove_dir_all(path)
+    }
+
+    /// Removes a file from a filesystem.
+    #[inline]
+    pub fn remove_file<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.remove_file(path)
+    }
+
+    /// Rename a file or directory to a new name, replacing the original file
+    /// if to already exists.
+    ///
+    /// This fails unless both `self`'s and `to_dir`'s rights permit renaming,
+    /// and `to_dir`'s rights permit removing.
+    #[inline]
+    pub fn rename<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<()> {
+        let from = from_utf8(from.as_ref())?;
+        let to = from_utf8(to.as_ref())?;
+        self.cap_std.rename(from, &to_dir.cap_std, to)
+    }
+
+    /// Rename a file or directory to a new name, failing if `to` already
+    /// exists.
+    #[inline]
+    pub fn rename_noreplace<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<()> {
+        let from = from_utf8(from.as_ref())?;
+        let to = from_utf8(to.as_ref())?;
+        self.cap_std.rename_noreplace(from, &to_dir.cap_std, to)
+    }
+
+    /// Atomically exchange two files or directories, which must both exist.
+    #[inline]
+    pub fn exchange<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        a: P,
+        b_dir: &Self,
+        b: Q,
+    ) -> io::Result<()> {
+        let a = from_utf8(a.as_ref())?;
+        let b = from_utf8(b.as_ref())?;
+        self.cap_std.exchange(a, &b_dir.cap_std, b)
+    }
+
+    /// Rename a file or directory to a new name, with the behavior configured
+    /// by `options`.
+    ///
+    /// This fails unless both `self`'s and `to_dir`'s rights permit renaming.
+    /// Unless `options` requests `noreplace` or `exchange`, it also fails
+    /// unless `to_dir`'s rights permit removing.
+    #[inline]
+    pub fn rename_with<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &RenameOptions,
+    ) -> io::Result<()> {
+        let from = from_utf8(from.as_ref())?;
+        let to = from_utf8(to.as_ref())?;
+        self.cap_std.rename_with(from, &to_dir.cap_std, to, options)
+    }
+
+    /// Changes the permissions found on a file or a directory.
+    #[cfg(not(target_os = "wasi"))]
+    #[inline]
+    pub fn set_permissions<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        perm: Permissions,
+    ) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.set_permissions(path, perm)
+    }
+
+    /// Query the metadata about a file without following symlinks.
+    #[inline]
+    pub fn symlink_metadata<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<Metadata> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.symlink_metadata(path)
+    }
+
+    /// Write a slice as the entire contents of a file.
+    #[inline]
+    pub fn write<P: AsRef<Utf8Path>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+    ) -> io::Result<()> {
+        let path = from_utf8(path.as_ref())?;
+        self.cap_std.write(path, contents)
+    }
+
+    /// Creates a new symbolic link on a filesystem.
+    #[cfg(not(windows))]
+    #[inline]
+    pub fn symlink<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        original: P,
+        link: Q,
+    ) -> io::Result<()> {
+        let original = from_utf8(original.as_ref())?;
+        let link = from_utf8(link.as_ref())?;
+        self.cap_std.symlink(original, link)
+    }
+
+    /// Creates a new file symbolic link on a filesystem.
+    #[cfg(windows)]
+    #[inline]
+    pub fn symlink_file<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        original: P,
+        link: Q,
+    ) -> io::Result<()> {
+        let original = from_utf8(original.as_ref())?;
+        let link = from_utf8(link.as_ref())?;
+        self.cap_std.symlink_file(original, link)
+    }
+
+    /// Creates a new directory symlink on a filesystem.
+    #[cfg(windows)]
+    #[inline]
+    pub fn symlink_dir<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        &self,
+        original: P,
+        link: Q,
+    ) -> io::Result<()> {
+        let original = from_utf8(original.as_ref())?;
+        let link = from_utf8(link.as_ref())?;
+        self.cap_std.symlink_dir(original, link)
+    }
+
+    /// Creates a new `RestrictedDir` instance that shares the same underlying
+    /// file handle as the existing `RestrictedDir` instance, with the same
+    /// rights.
+    #[inline]
+    pub fn try_clone(&self) -> io::Result<Self> {
+        self.cap_std.try_clone().map(Self::from_cap_std)
+    }
+
+    /// Returns `true` if the path points at an existing entity.
+    #[inline]
+    pub fn exists<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        match from_utf8(path.as_ref()) {
+            Ok(path) => self.cap_std.exists(path),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns `true` if the path points at an existing entity.
+    #[inline]
+    pub fn try_exists<P: AsRef<Utf8Path>>(&self, path: P) -> io::Result<bool> {
+        self.cap_std.try_exists(from_utf8(path.as_ref())?)
+    }
+
+    /// Returns `true` if the path exists on disk and is pointing at a regular
+    /// file.
+    #[inline]
+    pub fn is_file<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        match from_utf8(path.as_ref()) {
+            Ok(path) => self.cap_std.is_file(path),
+            Err(_) => false,
+        }
+    }
+
+    /// Checks if `path` is a directory.
+    #[inline]
+    pub fn is_dir<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        match from_utf8(path.as_ref()) {
+            Ok(path) => self.cap_std.is_dir(path),
+            Err(_) => false,
+        }
+    }
+}
+
+impl fmt::Debug for RestrictedDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cap_std.fmt(f)
+    }
+}
+
+/// Iterator over the entries in a [`RestrictedDir`].
+///
+/// This corresponds to [`cap_std::fs::RestrictedReadDir`].
+///
+/// [`cap_std::fs::RestrictedReadDir`]: crate::fs::RestrictedReadDir
+pub struct RestrictedReadDir {
+    cap_std: crate::fs::RestrictedReadDir,
+}
+
+impl RestrictedReadDir {
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::fs::RestrictedReadDir`.
+    #[inline]
+    pub fn from_cap_std(cap_std: crate::fs::RestrictedReadDir) -> Self {
+        Self { cap_std }
+    }
+}
+
+impl Iterator for RestrictedReadDir {
+    type Item = io::Result<RestrictedDirEntry>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cap_std
+            .next()
+            .map(|result| result.map(RestrictedDirEntry::from_cap_std))
+    }
+}
+
+impl fmt::Debug for RestrictedReadDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cap_std.fmt(f)
+    }
+}
+
+/// Entries returned by the [`RestrictedReadDir`] iterator.
+///
+/// This corresponds to [`cap_std::fs::RestrictedDirEntry`].
+///
+/// [`cap_std::fs::RestrictedDirEntry`]: crate::fs::RestrictedDirEntry
+pub struct RestrictedDirEntry {
+    cap_std: crate::fs::RestrictedDirEntry,
+}
+
+impl RestrictedDirEntry {
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::fs::RestrictedDirEntry`.
+    #[inline]
+    pub fn from_cap_std(cap_std: crate::fs::RestrictedDirEntry) -> Self {
+        Self { cap_std }
+    }
+
+    /// Open the file for reading.
+    #[inline]
+    pub fn open(&self) -> io::Result<RestrictedFile> {
+        self.cap_std.open()
+    }
+
+    /// Open the file with the given options.
+    #[inline]
+    pub fn open_with(&self, options: &OpenOptions) -> io::Result<RestrictedFile> {
+        self.cap_std.open_with(options)
+    }
+
+    /// Open the entry as a directory, with the same rights as the directory
+    /// it was read from.
+    #[inline]
+    pub fn open_dir(&self) -> io::Result<RestrictedDir> {
+        self.cap_std.open_dir().map(RestrictedDir::from_cap_std)
+    }
+
+    /// Removes the file from its filesystem.
+    #[inline]
+    pub fn remove_file(&self) -> io::Result<()> {
+        self.cap_std.remove_file()
+    }
+
+    /// Removes the directory from its filesystem.
+    #[inline]
+    pub fn remove_dir(&self) -> io::Result<()> {
+        self.cap_std.remove_dir()
+    }
+
+    /// Returns the metadata for the file that this entry points at.
+    #[inline]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.cap_std.metadata()
+    }
+
+    /// Returns the file type for the file that this entry points at.
+    #[inline]
+    pub fn file_type(&self) -> io::Result<FileType> {
+        self.cap_std.file_type()
+    }
+
+    /// Returns the bare file name of this directory entry without any other
+    /// leading path component.
+    ///
+    /// This function returns an `Err` in the case that the file name isn't
+    /// encodable as UTF-8.
+    #[inline]
+    pub fn file_name(&self) -> io::Result<String> {
+        Ok(to_utf8(self.cap_std.file_name())?.into())
+    }
+}
+
+#[cfg(not(windows))]
+impl DirEntryExt for RestrictedDirEntry {
+    #[inline]
+    fn ino(&self) -> u64 {
+        self.cap_std.ino()
+    }
+}
+
+impl fmt::Debug for RestrictedDirEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cap_std.fmt(f)
+    }
+}
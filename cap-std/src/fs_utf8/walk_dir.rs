@@ -0,0 +1,284 @@
+use crate::fs::{FileType, FollowSymlinks, Metadata};
+use crate::fs_utf8::{to_utf8, DirEntry};
+use camino::{Utf8Path, Utf8PathBuf};
+use std::{fmt, io};
+
+/// A builder for a recursive walk over a directory tree.
+///
+/// This corresponds to [`cap_std::fs::WalkDir`], but yields UTF-8 paths.
+///
+/// [`cap_std::fs::WalkDir`]: crate::fs::WalkDir
+pub struct WalkDir {
+    cap_std: crate::fs::WalkDir,
+}
+
+impl WalkDir {
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::fs::WalkDir`.
+    #[inline]
+    pub fn from_cap_std(cap_std: crate::fs::WalkDir) -> Self {
+        Self { cap_std }
+    }
+
+    /// Set the minimum depth of entries yielded.
+    ///
+    /// This corresponds to [`cap_std::fs::WalkDir::min_depth`].
+    ///
+    /// [`cap_std::fs::WalkDir::min_depth`]: crate::fs::WalkDir::min_depth
+    #[inline]
+    pub fn min_depth(self, depth: usize) -> Self {
+        Self::from_cap_std(self.cap_std.min_depth(depth))
+    }
+
+    /// Set the maximum depth of entries yielded.
+    ///
+    /// This corresponds to [`cap_std::fs::WalkDir::max_depth`].
+    ///
+    /// [`cap_std::fs::WalkDir::max_depth`]: crate::fs::WalkDir::max_depth
+    #[inline]
+    pub fn max_depth(self, depth: usize) -> Self {
+        Self::from_cap_std(self.cap_std.max_depth(depth))
+    }
+
+    /// Set whether symlinks are followed.
+    ///
+    /// This corresponds to [`cap_std::fs::WalkDir::follow_symlinks`].
+    ///
+    /// [`cap_std::fs::WalkDir::follow_symlinks`]: crate::fs::WalkDir::follow_symlinks
+    #[inline]
+    pub fn follow_symlinks(self, follow: FollowSymlinks) -> Self {
+        Self::from_cap_std(self.cap_std.follow_symlinks(follow))
+    }
+
+    /// Yield a directory's contents before the directory itself.
+    ///
+    /// This corresponds to [`cap_std::fs::WalkDir::contents_first`].
+    ///
+    /// [`cap_std::fs::WalkDir::contents_first`]: crate::fs::WalkDir::contents_first
+    #[inline]
+    pub fn contents_first(self, yes: bool) -> Self {
+        Self::from_cap_std(self.cap_std.contents_first(yes))
+    }
+
+    /// Sort the entries of each directory by file name.
+    ///
+    /// This corresponds to [`cap_std::fs::WalkDir::sort_by_file_name`].
+    ///
+    /// [`cap_std::fs::WalkDir::sort_by_file_name`]: crate::fs::WalkDir::sort_by_file_name
+    #[inline]
+    pub fn sort_by_file_name(self) -> Self {
+        Self::from_cap_std(self.cap_std.sort_by_file_name())
+    }
+}
+
+impl IntoIterator for WalkDir {
+    type Item = io::Result<WalkEntry>;
+    type IntoIter = Walk;
+
+    #[inline]
+    fn into_iter(self) -> Walk {
+        Walk {
+            cap_std: self.cap_std.into_iter(),
+        }
+    }
+}
+
+impl fmt::Debug for WalkDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cap_std.fmt(f)
+    }
+}
+
+/// An iterator over the entries of a directory tree.
+///
+/// This corresponds to [`cap_std::fs::Walk`].
+///
+/// [`cap_std::fs::Walk`]: crate::fs::Walk
+pub struct Walk {
+    cap_std: crate::fs::Walk,
+}
+
+impl Walk {
+    /// Skip the rest of the current directory.
+    ///
+    /// This corresponds to [`cap_std::fs::Walk::skip_current_dir`].
+    ///
+    /// [`cap_std::fs::Walk::skip_current_dir`]: crate::fs::Walk::skip_current_dir
+    #[inline]
+    pub fn skip_current_dir(&mut self) {
+        self.cap_std.skip_current_dir()
+    }
+
+    /// Yield only the entries for which `predicate` returns `true`.
+    ///
+    /// This corresponds to [`cap_std::fs::Walk::filter_entry`].
+    ///
+    /// [`cap_std::fs::Walk::filter_entry`]: crate::fs::Walk::filter_entry
+    #[inline]
+    pub fn filter_entry<P>(self, predicate: P) -> FilterEntry<P>
+    where
+        P: FnMut(&WalkEntry) -> bool,
+    {
+        FilterEntry {
+            walk: self,
+            predicate,
+        }
+    }
+}
+
+impl Iterator for Walk {
+    type Item = io::Result<WalkEntry>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cap_std
+            .next()
+            .map(|result| result.and_then(WalkEntry::from_cap_std))
+    }
+}
+
+impl fmt::Debug for Walk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cap_std.fmt(f)
+    }
+}
+
+/// An iterator over a directory tree which prunes entries with a predicate.
+///
+/// This corresponds to [`cap_std::fs::FilterEntry`].
+///
+/// [`cap_std::fs::FilterEntry`]: crate::fs::FilterEntry
+pub struct FilterEntry<P> {
+    walk: Walk,
+    predicate: P,
+}
+
+impl<P> FilterEntry<P>
+where
+    P: FnMut(&WalkEntry) -> bool,
+{
+    /// Skip the rest of the current directory.
+    ///
+    /// This corresponds to [`Walk::skip_current_dir`].
+    #[inline]
+    pub fn skip_current_dir(&mut self) {
+        self.walk.skip_current_dir()
+    }
+}
+
+impl<P> Iterator for FilterEntry<P>
+where
+    P: FnMut(&WalkEntry) -> bool,
+{
+    type Item = io::Result<WalkEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entry = match self.walk.next()? {
+                Ok(entry) => entry,
+                Err(error) => return Some(Err(error)),
+            };
+            if (self.predicate)(&entry) {
+                return Some(Ok(entry));
+            }
+            if !self.walk.cap_std.is_contents_first() {
+                self.walk.cap_std.prune_last();
+            }
+        }
+    }
+}
+
+impl<P> fmt::Debug for FilterEntry<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.walk.fmt(f)
+    }
+}
+
+/// An entry yielded by a [`Walk`].
+///
+/// This corresponds to [`cap_std::fs::WalkEntry`].
+///
+/// [`cap_std::fs::WalkEntry`]: crate::fs::WalkEntry
+pub struct WalkEntry {
+    cap_std: crate::fs::WalkEntry,
+    path: Utf8PathBuf,
+}
+
+impl WalkEntry {
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::fs::WalkEntry`.
+    ///
+    /// This function returns an `Err` in the case that the entry's path isn't
+    /// encodable as UTF-8.
+    #[inline]
+    pub fn from_cap_std(cap_std: crate::fs::WalkEntry) -> io::Result<Self> {
+        let path = to_utf8(cap_std.path())?;
+        Ok(Self { cap_std, path })
+    }
+
+    /// Returns the path of this entry, relative to the `Dir` that
+    /// [`Dir::walk_dir`] was called on.
+    ///
+    /// [`Dir::walk_dir`]: crate::fs_utf8::Dir::walk_dir
+    #[inline]
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+
+    /// Consumes `self` and returns its path.
+    #[inline]
+    pub fn into_path(self) -> Utf8PathBuf {
+        self.path
+    }
+
+    /// Returns the depth of this entry, where entries directly inside the
+    /// starting directory have depth 1.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.cap_std.depth()
+    }
+
+    /// Returns the file type of this entry.
+    ///
+    /// If this entry is a symlink which was followed, this is the type of
+    /// the symlink's target.
+    #[inline]
+    pub fn file_type(&self) -> FileType {
+        self.cap_std.file_type()
+    }
+
+    /// Returns `true` if this entry is a symlink, regardless of whether it
+    /// was followed.
+    #[inline]
+    pub fn path_is_symlink(&self) -> bool {
+        self.cap_std.path_is_symlink()
+    }
+
+    /// Returns the metadata for this entry.
+    ///
+    /// If this entry is a symlink which was followed, this is the metadata of
+    /// the symlink's target.
+    #[inline]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.cap_std.metadata()
+    }
+
+    /// Returns the bare file name of this entry without any other leading
+    /// path component.
+    #[inline]
+    pub fn file_name(&self) -> &str {
+        self.path.file_name().unwrap()
+    }
+
+    /// Consumes `self` and returns the underlying `DirEntry`.
+    #[inline]
+    pub fn into_entry(self) -> DirEntry {
+        DirEntry::from_cap_std(self.cap_std.into_entry())
+    }
+}
+
+impl fmt::Debug for WalkEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cap_std.fmt(f)
+    }
+}
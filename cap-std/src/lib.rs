@@ -41,6 +41,8 @@ pub mod fs_utf8;
 #[cfg(not(target_os = "wasi"))] // Disable `net` on WASI until it has networking support.
 pub mod net;
 pub mod os;
+#[cfg(any(target_os = "android", target_os = "linux", target_os = "freebsd"))]
+pub mod process;
 pub mod time;
 // Re-export ambient_authority etc. so that users can use our version.
 #[doc(hidden)]
@@ -20,6 +20,8 @@ pub use tcp_listener::*;
 pub use tcp_stream::*;
 pub use udp_socket::*;
 
+pub use cap_primitives::net::{Access, Resolve, Resolver, Rule, StaticResolver, Verdict};
+
 // Re-export things from `std::net` that we can use as-is.
 pub use std::net::{
     AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, SocketAddrV4, SocketAddrV6,
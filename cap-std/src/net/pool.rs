@@ -1,14 +1,24 @@
-use crate::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use crate::net::{
+    Access, Resolver, Rule, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket, Verdict,
+};
 use cap_primitives::net::no_socket_addrs;
 use cap_primitives::{ipnet, AmbientAuthority};
 use std::time::Duration;
-use std::{io, net};
+use std::{fmt, io, net};
 
 /// A pool of network addresses.
 ///
 /// This does not directly correspond to anything in `std`, however its methods
 /// correspond to the several functions in [`std::net`].
 ///
+/// A `Pool` holds allow rules, added with the `insert_*` methods, and deny
+/// rules, added with the `deny_*` methods. Deny rules take precedence over
+/// allow rules, regardless of the order they were added in.
+///
+/// Each rule applies to a set of operations, such as binding TCP listeners or
+/// connecting TCP streams, described by an [`Access`]. Methods which don't
+/// take an `Access` add rules which apply to all operations.
+///
 /// `Pool` implements `Clone`, which creates new independent entities that
 /// carry the full authority of the originals. This means that in a borrow
 /// of a `Pool`, the scope of the authority is not necessarily limited to
@@ -32,6 +42,47 @@ impl Pool {
         }
     }
 
+    /// Construct a new pool containing `rules`.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any IP address or host name.
+    pub fn from_rules<I: IntoIterator<Item = Rule>>(
+        rules: I,
+        ambient_authority: AmbientAuthority,
+    ) -> Self {
+        Self {
+            cap: cap_primitives::net::Pool::from_rules(rules, ambient_authority),
+        }
+    }
+
+    /// Parse a pool from its textual form, which has one [`Rule`] per line.
+    /// Empty lines, and everything after a `#` on a line, are ignored.
+    ///
+    /// The `Display` implementation for `Pool` prints this form. To load a
+    /// pool from a configuration file, read the file with
+    /// [`Dir::read_to_string`] and pass the contents to this function.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any IP address or host name.
+    ///
+    /// [`Dir::read_to_string`]: crate::fs::Dir::read_to_string
+    pub fn parse(text: &str, ambient_authority: AmbientAuthority) -> io::Result<Self> {
+        Ok(Self {
+            cap: cap_primitives::net::Pool::parse(text, ambient_authority)?,
+        })
+    }
+
+    /// Add a rule to the pool.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any IP address or host name.
+    pub fn insert_rule(&mut self, rule: Rule, ambient_authority: AmbientAuthority) {
+        self.cap.insert_rule(rule, ambient_authority)
+    }
+
     /// Add addresses to the pool.
     ///
     /// # Ambient Authority
@@ -94,6 +145,32 @@ impl Pool {
             .insert_ip_net_port_range(ip_net, ports_start, ports_end, ambient_authority)
     }
 
+    /// Add a range of network addresses, accepting a range of ports, to the
+    /// pool, for only the operations in `access`.
+    ///
+    /// For example, `Access::TCP_CONNECT` grants the ability to connect TCP
+    /// streams to the addresses, but not to bind listeners to them.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any IP address.
+    pub fn insert_ip_net_port_range_access(
+        &mut self,
+        ip_net: ipnet::IpNet,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        access: Access,
+        ambient_authority: AmbientAuthority,
+    ) {
+        self.cap.insert_ip_net_port_range_access(
+            ip_net,
+            ports_start,
+            ports_end,
+            access,
+            ambient_authority,
+        )
+    }
+
     /// Add a range of network addresses with a specific port to the pool.
     ///
     /// # Ambient Authority
@@ -108,6 +185,233 @@ impl Pool {
         self.cap.insert_ip_net(ip_net, port, ambient_authority)
     }
 
+    /// Add a host name with a specific port to the pool.
+    ///
+    /// Unlike [`Pool::insert`], this doesn't resolve the name. The grant
+    /// matches the name itself, when it's passed to methods such as
+    /// [`Pool::connect_tcp_stream_host`], and doesn't match any address
+    /// directly.
+    ///
+    /// This fails if `host` isn't a host name: it must be non-empty, contain
+    /// only ASCII letters, digits, `-`, `.`, and `_`, and not be an IP
+    /// address.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any host name.
+    pub fn insert_host(
+        &mut self,
+        host: &str,
+        port: u16,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<()> {
+        self.cap.insert_host(host, port, ambient_authority)
+    }
+
+    /// Add a host name, accepting any port, to the pool.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any host name.
+    pub fn insert_host_port_any(
+        &mut self,
+        host: &str,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<()> {
+        self.cap.insert_host_port_any(host, ambient_authority)
+    }
+
+    /// Add a host name, accepting a range of ports, to the pool.
+    ///
+    /// This grants access to the port range starting at `ports_start` and,
+    /// if `ports_end` is provided, ending before `ports_end`.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any host name.
+    pub fn insert_host_port_range(
+        &mut self,
+        host: &str,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<()> {
+        self.cap
+            .insert_host_port_range(host, ports_start, ports_end, ambient_authority)
+    }
+
+    /// Add a host name, accepting a range of ports, to the pool, for only the
+    /// operations in `access`.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any host name.
+    pub fn insert_host_port_range_access(
+        &mut self,
+        host: &str,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        access: Access,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<()> {
+        self.cap.insert_host_port_range_access(
+            host,
+            ports_start,
+            ports_end,
+            access,
+            ambient_authority,
+        )
+    }
+
+    /// Deny a range of network addresses with a specific port.
+    ///
+    /// Deny rules take precedence over allow rules.
+    pub fn deny_ip_net(&mut self, ip_net: ipnet::IpNet, port: u16) {
+        self.cap.deny_ip_net(ip_net, port)
+    }
+
+    /// Deny a range of network addresses, with any port.
+    ///
+    /// Deny rules take precedence over allow rules.
+    pub fn deny_ip_net_port_any(&mut self, ip_net: ipnet::IpNet) {
+        self.cap.deny_ip_net_port_any(ip_net)
+    }
+
+    /// Deny a range of network addresses, with a range of ports.
+    ///
+    /// This denies the port range starting at `ports_start` and, if
+    /// `ports_end` is provided, ending before `ports_end`. Deny rules take
+    /// precedence over allow rules.
+    pub fn deny_ip_net_port_range(
+        &mut self,
+        ip_net: ipnet::IpNet,
+        ports_start: u16,
+        ports_end: Option<u16>,
+    ) {
+        self.cap
+            .deny_ip_net_port_range(ip_net, ports_start, ports_end)
+    }
+
+    /// Deny the operations in `access` on a range of network addresses, with
+    /// a range of ports.
+    ///
+    /// Deny rules take precedence over allow rules.
+    pub fn deny_ip_net_port_range_access(
+        &mut self,
+        ip_net: ipnet::IpNet,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        access: Access,
+    ) {
+        self.cap
+            .deny_ip_net_port_range_access(ip_net, ports_start, ports_end, access)
+    }
+
+    /// Deny a host name with a specific port.
+    ///
+    /// Deny rules take precedence over allow rules. As with
+    /// [`Pool::insert_host`], this fails if `host` isn't a host name.
+    pub fn deny_host(&mut self, host: &str, port: u16) -> io::Result<()> {
+        self.cap.deny_host(host, port)
+    }
+
+    /// Deny a host name, with any port.
+    ///
+    /// Deny rules take precedence over allow rules.
+    pub fn deny_host_port_any(&mut self, host: &str) -> io::Result<()> {
+        self.cap.deny_host_port_any(host)
+    }
+
+    /// Deny a host name, with a range of ports.
+    ///
+    /// This denies the port range starting at `ports_start` and, if
+    /// `ports_end` is provided, ending before `ports_end`. Deny rules take
+    /// precedence over allow rules.
+    pub fn deny_host_port_range(
+        &mut self,
+        host: &str,
+        ports_start: u16,
+        ports_end: Option<u16>,
+    ) -> io::Result<()> {
+        self.cap.deny_host_port_range(host, ports_start, ports_end)
+    }
+
+    /// Deny the operations in `access` on a host name, with a range of ports.
+    ///
+    /// Deny rules take precedence over allow rules.
+    pub fn deny_host_port_range_access(
+        &mut self,
+        host: &str,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        access: Access,
+    ) -> io::Result<()> {
+        self.cap
+            .deny_host_port_range_access(host, ports_start, ports_end, access)
+    }
+
+    /// Construct a new pool with the authority of this pool, limited to the
+    /// operations in `access` on the range of network addresses `ip_net`,
+    /// with ports starting at `ports_start` and, if `ports_end` is provided,
+    /// ending before `ports_end`.
+    ///
+    /// Host name rules are dropped, since they don't match any addresses.
+    /// This doesn't need ambient authority, since the new pool is never
+    /// stronger than this one.
+    pub fn restrict(
+        &self,
+        ip_net: ipnet::IpNet,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        access: Access,
+    ) -> Self {
+        Self {
+            cap: self.cap.restrict(ip_net, ports_start, ports_end, access),
+        }
+    }
+
+    /// Construct a new pool with the authority of this pool, limited to the
+    /// operations in `access`.
+    pub fn restrict_access(&self, access: Access) -> Self {
+        Self {
+            cap: self.cap.restrict_access(access),
+        }
+    }
+
+    /// Construct a new pool which allows exactly what both this pool and
+    /// `other` allow.
+    pub fn intersect(&self, other: &Self) -> Self {
+        Self {
+            cap: self.cap.intersect(&other.cap),
+        }
+    }
+
+    /// Returns `true` if everything this pool allows is also allowed by
+    /// `other`.
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        self.cap.is_subset_of(&other.cap)
+    }
+
+    /// Returns the rules in the pool, in the order they were added.
+    #[inline]
+    pub fn rules(&self) -> &[Rule] {
+        self.cap.rules()
+    }
+
+    /// Determine whether the operations in `access` on the given address
+    /// are allowed by the pool, and which rule decided it.
+    #[inline]
+    pub fn explain_addr(&self, addr: &SocketAddr, access: Access) -> Verdict {
+        self.cap.explain_addr(addr, access)
+    }
+
+    /// Determine whether the operations in `access` on the given host name
+    /// and port are allowed by the pool, and which rule decided it.
+    #[inline]
+    pub fn explain_host(&self, host: &str, port: u16, access: Access) -> Verdict {
+        self.cap.explain_host(host, port, access)
+    }
+
     /// Creates a new `TcpListener` which will be bound to the specified
     /// address.
     ///
@@ -119,7 +423,7 @@ impl Pool {
 
         let mut last_err = None;
         for addr in addrs {
-            self.cap.check_addr(&addr)?;
+            self.cap.check_addr_access(&addr, Access::TCP_BIND)?;
             // TODO: when compiling for WASI, use WASI-specific methods instead
             match net::TcpListener::bind(addr) {
                 Ok(tcp_listener) => return Ok(TcpListener::from_std(tcp_listener)),
@@ -142,7 +446,7 @@ impl Pool {
 
         let mut last_err = None;
         for addr in addrs {
-            self.cap.check_addr(&addr)?;
+            self.cap.check_addr_access(&addr, Access::TCP_CONNECT)?;
             // TODO: when compiling for WASI, use WASI-specific methods instead
             match net::TcpStream::connect(addr) {
                 Ok(tcp_stream) => return Ok(TcpStream::from_std(tcp_stream)),
@@ -155,6 +459,35 @@ impl Pool {
         }
     }
 
+    /// Opens a TCP connection to a remote host, by name.
+    ///
+    /// The name is resolved with `resolver`, and the host name and port must
+    /// have been granted with [`Pool::insert_host`] or similar.
+    #[doc(alias = "connect")]
+    #[inline]
+    pub fn connect_tcp_stream_host(
+        &self,
+        resolver: &Resolver,
+        host: &str,
+        port: u16,
+    ) -> io::Result<TcpStream> {
+        let addrs = self
+            .cap
+            .resolve_host(resolver, host, port, Access::TCP_CONNECT)?;
+
+        let mut last_err = None;
+        for addr in addrs {
+            match net::TcpStream::connect(addr) {
+                Ok(tcp_stream) => return Ok(TcpStream::from_std(tcp_stream)),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Err(no_socket_addrs()),
+        }
+    }
+
     /// Opens a TCP connection to a remote host with a timeout.
     ///
     /// This corresponds to [`std::net::TcpStream::connect_timeout`].
@@ -165,7 +498,7 @@ impl Pool {
         addr: &SocketAddr,
         timeout: Duration,
     ) -> io::Result<TcpStream> {
-        self.cap.check_addr(addr)?;
+        self.cap.check_addr_access(addr, Access::TCP_CONNECT)?;
         let tcp_stream = net::TcpStream::connect_timeout(addr, timeout)?;
         Ok(TcpStream::from_std(tcp_stream))
     }
@@ -180,7 +513,7 @@ impl Pool {
 
         let mut last_err = None;
         for addr in addrs {
-            self.cap.check_addr(&addr)?;
+            self.cap.check_addr_access(&addr, Access::UDP_BIND)?;
             match net::UdpSocket::bind(addr) {
                 Ok(udp_socket) => return Ok(UdpSocket::from_std(udp_socket)),
                 Err(e) => last_err = Some(e),
@@ -208,7 +541,7 @@ impl Pool {
 
         // `UdpSocket::send_to` only sends to the first address.
         let addr = addrs.next().ok_or_else(no_socket_addrs)?;
-        self.cap.check_addr(&addr)?;
+        self.cap.check_addr_access(&addr, Access::UDP_CONNECT)?;
         udp_socket.std.send_to(buf, addr)
     }
 
@@ -228,7 +561,37 @@ impl Pool {
 
         let mut last_err = None;
         for addr in addrs {
-            self.cap.check_addr(&addr)?;
+            self.cap.check_addr_access(&addr, Access::UDP_CONNECT)?;
+            match udp_socket.std.connect(addr) {
+                Ok(()) => return Ok(()),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Err(no_socket_addrs()),
+        }
+    }
+
+    /// Connects this UDP socket to a remote host, by name.
+    ///
+    /// The name is resolved with `resolver`, and the host name and port must
+    /// have been granted with [`Pool::insert_host`] or similar.
+    #[doc(alias = "connect")]
+    #[inline]
+    pub fn connect_udp_socket_host(
+        &self,
+        udp_socket: &UdpSocket,
+        resolver: &Resolver,
+        host: &str,
+        port: u16,
+    ) -> io::Result<()> {
+        let addrs = self
+            .cap
+            .resolve_host(resolver, host, port, Access::UDP_CONNECT)?;
+
+        let mut last_err = None;
+        for addr in addrs {
             match udp_socket.std.connect(addr) {
                 Ok(()) => return Ok(()),
                 Err(e) => last_err = Some(e),
@@ -246,3 +609,22 @@ impl Pool {
         &self.cap
     }
 }
+
+/// Prints the rules in the pool, one per line, in the form accepted by
+/// [`Pool::parse`].
+impl fmt::Display for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.cap, f)
+    }
+}
+
+/// Serializes the pool as a sequence of [`Rule`]s. There's no corresponding
+/// `Deserialize` implementation, because constructing a pool requires
+/// ambient authority; deserialize a `Vec<Rule>` and use [`Pool::from_rules`]
+/// instead.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Pool {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.cap, serializer)
+    }
+}
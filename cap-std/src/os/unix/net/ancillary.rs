@@ -0,0 +1,205 @@
+use crate::fs::{Dir, File};
+use io_lifetimes::{AsFd, BorrowedFd, OwnedFd};
+use rustix::net::{
+    recvmsg, sendmsg, RecvAncillaryBuffer, RecvAncillaryMessage, RecvFlags, ReturnFlags,
+    SendAncillaryBuffer, SendAncillaryMessage, SendFlags,
+};
+use std::collections::VecDeque;
+use std::io::{self, IoSlice, IoSliceMut};
+use std::mem::MaybeUninit;
+use std::{fmt, fs};
+
+/// File descriptors received as `SCM_RIGHTS` ancillary data.
+///
+/// This is used with [`UnixStream::recv_with_fds`] and
+/// [`UnixDatagram::recv_with_fds`]. It has room for a fixed number of file
+/// descriptors; if a message carries more than that, the excess ones are
+/// closed and [`ReceivedFds::is_truncated`] returns `true`.
+///
+/// Received file descriptors are taken out in the order they were sent, with
+/// methods which convert them to the types they're expected to be. Any which
+/// aren't taken are closed when the `ReceivedFds` is dropped or reused.
+///
+/// [`UnixStream::recv_with_fds`]: super::UnixStream::recv_with_fds
+/// [`UnixDatagram::recv_with_fds`]: super::UnixDatagram::recv_with_fds
+pub struct ReceivedFds {
+    space: Vec<MaybeUninit<u8>>,
+    max: usize,
+    fds: VecDeque<OwnedFd>,
+    truncated: bool,
+}
+
+impl ReceivedFds {
+    /// Constructs a new, empty `ReceivedFds` with room for at most `max` file
+    /// descriptors per message.
+    pub fn new(max: usize) -> Self {
+        Self {
+            space: vec![MaybeUninit::uninit(); rustix::cmsg_space!(ScmRights(max))],
+            max,
+            fds: VecDeque::new(),
+            truncated: false,
+        }
+    }
+
+    /// Returns the number of received file descriptors which haven't been
+    /// taken yet.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.fds.len()
+    }
+
+    /// Returns `true` if there are no received file descriptors left to take.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.fds.is_empty()
+    }
+
+    /// Returns `true` if the last message carried more file descriptors than
+    /// there was room for, in which case the excess ones were closed.
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Takes the next received file descriptor.
+    #[inline]
+    pub fn take_fd(&mut self) -> io::Result<OwnedFd> {
+        self.fds
+            .pop_front()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no file descriptor left"))
+    }
+
+    /// Takes the next received file descriptor as a [`Dir`].
+    ///
+    /// This fails if the file descriptor doesn't refer to a directory.
+    pub fn take_dir(&mut self) -> io::Result<Dir> {
+        let file = fs::File::from(self.take_fd()?);
+        if !file.metadata()?.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "received file descriptor is not a directory",
+            ));
+        }
+        Ok(Dir::from_std_file(file))
+    }
+
+    /// Takes the next received file descriptor as a [`File`].
+    ///
+    /// This fails if the file descriptor refers to a directory.
+    pub fn take_file(&mut self) -> io::Result<File> {
+        let file = fs::File::from(self.take_fd()?);
+        if file.metadata()?.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "received file descriptor is a directory",
+            ));
+        }
+        Ok(File::from_std(file))
+    }
+
+    /// Takes the next received file descriptor as a `T`, such as a
+    /// [`TcpStream`] or [`UnixStream`].
+    ///
+    /// Unlike [`ReceivedFds::take_dir`] and [`ReceivedFds::take_file`], this
+    /// doesn't check that the file descriptor refers to a `T`.
+    ///
+    /// [`TcpStream`]: crate::net::TcpStream
+    /// [`UnixStream`]: super::UnixStream
+    #[inline]
+    pub fn take<T: From<OwnedFd>>(&mut self) -> io::Result<T> {
+        self.take_fd().map(T::from)
+    }
+}
+
+impl fmt::Debug for ReceivedFds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReceivedFds")
+            .field("max", &self.max)
+            .field("fds", &self.fds)
+            .field("truncated", &self.truncated)
+            .finish()
+    }
+}
+
+/// `recvmsg` can't set close-on-exec atomically on these platforms, so it's
+/// set after the file descriptors are received.
+#[cfg(any(
+    target_vendor = "apple",
+    target_os = "aix",
+    target_os = "haiku",
+    target_os = "illumos",
+    target_os = "nto",
+    target_os = "redox",
+    target_os = "solaris",
+))]
+const RECV_FLAGS: RecvFlags = RecvFlags::empty();
+#[cfg(not(any(
+    target_vendor = "apple",
+    target_os = "aix",
+    target_os = "haiku",
+    target_os = "illumos",
+    target_os = "nto",
+    target_os = "redox",
+    target_os = "solaris",
+)))]
+const RECV_FLAGS: RecvFlags = RecvFlags::CMSG_CLOEXEC;
+
+/// Send `buf` on `socket`, with `fds` as `SCM_RIGHTS` ancillary data.
+pub(super) fn send_with_fds(
+    socket: BorrowedFd<'_>,
+    buf: &[u8],
+    fds: &[BorrowedFd<'_>],
+) -> io::Result<usize> {
+    let mut space = vec![MaybeUninit::uninit(); rustix::cmsg_space!(ScmRights(fds.len()))];
+    let mut control = SendAncillaryBuffer::new(&mut space);
+    if !fds.is_empty() {
+        let pushed = control.push(SendAncillaryMessage::ScmRights(fds));
+        assert!(pushed, "ancillary buffer is sized for the file descriptors");
+    }
+    Ok(sendmsg(
+        socket,
+        &[IoSlice::new(buf)],
+        &mut control,
+        SendFlags::empty(),
+    )?)
+}
+
+/// Receive into `buf` from `socket`, replacing the contents of `fds` with any
+/// file descriptors received as `SCM_RIGHTS` ancillary data.
+pub(super) fn recv_with_fds(
+    socket: BorrowedFd<'_>,
+    buf: &mut [u8],
+    fds: &mut ReceivedFds,
+) -> io::Result<usize> {
+    fds.fds.clear();
+    fds.truncated = false;
+
+    let mut control = RecvAncillaryBuffer::new(&mut fds.space);
+    let msg = recvmsg(
+        socket,
+        &mut [IoSliceMut::new(buf)],
+        &mut control,
+        RECV_FLAGS,
+    )?;
+    for message in control.drain() {
+        if let RecvAncillaryMessage::ScmRights(received) = message {
+            fds.fds.extend(received);
+        }
+    }
+    fds.truncated = msg.flags.contains(ReturnFlags::CTRUNC);
+
+    // The buffer may have room for a few more than requested, due to
+    // alignment, so enforce the bound here.
+    if fds.fds.len() > fds.max {
+        fds.fds.truncate(fds.max);
+        fds.truncated = true;
+    }
+
+    if RECV_FLAGS.is_empty() {
+        for fd in &fds.fds {
+            rustix::io::fcntl_setfd(fd.as_fd(), rustix::io::FdFlags::CLOEXEC)?;
+        }
+    }
+
+    Ok(msg.bytes)
+}
@@ -9,11 +9,13 @@
 //! [`std::os::unix::net`]: https://doc.rust-lang.org/std/os/unix/net/
 //! [this POSIX discussion]: https://www.austingroupbugs.net/view.php?id=980
 
+mod ancillary;
 mod incoming;
 mod unix_datagram;
 mod unix_listener;
 mod unix_stream;
 
+pub use ancillary::ReceivedFds;
 pub use incoming::*;
 pub use unix_datagram::*;
 pub use unix_listener::*;
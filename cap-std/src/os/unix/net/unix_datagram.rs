@@ -1,3 +1,4 @@
+use super::{ancillary, ReceivedFds};
 use crate::net::Shutdown;
 use crate::os::unix::net::SocketAddr;
 use io_lifetimes::{AsFd, BorrowedFd, OwnedFd};
@@ -194,6 +195,33 @@ impl UnixDatagram {
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         self.std.shutdown(how)
     }
+
+    /// Sends data on the socket to its connected peer, along with file
+    /// descriptors, such as those of [`Dir`]s, [`File`]s, and sockets, as
+    /// `SCM_RIGHTS` ancillary data.
+    ///
+    /// This does not correspond to anything in `std`. This is how a process
+    /// hands capabilities to another process.
+    ///
+    /// [`Dir`]: crate::fs::Dir
+    /// [`File`]: crate::fs::File
+    #[inline]
+    pub fn send_with_fds(&self, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        ancillary::send_with_fds(self.as_fd(), buf, fds)
+    }
+
+    /// Receives a datagram on the socket, along with any file descriptors sent
+    /// as `SCM_RIGHTS` ancillary data.
+    ///
+    /// This does not correspond to anything in `std`. The file descriptors
+    /// are placed in `fds`, replacing any left over from a previous call, and
+    /// can be converted back into `Dir`s, `File`s, and sockets with its
+    /// methods. At most as many file descriptors as `fds` has room for are
+    /// received.
+    #[inline]
+    pub fn recv_with_fds(&self, buf: &mut [u8], fds: &mut ReceivedFds) -> io::Result<usize> {
+        ancillary::recv_with_fds(self.as_fd(), buf, fds)
+    }
 }
 
 impl FromRawFd for UnixDatagram {
@@ -0,0 +1,405 @@
+use crate::fs::{Dir, File, OpenOptions};
+use io_lifetimes::{AsFd, OwnedFd};
+use std::collections::BTreeMap;
+use std::ffi::{CString, OsStr, OsString};
+use std::io::Read;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{self, Child, ExitStatus, Output, Stdio};
+use std::{fs, io, ptr};
+
+/// A process builder, similar to [`std::process::Command`].
+///
+/// The program to run is an open [`File`], which is executed with `fexecve`,
+/// rather than a name to look up in `PATH`. The child starts out with an
+/// empty environment and, since Rust opens file descriptors with
+/// close-on-exec set, no file descriptors other than its standard streams;
+/// anything else it should have must be passed in explicitly with
+/// [`Command::env`], [`Command::current_dir`], and [`Command::fd`].
+///
+/// Since the program is run from a file descriptor which is closed on exec,
+/// scripts which start with `#!` can't be run this way, as their interpreter
+/// would have no path to open them with.
+///
+/// Unless [`Command::current_dir`] is used, the child inherits the current
+/// working directory of the parent.
+#[derive(Debug)]
+pub struct Command {
+    program: OwnedFd,
+    argv: Vec<OsString>,
+    env: BTreeMap<OsString, OsString>,
+    cwd: Option<Dir>,
+    fds: Vec<(RawFd, OwnedFd)>,
+    stdin: Option<Stdio>,
+    stdout: Option<Stdio>,
+    stderr: Option<Stdio>,
+}
+
+impl Command {
+    /// Constructs a new `Command` which runs `program`.
+    ///
+    /// The child's `argv[0]` is empty; use [`Command::arg0`] to set it.
+    pub fn new(program: File) -> Self {
+        Self {
+            program: program.into(),
+            argv: vec![OsString::new()],
+            env: BTreeMap::new(),
+            cwd: None,
+            fds: Vec::new(),
+            stdin: None,
+            stdout: None,
+            stderr: None,
+        }
+    }
+
+    /// Constructs a new `Command` which runs the program at `path` within
+    /// `dir`.
+    ///
+    /// The child's `argv[0]` is `path`.
+    pub fn open<P: AsRef<Path>>(dir: &Dir, path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut options = OpenOptions::new();
+        options.read(true);
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        {
+            use crate::fs::OpenOptionsExt;
+            options.custom_flags(libc::O_PATH);
+        }
+        let program = dir.open_with(path, &options)?;
+        let mut command = Self::new(program);
+        command.arg0(path);
+        Ok(command)
+    }
+
+    /// Sets the child's `argv[0]`.
+    pub fn arg0<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut Self {
+        self.argv[0] = arg.as_ref().to_owned();
+        self
+    }
+
+    /// Adds an argument to pass to the program.
+    pub fn arg<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut Self {
+        self.argv.push(arg.as_ref().to_owned());
+        self
+    }
+
+    /// Adds multiple arguments to pass to the program.
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        for arg in args {
+            self.arg(arg);
+        }
+        self
+    }
+
+    /// Adds or updates an environment variable for the child.
+    pub fn env<K, V>(&mut self, key: K, val: V) -> &mut Self
+    where
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.env
+            .insert(key.as_ref().to_owned(), val.as_ref().to_owned());
+        self
+    }
+
+    /// Adds or updates multiple environment variables for the child.
+    pub fn envs<I, K, V>(&mut self, vars: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        for (key, val) in vars {
+            self.env(key, val);
+        }
+        self
+    }
+
+    /// Removes an environment variable previously added with
+    /// [`Command::env`] or [`Command::envs`].
+    pub fn env_remove<K: AsRef<OsStr>>(&mut self, key: K) -> &mut Self {
+        self.env.remove(key.as_ref());
+        self
+    }
+
+    /// Removes all environment variables previously added with
+    /// [`Command::env`] or [`Command::envs`].
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.env.clear();
+        self
+    }
+
+    /// Sets the working directory of the child to `dir`, using `fchdir`.
+    pub fn current_dir(&mut self, dir: Dir) -> &mut Self {
+        self.cwd = Some(dir);
+        self
+    }
+
+    /// Passes `fd` to the child as its file descriptor number `child_fd`.
+    ///
+    /// Mapping a file descriptor to 0, 1, or 2 overrides the configuration
+    /// from [`Command::stdin`], [`Command::stdout`], or [`Command::stderr`].
+    pub fn fd<F: Into<OwnedFd>>(&mut self, child_fd: RawFd, fd: F) -> &mut Self {
+        let fd = fd.into();
+        match self.fds.iter_mut().find(|(target, _)| *target == child_fd) {
+            Some(entry) => entry.1 = fd,
+            None => self.fds.push((child_fd, fd)),
+        }
+        self
+    }
+
+    /// Configures the child's standard input.
+    ///
+    /// This applies to the next spawn of the child only.
+    pub fn stdin<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Self {
+        self.stdin = Some(cfg.into());
+        self
+    }
+
+    /// Configures the child's standard output.
+    ///
+    /// This applies to the next spawn of the child only.
+    pub fn stdout<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Self {
+        self.stdout = Some(cfg.into());
+        self
+    }
+
+    /// Configures the child's standard error.
+    ///
+    /// This applies to the next spawn of the child only.
+    pub fn stderr<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Self {
+        self.stderr = Some(cfg.into());
+        self
+    }
+
+    /// Executes the command as a child process, returning a handle to it.
+    ///
+    /// This corresponds to [`std::process::Command::spawn`].
+    pub fn spawn(&mut self) -> io::Result<Child> {
+        self.spawn_with(Stdio::inherit, Stdio::inherit)
+    }
+
+    /// Executes the command as a child process, waiting for it to finish and
+    /// collecting all of its output.
+    ///
+    /// This corresponds to [`std::process::Command::output`].
+    pub fn output(&mut self) -> io::Result<Output> {
+        self.spawn_with(Stdio::null, Stdio::piped)?
+            .wait_with_output()
+    }
+
+    /// Executes the command as a child process, waiting for it to finish and
+    /// collecting its status.
+    ///
+    /// This corresponds to [`std::process::Command::status`].
+    pub fn status(&mut self) -> io::Result<ExitStatus> {
+        self.spawn()?.wait()
+    }
+
+    /// Spawns the child with a `std::process::Command` which performs the
+    /// exec itself, in a `pre_exec` hook, so that `std` only does the fork and
+    /// the stdio setup.
+    ///
+    /// `std` reports exec failures through a pipe whose file descriptor
+    /// number in the child is arbitrary, and may be one of the targets of
+    /// [`Command::fd`], so once those are in place, failures are reported
+    /// through a pipe of our own instead.
+    fn spawn_with(
+        &mut self,
+        default_stdin: fn() -> Stdio,
+        default_output: fn() -> Stdio,
+    ) -> io::Result<Child> {
+        let (reader, writer) = pipe()?;
+        let mut exec = Exec::new(self, writer.as_raw_fd())?;
+
+        let mut command = process::Command::new(&self.argv[0]);
+        command.env_clear();
+        command.stdin(self.stdin.take().unwrap_or_else(default_stdin));
+        command.stdout(self.stdout.take().unwrap_or_else(default_output));
+        command.stderr(self.stderr.take().unwrap_or_else(default_output));
+
+        // SAFETY: `Exec::run` only makes async-signal-safe calls, and doesn't
+        // allocate. The file descriptors it uses are owned by `self` and
+        // `writer`, which are both still open when `spawn` forks.
+        unsafe {
+            command.pre_exec(move || exec.run());
+        }
+        let mut child = command.spawn()?;
+        drop(writer);
+
+        // The pipe is closed on exec, so it reaches end-of-file without any
+        // data if the exec succeeded, and otherwise holds the error number.
+        let mut errno = Vec::new();
+        let result = fs::File::from(reader).read_to_end(&mut errno);
+        match (result, <[u8; 4]>::try_from(errno.as_slice())) {
+            (Ok(0), _) => Ok(child),
+            (Ok(_), Ok(errno)) => {
+                child.wait()?;
+                Err(io::Error::from_raw_os_error(i32::from_ne_bytes(errno)))
+            }
+            (result, _) => {
+                let _ = child.kill();
+                child.wait()?;
+                Err(result.err().unwrap_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "invalid exec error report")
+                }))
+            }
+        }
+    }
+}
+
+/// Everything the child needs to exec the program, prepared before forking.
+struct Exec {
+    _argv: Vec<CString>,
+    _envp: Vec<CString>,
+    argv: Vec<*const libc::c_char>,
+    envp: Vec<*const libc::c_char>,
+    program: RawFd,
+    error_pipe: RawFd,
+    cwd: Option<RawFd>,
+    fds: Vec<(RawFd, RawFd)>,
+    temps: Vec<RawFd>,
+    min_fd: RawFd,
+}
+
+// SAFETY: The pointers in `argv` and `envp` point into the `CString`s in
+// `_argv` and `_envp`, which are owned by the `Exec` and never modified.
+unsafe impl Send for Exec {}
+unsafe impl Sync for Exec {}
+
+impl Exec {
+    fn new(command: &Command, error_pipe: RawFd) -> io::Result<Self> {
+        let argv = command
+            .argv
+            .iter()
+            .map(|arg| c_string(arg.as_bytes()))
+            .collect::<io::Result<Vec<_>>>()?;
+        let envp = command
+            .env
+            .iter()
+            .map(|(key, val)| {
+                let mut var = key.as_bytes().to_vec();
+                var.push(b'=');
+                var.extend_from_slice(val.as_bytes());
+                c_string(&var)
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let fds = command
+            .fds
+            .iter()
+            .map(|(target, fd)| (*target, fd.as_raw_fd()))
+            .collect::<Vec<_>>();
+
+        // Everything is moved out of the way to file descriptors above all
+        // of the targets before any target is overwritten.
+        let min_fd = fds
+            .iter()
+            .map(|(target, _)| target.saturating_add(1))
+            .fold(3, RawFd::max);
+
+        Ok(Self {
+            argv: null_terminated(&argv),
+            envp: null_terminated(&envp),
+            _argv: argv,
+            _envp: envp,
+            program: command.program.as_fd().as_raw_fd(),
+            error_pipe,
+            cwd: command.cwd.as_ref().map(|dir| dir.as_fd().as_raw_fd()),
+            temps: vec![-1; fds.len()],
+            fds,
+            min_fd,
+        })
+    }
+
+    /// Runs in the child, after `fork`. On success this doesn't return.
+    fn run(&mut self) -> io::Result<()> {
+        // SAFETY: These calls only use file descriptors which were open in
+        // the parent at the time of the fork, and pointers to
+        // NUL-terminated arrays of NUL-terminated strings.
+        unsafe {
+            if let Some(cwd) = self.cwd {
+                check(libc::fchdir(cwd))?;
+            }
+
+            let error_pipe = check(libc::fcntl(
+                self.error_pipe,
+                libc::F_DUPFD_CLOEXEC,
+                self.min_fd,
+            ))?;
+            let program = check(libc::fcntl(
+                self.program,
+                libc::F_DUPFD_CLOEXEC,
+                self.min_fd,
+            ))?;
+            for ((_, fd), temp) in self.fds.iter().zip(self.temps.iter_mut()) {
+                *temp = check(libc::fcntl(*fd, libc::F_DUPFD_CLOEXEC, self.min_fd))?;
+            }
+
+            // From here on, `std`'s error pipe may have been replaced, so
+            // failures are reported through ours, and the child exits
+            // without returning to `std`.
+            //
+            // `dup2` clears the close-on-exec flag on the new descriptor.
+            for ((target, _), temp) in self.fds.iter().zip(self.temps.iter()) {
+                if libc::dup2(*temp, *target) == -1 {
+                    fail(error_pipe);
+                }
+            }
+
+            libc::fexecve(program, self.argv.as_ptr(), self.envp.as_ptr());
+            fail(error_pipe)
+        }
+    }
+}
+
+/// Report the current `errno` through `error_pipe` and exit. This runs in the
+/// child, after `fork`, so it only makes async-signal-safe calls.
+unsafe fn fail(error_pipe: RawFd) -> ! {
+    let errno = io::Error::last_os_error().raw_os_error().unwrap_or(0);
+    let bytes = errno.to_ne_bytes();
+    libc::write(error_pipe, bytes.as_ptr().cast(), bytes.len());
+    libc::_exit(127)
+}
+
+/// Create a pipe with close-on-exec set on both ends.
+fn pipe() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0; 2];
+    // SAFETY: `fds` has room for the two file descriptors `pipe2` returns,
+    // which are owned by the caller once it succeeds.
+    unsafe {
+        check(libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC))?;
+        Ok((OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])))
+    }
+}
+
+fn c_string(bytes: &[u8]) -> io::Result<CString> {
+    CString::new(bytes).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "nul byte found in provided data",
+        )
+    })
+}
+
+fn null_terminated(strings: &[CString]) -> Vec<*const libc::c_char> {
+    strings
+        .iter()
+        .map(|s| s.as_ptr())
+        .chain(Some(ptr::null()))
+        .collect()
+}
+
+fn check(ret: libc::c_int) -> io::Result<libc::c_int> {
+    if ret == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(ret)
+    }
+}
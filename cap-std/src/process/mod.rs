@@ -0,0 +1,17 @@
+//! A capability-based process API modeled after [`std::process`].
+//!
+//! This corresponds to [`std::process`].
+//!
+//! Instead of [`std::process::Command`]'s methods which take a program name
+//! to search for in `PATH` and a path for the working directory, this
+//! module's [`Command`] takes the program as an open [`File`], and the
+//! working directory as a [`Dir`]. The environment and any file descriptors
+//! the child inherits are passed explicitly.
+//!
+//! [`File`]: crate::fs::File
+//! [`Dir`]: crate::fs::Dir
+
+mod command;
+
+pub use command::Command;
+pub use std::process::{Child, ChildStderr, ChildStdin, ChildStdout, ExitStatus, Output, Stdio};
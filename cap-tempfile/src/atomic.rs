@@ -0,0 +1,172 @@
+//! Atomic whole-file writes.
+
+use crate::TempFile;
+use cap_std::fs::{Dir, Permissions};
+use std::ffi::OsStr;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Extension trait for `Dir` providing atomic whole-file writes.
+///
+/// Each of these writes the new contents to a [`TempFile`] in the same
+/// directory as the destination, and then renames it into place with
+/// [`TempFile::replace`]. Readers never observe a partially written file, and
+/// after a crash the destination holds either its old contents or its new
+/// contents in full.
+pub trait AtomicWriteExt {
+    /// Write a slice as the entire contents of a file, atomically.
+    ///
+    /// This corresponds to [`Dir::write`], except that an existing file is
+    /// replaced rather than truncated. The new file gets the default
+    /// permissions for newly created files, regardless of the permissions of
+    /// any file it replaces.
+    fn write_atomic<P: AsRef<Path>, C: AsRef<[u8]>>(&self, path: P, contents: C) -> io::Result<()>;
+
+    /// Write a slice as the entire contents of a file with the given
+    /// permissions, atomically.
+    ///
+    /// This is the same as [`AtomicWriteExt::write_atomic`], except that
+    /// `permissions` are applied to the new file before it is renamed into
+    /// place.
+    fn write_atomic_with_permissions<P: AsRef<Path>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+        permissions: Permissions,
+    ) -> io::Result<()>;
+
+    /// Atomically replace a file with the output of `f`.
+    ///
+    /// `f` is called with a [`TempFile`] to stream the new contents into. If
+    /// it returns `Ok`, the file is renamed into place at `path`; if it
+    /// returns `Err`, the temporary file is removed and `path` is left
+    /// untouched. To choose the permissions of the new file, call
+    /// [`File::set_permissions`] on [`TempFile::as_file`] from within `f`.
+    ///
+    /// [`File::set_permissions`]: cap_std::fs::File::set_permissions
+    fn write_atomic_with<P: AsRef<Path>, F, T>(&self, path: P, f: F) -> io::Result<T>
+    where
+        F: FnOnce(&mut TempFile<'_>) -> io::Result<T>;
+}
+
+impl AtomicWriteExt for Dir {
+    fn write_atomic<P: AsRef<Path>, C: AsRef<[u8]>>(&self, path: P, contents: C) -> io::Result<()> {
+        self.write_atomic_with(path, |file| file.write_all(contents.as_ref()))
+    }
+
+    fn write_atomic_with_permissions<P: AsRef<Path>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+        permissions: Permissions,
+    ) -> io::Result<()> {
+        in_parent(self, path.as_ref(), |dir, name| {
+            let mut file = TempFile::new(dir)?;
+            file.write_all(contents.as_ref())?;
+            file.replace_with_permissions(name, permissions)
+        })
+    }
+
+    fn write_atomic_with<P: AsRef<Path>, F, T>(&self, path: P, f: F) -> io::Result<T>
+    where
+        F: FnOnce(&mut TempFile<'_>) -> io::Result<T>,
+    {
+        in_parent(self, path.as_ref(), |dir, name| {
+            let mut file = TempFile::new(dir)?;
+            let value = f(&mut file)?;
+            file.replace(name)?;
+            Ok(value)
+        })
+    }
+}
+
+/// Call `f` with the directory containing `path` and the file name within
+/// it, since temporary files can only be renamed within a single directory.
+fn in_parent<T>(
+    dir: &Dir,
+    path: &Path,
+    f: impl FnOnce(&Dir, &OsStr) -> io::Result<T>,
+) -> io::Result<T> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => f(&dir.open_dir(parent)?, name),
+        _ => f(dir, name),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic() -> io::Result<()> {
+        use crate::ambient_authority;
+
+        let td = crate::tempdir(ambient_authority())?;
+
+        td.write_atomic("file", b"hello")?;
+        assert_eq!(td.read("file")?, b"hello");
+        td.write_atomic("file", b"hello world")?;
+        assert_eq!(td.read("file")?, b"hello world");
+
+        // Files in subdirectories are replaced within that subdirectory.
+        td.create_dir("sub")?;
+        td.write_atomic("sub/file", b"nested")?;
+        assert_eq!(td.read("sub/file")?, b"nested");
+        assert_eq!(td.entries()?.count(), 2);
+        assert_eq!(td.read_dir("sub")?.count(), 1);
+
+        // An error from the closure leaves the old contents in place.
+        let err = td
+            .write_atomic_with("file", |file| {
+                file.write_all(b"partial")?;
+                Err::<(), _>(io::Error::new(io::ErrorKind::Other, "oops"))
+            })
+            .unwrap_err();
+        assert_eq!(err.to_string(), "oops");
+        assert_eq!(td.read("file")?, b"hello world");
+        assert_eq!(td.entries()?.count(), 2);
+
+        let len = td.write_atomic_with("file", |file| {
+            file.write_all(b"streamed")?;
+            Ok(8)
+        })?;
+        assert_eq!(len, 8);
+        assert_eq!(td.read("file")?, b"streamed");
+
+        // Paths can't escape the directory.
+        assert!(td.write_atomic("../file", b"escape").is_err());
+        assert!(td.write_atomic("..", b"escape").is_err());
+
+        td.close()
+    }
+
+    #[cfg(not(target_os = "wasi"))]
+    #[test]
+    fn test_write_atomic_with_permissions() -> io::Result<()> {
+        use crate::ambient_authority;
+
+        let td = crate::tempdir(ambient_authority())?;
+
+        td.write("template", b"")?;
+        let mut permissions = td.metadata("template")?.permissions();
+        permissions.set_readonly(true);
+        td.write_atomic_with_permissions("file", b"hello", permissions)?;
+        assert_eq!(td.read("file")?, b"hello");
+        assert!(td.metadata("file")?.permissions().readonly());
+
+        #[cfg(unix)]
+        {
+            use cap_std::fs::{MetadataExt, PermissionsExt};
+            td.write_atomic_with_permissions("file", b"private", Permissions::from_mode(0o600))?;
+            assert_eq!(td.metadata("file")?.mode() & 0o777, 0o600);
+        }
+
+        let mut permissions = td.metadata("file")?.permissions();
+        permissions.set_readonly(false);
+        td.set_permissions("file", permissions)?;
+        td.close()
+    }
+}
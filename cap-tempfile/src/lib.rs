@@ -18,7 +18,9 @@ use uuid::Uuid;
 #[cfg(feature = "fs_utf8")]
 pub mod utf8;
 
+mod atomic;
 mod tempfile;
+pub use crate::atomic::*;
 pub use crate::tempfile::*;
 
 /// Re-export because we use this in our public API.
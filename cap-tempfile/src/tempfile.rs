@@ -1,9 +1,11 @@
 //! Temporary files.
 
-use cap_std::fs::{Dir, File};
+use cap_std::fs::{Dir, File, Permissions};
 use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::io::{self, Read, Seek, Write};
+use std::ops::Deref;
+use std::sync::Arc;
 
 /// A file in a directory that is by default deleted when it goes out
 /// of scope, but may also be written persistently.
@@ -46,16 +48,33 @@ use std::io::{self, Read, Seek, Write};
 /// [`File::create_new`]: https://doc.rust-lang.org/std/fs/struct.OpenOptions.html#method.create_new
 /// [`File::set_permissions`]: https://docs.rs/cap-std/latest/cap_std/fs/struct.File.html#method.set_permissions
 pub struct TempFile<'d> {
-    dir: &'d Dir,
+    dir: DirRef<'d>,
     fd: File,
     name: Option<String>,
 }
 
+/// The directory a [`TempFile`] lives in, either borrowed or shared.
+enum DirRef<'d> {
+    Borrowed(&'d Dir),
+    Shared(Arc<Dir>),
+}
+
+impl<'d> Deref for DirRef<'d> {
+    type Target = Dir;
+
+    fn deref(&self) -> &Dir {
+        match self {
+            Self::Borrowed(dir) => dir,
+            Self::Shared(dir) => dir,
+        }
+    }
+}
+
 impl<'d> Debug for TempFile<'d> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // Manual Debug implementation to omit the file reference and name so
         // we don't leak the path, the same as `cap_std::fs::File`.
-        f.debug_struct("TempFile").field("dir", &self.dir).finish()
+        f.debug_struct("TempFile").field("dir", &*self.dir).finish()
     }
 }
 
@@ -105,6 +124,26 @@ fn generate_name_in(subdir: &Dir, f: &File) -> io::Result<String> {
     .map(|(_, name)| name)
 }
 
+/// Flush the entries of `d` to disk, so that a rename within it is durable.
+fn sync_dir(d: &Dir) -> io::Result<()> {
+    // `Dir` may be opened with `O_PATH`, which can't be synced, so open it
+    // again for reading.
+    #[cfg(not(windows))]
+    {
+        use rustix::fs::{Mode, OFlags};
+        let oflags = OFlags::CLOEXEC | OFlags::DIRECTORY | OFlags::RDONLY;
+        let fd = rustix::fs::openat(d, ".", oflags, Mode::empty())?;
+        rustix::fs::fsync(fd)?;
+    }
+
+    // Windows doesn't support syncing directories; renames are committed
+    // along with the file's own metadata.
+    #[cfg(windows)]
+    let _ = d;
+
+    Ok(())
+}
+
 /// Create a new temporary file in the target directory, which may or may not
 /// have a (randomly generated) name at this point. If anonymous is specified,
 /// the file will be deleted
@@ -152,7 +191,11 @@ impl<'d> TempFile<'d> {
     /// Create a new temporary file in the provided directory.
     pub fn new(dir: &'d Dir) -> io::Result<Self> {
         let (fd, name) = new_tempfile(dir, false)?;
-        Ok(Self { dir, fd, name })
+        Ok(Self {
+            dir: DirRef::Borrowed(dir),
+            fd,
+            name,
+        })
     }
 
     /// Create a new temporary file in the provided directory that will not have
@@ -163,6 +206,20 @@ impl<'d> TempFile<'d> {
         new_tempfile(dir, true).map(|v| v.0)
     }
 
+    /// Create a new temporary file in the provided shared directory.
+    ///
+    /// This is the same as [`TempFile::new`], except that the temporary file
+    /// holds a reference to the directory instead of borrowing it, so it can
+    /// be moved into other threads and tasks.
+    pub fn new_shared(dir: Arc<Dir>) -> io::Result<TempFile<'static>> {
+        let (fd, name) = new_tempfile(&dir, false)?;
+        Ok(TempFile {
+            dir: DirRef::Shared(dir),
+            fd,
+            name,
+        })
+    }
+
     /// Get a reference to the underlying file.
     pub fn as_file(&self) -> &File {
         &self.fd
@@ -174,6 +231,9 @@ impl<'d> TempFile<'d> {
     }
 
     fn impl_replace(mut self, destname: &OsStr) -> io::Result<()> {
+        // Ensure the contents are on disk before they become visible under
+        // the destination name.
+        self.fd.sync_all()?;
         // At this point on Linux if O_TMPFILE is used, we need to give the file a
         // temporary name in order to link it into place. There are patches to
         // add an `AT_LINKAT_REPLACE` API. With that we could skip this and
@@ -183,28 +243,52 @@ impl<'d> TempFile<'d> {
             .name
             .take()
             .map(Ok)
-            .unwrap_or_else(|| generate_name_in(self.dir, &self.fd))?;
+            .unwrap_or_else(|| generate_name_in(&self.dir, &self.fd))?;
         // SAFETY: We only support anonymous files on Linux, so the file must have a
         // name here.
         #[cfg(not(any(target_os = "android", target_os = "linux")))]
         let tempname = self.name.take().unwrap();
         // And try the rename into place.
-        self.dir.rename(&tempname, self.dir, destname).map_err(|e| {
-            // But, if we catch an error here, then move ownership back into self,
-            // which means the Drop invocation will clean it up.
-            self.name = Some(tempname);
-            e
-        })
+        self.dir
+            .rename(&tempname, &self.dir, destname)
+            .map_err(|e| {
+                // But, if we catch an error here, then move ownership back into self,
+                // which means the Drop invocation will clean it up.
+                self.name = Some(tempname);
+                e
+            })?;
+        // And make the rename itself durable.
+        sync_dir(&self.dir)
     }
 
     /// Write the file to the target directory with the provided name.
     /// Any existing file will be replaced.
     ///
-    /// The file permissions will default to read-only.
+    /// The file's contents are synced to disk before it is renamed into
+    /// place, and the directory is synced afterwards, so that after a crash
+    /// the destination holds either the old contents or the new contents in
+    /// full.
+    ///
+    /// The file keeps the permissions it was created with; see
+    /// [`TempFile::replace_with_permissions`] to choose them instead.
     pub fn replace(self, destname: impl AsRef<OsStr>) -> io::Result<()> {
         let destname = destname.as_ref();
         self.impl_replace(destname)
     }
+
+    /// Write the file to the target directory with the provided name and
+    /// permissions. Any existing file will be replaced.
+    ///
+    /// This is the same as [`TempFile::replace`], except that `permissions`
+    /// are applied to the file before it is renamed into place.
+    pub fn replace_with_permissions(
+        self,
+        destname: impl AsRef<OsStr>,
+        permissions: Permissions,
+    ) -> io::Result<()> {
+        self.fd.set_permissions(permissions)?;
+        self.replace(destname)
+    }
 }
 
 impl<'d> Read for TempFile<'d> {
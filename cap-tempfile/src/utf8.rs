@@ -2,8 +2,11 @@
 //!
 //! TODO: This whole scheme is still under development.
 
+use crate::TempFile;
+use camino::Utf8Path;
 #[cfg(test)]
 use camino::Utf8PathBuf;
+use cap_std::fs::Permissions;
 use cap_std::fs_utf8::Dir;
 #[cfg(test)]
 use std::env;
@@ -118,6 +121,83 @@ pub fn tempdir_in(dir: &Dir) -> io::Result<TempDir> {
     TempDir::new_in(dir)
 }
 
+/// Extension trait for `Dir` providing atomic whole-file writes.
+///
+/// This corresponds to [`crate::AtomicWriteExt`], but with UTF-8 paths.
+pub trait AtomicWriteExt {
+    /// Write a slice as the entire contents of a file, atomically.
+    ///
+    /// This corresponds to [`crate::AtomicWriteExt::write_atomic`].
+    fn write_atomic<P: AsRef<Utf8Path>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+    ) -> io::Result<()>;
+
+    /// Write a slice as the entire contents of a file with the given
+    /// permissions, atomically.
+    ///
+    /// This corresponds to
+    /// [`crate::AtomicWriteExt::write_atomic_with_permissions`].
+    fn write_atomic_with_permissions<P: AsRef<Utf8Path>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+        permissions: Permissions,
+    ) -> io::Result<()>;
+
+    /// Atomically replace a file with the output of `f`.
+    ///
+    /// This corresponds to [`crate::AtomicWriteExt::write_atomic_with`].
+    fn write_atomic_with<P: AsRef<Utf8Path>, F, T>(&self, path: P, f: F) -> io::Result<T>
+    where
+        F: FnOnce(&mut TempFile<'_>) -> io::Result<T>;
+}
+
+impl AtomicWriteExt for Dir {
+    fn write_atomic<P: AsRef<Utf8Path>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+    ) -> io::Result<()> {
+        let (parent, name) = open_parent(self, path.as_ref())?;
+        let dir = parent.as_ref().unwrap_or(self).as_cap_std();
+        crate::AtomicWriteExt::write_atomic(dir, name, contents)
+    }
+
+    fn write_atomic_with_permissions<P: AsRef<Utf8Path>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+        permissions: Permissions,
+    ) -> io::Result<()> {
+        let (parent, name) = open_parent(self, path.as_ref())?;
+        let dir = parent.as_ref().unwrap_or(self).as_cap_std();
+        crate::AtomicWriteExt::write_atomic_with_permissions(dir, name, contents, permissions)
+    }
+
+    fn write_atomic_with<P: AsRef<Utf8Path>, F, T>(&self, path: P, f: F) -> io::Result<T>
+    where
+        F: FnOnce(&mut TempFile<'_>) -> io::Result<T>,
+    {
+        let (parent, name) = open_parent(self, path.as_ref())?;
+        let dir = parent.as_ref().unwrap_or(self).as_cap_std();
+        crate::AtomicWriteExt::write_atomic_with(dir, name, f)
+    }
+}
+
+/// Open the directory containing `path`, if it isn't `dir` itself, and
+/// return it along with the file name within it.
+fn open_parent<'p>(dir: &Dir, path: &'p Utf8Path) -> io::Result<(Option<Dir>, &'p str)> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    match path.parent() {
+        Some(parent) if !parent.as_str().is_empty() => Ok((Some(dir.open_dir(parent)?), name)),
+        _ => Ok((None, name)),
+    }
+}
+
 #[test]
 fn drop_tempdir() {
     use crate::ambient_authority;
@@ -178,3 +258,15 @@ fn close_inner() {
     let s = tempdir_in(&t).unwrap();
     s.close().unwrap();
 }
+
+#[test]
+fn write_atomic() {
+    use crate::ambient_authority;
+
+    let t = tempdir(ambient_authority()).unwrap();
+    t.create_dir("sub").unwrap();
+    t.write_atomic("sub/file", b"hello").unwrap();
+    assert_eq!(t.read_to_string("sub/file").unwrap(), "hello");
+    assert!(t.write_atomic("../file", b"escape").is_err());
+    t.close().unwrap();
+}
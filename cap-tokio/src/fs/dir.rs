@@ -0,0 +1,645 @@
+use crate::fs::{
+    asyncify, CopyOptions, File, Metadata, OpenOptions, Permissions, ReadDir, RenameOptions,
+};
+use cap_std::AmbientAuthority;
+#[cfg(not(windows))]
+use io_lifetimes::{AsFd, BorrowedFd};
+#[cfg(windows)]
+use io_lifetimes::{AsHandle, BorrowedHandle};
+#[cfg(not(windows))]
+use std::os::unix::io::{AsRawFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawHandle, RawHandle};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{fmt, fs, io};
+
+/// A reference to an open directory on a filesystem, for use in async code.
+///
+/// This corresponds to [`cap_std::fs::Dir`]. Its methods correspond to the
+/// [functions in `tokio::fs`], but only access paths relative to `self`.
+///
+/// Each operation is run on tokio's blocking thread pool, with the directory
+/// shared with the blocking task, so cloning a `Dir` with [`Dir::try_clone`]
+/// and dropping a future before it completes are both cheap.
+///
+/// [functions in `tokio::fs`]: https://docs.rs/tokio/latest/tokio/fs/index.html#functions
+pub struct Dir {
+    cap_std: Arc<cap_std::fs::Dir>,
+}
+
+impl Dir {
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::fs::Dir`.
+    #[inline]
+    pub fn from_cap_std(cap_std: cap_std::fs::Dir) -> Self {
+        Self {
+            cap_std: Arc::new(cap_std),
+        }
+    }
+
+    /// Constructs a new instance of `Self` from the given `std::fs::File`.
+    ///
+    /// To prevent race conditions on Windows, the file must be opened without
+    /// `FILE_SHARE_DELETE`.
+    #[inline]
+    pub fn from_std_file(std_file: fs::File) -> Self {
+        Self::from_cap_std(cap_std::fs::Dir::from_std_file(std_file))
+    }
+
+    /// Returns a reference to the underlying `cap_std::fs::Dir`.
+    ///
+    /// Methods called on it block the current thread.
+    #[inline]
+    pub fn as_cap_std(&self) -> &cap_std::fs::Dir {
+        &self.cap_std
+    }
+
+    /// Consumes `self` and returns a `cap_std::fs::Dir`.
+    ///
+    /// If an operation started by a dropped future is still running on the
+    /// blocking thread pool, this returns a duplicate of the handle.
+    pub fn into_cap_std(self) -> io::Result<cap_std::fs::Dir> {
+        Arc::try_unwrap(self.cap_std).or_else(|cap_std| cap_std.try_clone())
+    }
+
+    /// Attempts to open a file in read-only mode.
+    ///
+    /// This corresponds to [`tokio::fs::File::open`], but only accesses paths
+    /// relative to `self`.
+    ///
+    /// [`tokio::fs::File::open`]: https://docs.rs/tokio/latest/tokio/fs/struct.File.html#method.open
+    #[inline]
+    pub async fn open<P: AsRef<Path>>(&self, path: P) -> io::Result<File> {
+        let mut options = OpenOptions::new();
+        options.read(true);
+        self.open_with(path, &options).await
+    }
+
+    /// Opens a file at `path` with the options specified by `options`.
+    ///
+    /// This corresponds to [`tokio::fs::OpenOptions::open`].
+    ///
+    /// Instead of being a method on `OpenOptions`, this is a method on `Dir`,
+    /// and it only accesses paths relative to `self`.
+    ///
+    /// [`tokio::fs::OpenOptions::open`]: https://docs.rs/tokio/latest/tokio/fs/struct.OpenOptions.html#method.open
+    pub async fn open_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &OpenOptions,
+    ) -> io::Result<File> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        let options = options.clone();
+        let file = asyncify(move || dir.open_with(path, &options)).await?;
+        Ok(File::from_cap_std(file))
+    }
+
+    /// Attempts to open a directory.
+    pub async fn open_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<Self> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        let dir = asyncify(move || dir.open_dir(path)).await?;
+        Ok(Self::from_cap_std(dir))
+    }
+
+    /// Creates a new, empty directory at the provided path.
+    ///
+    /// This corresponds to [`tokio::fs::create_dir`], but only accesses paths
+    /// relative to `self`.
+    ///
+    /// [`tokio::fs::create_dir`]: https://docs.rs/tokio/latest/tokio/fs/fn.create_dir.html
+    pub async fn create_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.create_dir(path)).await
+    }
+
+    /// Recursively create a directory and all of its parent components if
+    /// they are missing.
+    ///
+    /// This corresponds to [`tokio::fs::create_dir_all`], but only accesses
+    /// paths relative to `self`.
+    ///
+    /// [`tokio::fs::create_dir_all`]: https://docs.rs/tokio/latest/tokio/fs/fn.create_dir_all.html
+    pub async fn create_dir_all<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.create_dir_all(path)).await
+    }
+
+    /// Opens a file in write-only mode.
+    ///
+    /// This corresponds to [`tokio::fs::File::create`], but only accesses
+    /// paths relative to `self`.
+    ///
+    /// [`tokio::fs::File::create`]: https://docs.rs/tokio/latest/tokio/fs/struct.File.html#method.create
+    #[inline]
+    pub async fn create<P: AsRef<Path>>(&self, path: P) -> io::Result<File> {
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        self.open_with(path, &options).await
+    }
+
+    /// Returns the canonical form of a path with all intermediate components
+    /// normalized and symbolic links resolved.
+    ///
+    /// This corresponds to [`tokio::fs::canonicalize`], but instead of
+    /// returning an absolute path, returns a path relative to the directory
+    /// represented by `self`.
+    ///
+    /// [`tokio::fs::canonicalize`]: https://docs.rs/tokio/latest/tokio/fs/fn.canonicalize.html
+    pub async fn canonicalize<P: AsRef<Path>>(&self, path: P) -> io::Result<PathBuf> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.canonicalize(path)).await
+    }
+
+    /// Copies the contents of one file to another. This function will also
+    /// copy the permission bits of the original file to the destination
+    /// file.
+    ///
+    /// This corresponds to [`tokio::fs::copy`], but only accesses paths
+    /// relative to `self` and `to_dir`.
+    ///
+    /// [`tokio::fs::copy`]: https://docs.rs/tokio/latest/tokio/fs/fn.copy.html
+    pub async fn copy<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<u64> {
+        let (dir, to_dir) = (Arc::clone(&self.cap_std), Arc::clone(&to_dir.cap_std));
+        let (from, to) = (from.as_ref().to_path_buf(), to.as_ref().to_path_buf());
+        asyncify(move || dir.copy(from, &to_dir, to)).await
+    }
+
+    /// Copies the contents of one file to another, configured by `options`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::copy_with`].
+    pub async fn copy_with<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &CopyOptions,
+    ) -> io::Result<u64> {
+        let (dir, to_dir) = (Arc::clone(&self.cap_std), Arc::clone(&to_dir.cap_std));
+        let (from, to) = (from.as_ref().to_path_buf(), to.as_ref().to_path_buf());
+        let options = options.clone();
+        asyncify(move || dir.copy_with(from, &to_dir, to, &options)).await
+    }
+
+    /// Recursively copies the directory tree at `from` to `to`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::copy_dir_all`].
+    pub async fn copy_dir_all<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &CopyOptions,
+    ) -> io::Result<u64> {
+        let (dir, to_dir) = (Arc::clone(&self.cap_std), Arc::clone(&to_dir.cap_std));
+        let (from, to) = (from.as_ref().to_path_buf(), to.as_ref().to_path_buf());
+        let options = options.clone();
+        asyncify(move || dir.copy_dir_all(from, &to_dir, to, &options)).await
+    }
+
+    /// Creates a new hard link on a filesystem.
+    ///
+    /// This corresponds to [`tokio::fs::hard_link`], but only accesses paths
+    /// relative to `self` and `dst_dir`.
+    ///
+    /// [`tokio::fs::hard_link`]: https://docs.rs/tokio/latest/tokio/fs/fn.hard_link.html
+    pub async fn hard_link<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        src: P,
+        dst_dir: &Self,
+        dst: Q,
+    ) -> io::Result<()> {
+        let (dir, dst_dir) = (Arc::clone(&self.cap_std), Arc::clone(&dst_dir.cap_std));
+        let (src, dst) = (src.as_ref().to_path_buf(), dst.as_ref().to_path_buf());
+        asyncify(move || dir.hard_link(src, &dst_dir, dst)).await
+    }
+
+    /// Given a path, query the file system to get information about a file,
+    /// directory, etc.
+    ///
+    /// This corresponds to [`tokio::fs::metadata`], but only accesses paths
+    /// relative to `self`.
+    ///
+    /// [`tokio::fs::metadata`]: https://docs.rs/tokio/latest/tokio/fs/fn.metadata.html
+    pub async fn metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.metadata(path)).await
+    }
+
+    /// Queries metadata about the underlying directory.
+    ///
+    /// This is similar to [`tokio::fs::File::metadata`], but for `Dir`
+    /// rather than for `File`.
+    ///
+    /// [`tokio::fs::File::metadata`]: https://docs.rs/tokio/latest/tokio/fs/struct.File.html#method.metadata
+    pub async fn dir_metadata(&self) -> io::Result<Metadata> {
+        let dir = Arc::clone(&self.cap_std);
+        asyncify(move || dir.dir_metadata()).await
+    }
+
+    /// Returns a stream of the entries within `self`.
+    pub async fn entries(&self) -> io::Result<ReadDir> {
+        let dir = Arc::clone(&self.cap_std);
+        let read_dir = asyncify(move || dir.entries()).await?;
+        Ok(ReadDir::from_cap_std(read_dir))
+    }
+
+    /// Returns a stream of the entries within a directory.
+    ///
+    /// This corresponds to [`tokio::fs::read_dir`], but only accesses paths
+    /// relative to `self`.
+    ///
+    /// [`tokio::fs::read_dir`]: https://docs.rs/tokio/latest/tokio/fs/fn.read_dir.html
+    pub async fn read_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<ReadDir> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        let read_dir = asyncify(move || dir.read_dir(path)).await?;
+        Ok(ReadDir::from_cap_std(read_dir))
+    }
+
+    /// Read the entire contents of a file into a bytes vector.
+    ///
+    /// This corresponds to [`tokio::fs::read`], but only accesses paths
+    /// relative to `self`.
+    ///
+    /// [`tokio::fs::read`]: https://docs.rs/tokio/latest/tokio/fs/fn.read.html
+    pub async fn read<P: AsRef<Path>>(&self, path: P) -> io::Result<Vec<u8>> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.read(path)).await
+    }
+
+    /// Reads a symbolic link, returning the file that the link points to.
+    ///
+    /// This corresponds to [`tokio::fs::read_link`], but only accesses paths
+    /// relative to `self`. Unlike [`Dir::read_link_contents`], this method
+    /// considers it an error if the link's target is an absolute path.
+    ///
+    /// [`tokio::fs::read_link`]: https://docs.rs/tokio/latest/tokio/fs/fn.read_link.html
+    pub async fn read_link<P: AsRef<Path>>(&self, path: P) -> io::Result<PathBuf> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.read_link(path)).await
+    }
+
+    /// Reads a symbolic link, returning the file that the link points to.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::read_link_contents`].
+    pub async fn read_link_contents<P: AsRef<Path>>(&self, path: P) -> io::Result<PathBuf> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.read_link_contents(path)).await
+    }
+
+    /// Read the entire contents of a file into a string.
+    ///
+    /// This corresponds to [`tokio::fs::read_to_string`], but only accesses
+    /// paths relative to `self`.
+    ///
+    /// [`tokio::fs::read_to_string`]: https://docs.rs/tokio/latest/tokio/fs/fn.read_to_string.html
+    pub async fn read_to_string<P: AsRef<Path>>(&self, path: P) -> io::Result<String> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.read_to_string(path)).await
+    }
+
+    /// Removes an empty directory.
+    ///
+    /// This corresponds to [`tokio::fs::remove_dir`], but only accesses paths
+    /// relative to `self`.
+    ///
+    /// [`tokio::fs::remove_dir`]: https://docs.rs/tokio/latest/tokio/fs/fn.remove_dir.html
+    pub async fn remove_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.remove_dir(path)).await
+    }
+
+    /// Removes a directory at this path, after removing all its contents. Use
+    /// carefully!
+    ///
+    /// This corresponds to [`tokio::fs::remove_dir_all`], but only accesses
+    /// paths relative to `self`.
+    ///
+    /// [`tokio::fs::remove_dir_all`]: https://docs.rs/tokio/latest/tokio/fs/fn.remove_dir_all.html
+    pub async fn remove_dir_all<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.remove_dir_all(path)).await
+    }
+
+    /// Remove the directory referenced by `self` and consume `self`.
+    ///
+    /// Even though this implementation works in terms of handles as much as
+    /// possible, removal is not guaranteed to be atomic with respect to a
+    /// concurrent rename of the directory.
+    pub async fn remove_open_dir(self) -> io::Result<()> {
+        let dir = self.into_cap_std()?;
+        asyncify(move || dir.remove_open_dir()).await
+    }
+
+    /// Removes the directory referenced by `self`, after removing all its
+    /// contents, and consume `self`. Use carefully!
+    ///
+    /// Even though this implementation works in terms of handles as much as
+    /// possible, removal is not guaranteed to be atomic with respect to a
+    /// concurrent rename of the directory.
+    pub async fn remove_open_dir_all(self) -> io::Result<()> {
+        let dir = self.into_cap_std()?;
+        asyncify(move || dir.remove_open_dir_all()).await
+    }
+
+    /// Removes a file from a filesystem.
+    ///
+    /// This corresponds to [`tokio::fs::remove_file`], but only accesses paths
+    /// relative to `self`.
+    ///
+    /// [`tokio::fs::remove_file`]: https://docs.rs/tokio/latest/tokio/fs/fn.remove_file.html
+    pub async fn remove_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.remove_file(path)).await
+    }
+
+    /// Rename a file or directory to a new name, replacing the original file
+    /// if to already exists.
+    ///
+    /// This corresponds to [`tokio::fs::rename`], but only accesses paths
+    /// relative to `self` and `to_dir`.
+    ///
+    /// [`tokio::fs::rename`]: https://docs.rs/tokio/latest/tokio/fs/fn.rename.html
+    pub async fn rename<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<()> {
+        let (dir, to_dir) = (Arc::clone(&self.cap_std), Arc::clone(&to_dir.cap_std));
+        let (from, to) = (from.as_ref().to_path_buf(), to.as_ref().to_path_buf());
+        asyncify(move || dir.rename(from, &to_dir, to)).await
+    }
+
+    /// Rename a file or directory to a new name, configured by `options`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::rename_with`].
+    pub async fn rename_with<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &RenameOptions,
+    ) -> io::Result<()> {
+        let (dir, to_dir) = (Arc::clone(&self.cap_std), Arc::clone(&to_dir.cap_std));
+        let (from, to) = (from.as_ref().to_path_buf(), to.as_ref().to_path_buf());
+        let options = options.clone();
+        asyncify(move || dir.rename_with(from, &to_dir, to, &options)).await
+    }
+
+    /// Changes the permissions found on a file or a directory.
+    ///
+    /// This corresponds to [`tokio::fs::set_permissions`], but only accesses
+    /// paths relative to `self`. Also, on some platforms, this function may
+    /// fail if the file or directory cannot be opened for reading or writing
+    /// first.
+    ///
+    /// [`tokio::fs::set_permissions`]: https://docs.rs/tokio/latest/tokio/fs/fn.set_permissions.html
+    pub async fn set_permissions<P: AsRef<Path>>(
+        &self,
+        path: P,
+        perm: Permissions,
+    ) -> io::Result<()> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.set_permissions(path, perm)).await
+    }
+
+    /// Query the metadata about a file without following symlinks.
+    ///
+    /// This corresponds to [`tokio::fs::symlink_metadata`], but only accesses
+    /// paths relative to `self`.
+    ///
+    /// [`tokio::fs::symlink_metadata`]: https://docs.rs/tokio/latest/tokio/fs/fn.symlink_metadata.html
+    pub async fn symlink_metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.symlink_metadata(path)).await
+    }
+
+    /// Write a slice as the entire contents of a file.
+    ///
+    /// This corresponds to [`tokio::fs::write`], but only accesses paths
+    /// relative to `self`.
+    ///
+    /// [`tokio::fs::write`]: https://docs.rs/tokio/latest/tokio/fs/fn.write.html
+    pub async fn write<P: AsRef<Path>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+    ) -> io::Result<()> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        let contents = contents.as_ref().to_owned();
+        asyncify(move || dir.write(path, contents)).await
+    }
+
+    /// Creates a new symbolic link on a filesystem.
+    ///
+    /// The `original` argument provides the target of the symlink. The `link`
+    /// argument provides the name of the created symlink.
+    ///
+    /// This corresponds to [`tokio::fs::symlink`], but only accesses paths
+    /// relative to `self`.
+    ///
+    /// [`tokio::fs::symlink`]: https://docs.rs/tokio/latest/tokio/fs/fn.symlink.html
+    #[cfg(not(windows))]
+    pub async fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        original: P,
+        link: Q,
+    ) -> io::Result<()> {
+        let dir = Arc::clone(&self.cap_std);
+        let (original, link) = (original.as_ref().to_path_buf(), link.as_ref().to_path_buf());
+        asyncify(move || dir.symlink(original, link)).await
+    }
+
+    /// Creates a new file symbolic link on a filesystem.
+    ///
+    /// This corresponds to [`tokio::fs::symlink_file`], but only accesses
+    /// paths relative to `self`.
+    ///
+    /// [`tokio::fs::symlink_file`]: https://docs.rs/tokio/latest/tokio/fs/fn.symlink_file.html
+    #[cfg(windows)]
+    pub async fn symlink_file<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        original: P,
+        link: Q,
+    ) -> io::Result<()> {
+        let dir = Arc::clone(&self.cap_std);
+        let (original, link) = (original.as_ref().to_path_buf(), link.as_ref().to_path_buf());
+        asyncify(move || dir.symlink_file(original, link)).await
+    }
+
+    /// Creates a new directory symlink on a filesystem.
+    ///
+    /// This corresponds to [`tokio::fs::symlink_dir`], but only accesses
+    /// paths relative to `self`.
+    ///
+    /// [`tokio::fs::symlink_dir`]: https://docs.rs/tokio/latest/tokio/fs/fn.symlink_dir.html
+    #[cfg(windows)]
+    pub async fn symlink_dir<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        original: P,
+        link: Q,
+    ) -> io::Result<()> {
+        let dir = Arc::clone(&self.cap_std);
+        let (original, link) = (original.as_ref().to_path_buf(), link.as_ref().to_path_buf());
+        asyncify(move || dir.symlink_dir(original, link)).await
+    }
+
+    /// Creates a new `Dir` instance that shares the same underlying file
+    /// handle as the existing `Dir` instance.
+    pub async fn try_clone(&self) -> io::Result<Self> {
+        let dir = Arc::clone(&self.cap_std);
+        let dir = asyncify(move || dir.try_clone()).await?;
+        Ok(Self::from_cap_std(dir))
+    }
+
+    /// Returns `true` if the path points at an existing entity.
+    ///
+    /// This corresponds to [`std::path::Path::exists`], but only accesses
+    /// paths relative to `self`.
+    pub async fn exists<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.try_exists(path).await.unwrap_or(false)
+    }
+
+    /// Returns `Ok(true)` if the path points at an existing entity.
+    ///
+    /// This corresponds to [`tokio::fs::try_exists`], but only accesses
+    /// paths relative to `self`.
+    ///
+    /// [`tokio::fs::try_exists`]: https://docs.rs/tokio/latest/tokio/fs/fn.try_exists.html
+    pub async fn try_exists<P: AsRef<Path>>(&self, path: P) -> io::Result<bool> {
+        let dir = Arc::clone(&self.cap_std);
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || dir.try_exists(path)).await
+    }
+
+    /// Returns `true` if the path exists on disk and is pointing at a regular
+    /// file.
+    ///
+    /// This corresponds to [`std::path::Path::is_file`], but only accesses
+    /// paths relative to `self`.
+    pub async fn is_file<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.metadata(path)
+            .await
+            .map(|m| m.is_file())
+            .unwrap_or(false)
+    }
+
+    /// Checks if `path` is a directory.
+    ///
+    /// This is similar to [`std::path::Path::is_dir`] in that it checks if
+    /// `path` relative to `Dir` is a directory. This function will traverse
+    /// symbolic links to query information about the destination file. In
+    /// case of broken symbolic links, this will return `false`.
+    pub async fn is_dir<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.metadata(path)
+            .await
+            .map(|m| m.is_dir())
+            .unwrap_or(false)
+    }
+
+    /// Constructs a new instance of `Self` by opening the given path as a
+    /// directory using the host process' ambient authority.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function is not sandboxed and may access any path that the host
+    /// process has access to.
+    pub async fn open_ambient_dir<P: AsRef<Path>>(
+        path: P,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let dir =
+            asyncify(move || cap_std::fs::Dir::open_ambient_dir(path, ambient_authority)).await?;
+        Ok(Self::from_cap_std(dir))
+    }
+
+    /// Recursively create a directory and all of its parent components if
+    /// they are missing, using the host process' ambient authority.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function is not sandboxed and may access any path that the host
+    /// process has access to.
+    pub async fn create_ambient_dir_all<P: AsRef<Path>>(
+        path: P,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<()> {
+        let path = path.as_ref().to_path_buf();
+        asyncify(move || cap_std::fs::Dir::create_ambient_dir_all(path, ambient_authority)).await
+    }
+
+    /// Returns the shared `cap_std::fs::Dir`, for use by other modules in
+    /// this crate.
+    pub(crate) fn shared(&self) -> Arc<cap_std::fs::Dir> {
+        Arc::clone(&self.cap_std)
+    }
+}
+
+impl From<cap_std::fs::Dir> for Dir {
+    #[inline]
+    fn from(cap_std: cap_std::fs::Dir) -> Self {
+        Self::from_cap_std(cap_std)
+    }
+}
+
+#[cfg(not(windows))]
+impl AsRawFd for Dir {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.cap_std.as_raw_fd()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsFd for Dir {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.cap_std.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl AsRawHandle for Dir {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.cap_std.as_raw_handle()
+    }
+}
+
+#[cfg(windows)]
+impl AsHandle for Dir {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.cap_std.as_handle()
+    }
+}
+
+impl fmt::Debug for Dir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cap_std.fmt(f)
+    }
+}
@@ -0,0 +1,100 @@
+use crate::fs::{asyncify, Dir, File, FileType, Metadata, OpenOptions};
+use std::ffi::OsString;
+use std::sync::Arc;
+use std::{fmt, io};
+
+/// Entries returned by the `ReadDir` stream.
+///
+/// This corresponds to [`tokio::fs::DirEntry`].
+///
+/// Unlike `tokio::fs::DirEntry`, this API has no `DirEntry::path`, because
+/// absolute paths don't interoperate well with the capability model.
+///
+/// There is a `file_name` function, however there are also `open`,
+/// `open_with`, `open_dir`, `remove_file`, and `remove_dir` functions for
+/// opening or removing the entry directly, which can be more efficient and
+/// convenient.
+///
+/// [`tokio::fs::DirEntry`]: https://docs.rs/tokio/latest/tokio/fs/struct.DirEntry.html
+pub struct DirEntry {
+    cap_std: Arc<cap_std::fs::DirEntry>,
+}
+
+impl DirEntry {
+    pub(crate) fn from_cap_std(cap_std: cap_std::fs::DirEntry) -> Self {
+        Self {
+            cap_std: Arc::new(cap_std),
+        }
+    }
+
+    /// Open the file for reading.
+    pub async fn open(&self) -> io::Result<File> {
+        let entry = Arc::clone(&self.cap_std);
+        let file = asyncify(move || entry.open()).await?;
+        Ok(File::from_cap_std(file))
+    }
+
+    /// Open the file with the given options.
+    pub async fn open_with(&self, options: &OpenOptions) -> io::Result<File> {
+        let entry = Arc::clone(&self.cap_std);
+        let options = options.clone();
+        let file = asyncify(move || entry.open_with(&options)).await?;
+        Ok(File::from_cap_std(file))
+    }
+
+    /// Open the entry as a directory.
+    pub async fn open_dir(&self) -> io::Result<Dir> {
+        let entry = Arc::clone(&self.cap_std);
+        let dir = asyncify(move || entry.open_dir()).await?;
+        Ok(Dir::from_cap_std(dir))
+    }
+
+    /// Removes the file from its filesystem.
+    pub async fn remove_file(&self) -> io::Result<()> {
+        let entry = Arc::clone(&self.cap_std);
+        asyncify(move || entry.remove_file()).await
+    }
+
+    /// Removes the directory from its filesystem.
+    pub async fn remove_dir(&self) -> io::Result<()> {
+        let entry = Arc::clone(&self.cap_std);
+        asyncify(move || entry.remove_dir()).await
+    }
+
+    /// Returns the metadata for the file that this entry points at.
+    ///
+    /// This corresponds to [`tokio::fs::DirEntry::metadata`].
+    ///
+    /// [`tokio::fs::DirEntry::metadata`]: https://docs.rs/tokio/latest/tokio/fs/struct.DirEntry.html#method.metadata
+    pub async fn metadata(&self) -> io::Result<Metadata> {
+        let entry = Arc::clone(&self.cap_std);
+        asyncify(move || entry.metadata()).await
+    }
+
+    /// Returns the file type for the file that this entry points at.
+    ///
+    /// This corresponds to [`tokio::fs::DirEntry::file_type`].
+    ///
+    /// [`tokio::fs::DirEntry::file_type`]: https://docs.rs/tokio/latest/tokio/fs/struct.DirEntry.html#method.file_type
+    pub async fn file_type(&self) -> io::Result<FileType> {
+        let entry = Arc::clone(&self.cap_std);
+        asyncify(move || entry.file_type()).await
+    }
+
+    /// Returns the bare file name of this directory entry without any other
+    /// leading path component.
+    ///
+    /// This corresponds to [`tokio::fs::DirEntry::file_name`].
+    ///
+    /// [`tokio::fs::DirEntry::file_name`]: https://docs.rs/tokio/latest/tokio/fs/struct.DirEntry.html#method.file_name
+    #[inline]
+    pub fn file_name(&self) -> OsString {
+        self.cap_std.file_name()
+    }
+}
+
+impl fmt::Debug for DirEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cap_std.fmt(f)
+    }
+}
@@ -0,0 +1,304 @@
+use crate::fs::{asyncify, Metadata, OpenOptions, Permissions};
+use cap_std::AmbientAuthority;
+use io_lifetimes::AsFilelike;
+#[cfg(not(windows))]
+use io_lifetimes::{AsFd, BorrowedFd};
+#[cfg(windows)]
+use io_lifetimes::{AsHandle, BorrowedHandle};
+#[cfg(not(windows))]
+use std::os::unix::io::{AsRawFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawHandle, RawHandle};
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{fmt, fs, io};
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+
+/// A reference to an open file on a filesystem, for use in async code.
+///
+/// This corresponds to [`tokio::fs::File`], and it implements [`AsyncRead`],
+/// [`AsyncWrite`], and [`AsyncSeek`] in the same way.
+///
+/// This API has no `open` or `create` methods. To open a file, you must first
+/// obtain a [`Dir`] containing the path, and then call [`Dir::open`] or
+/// [`Dir::create`].
+///
+/// [`tokio::fs::File`]: https://docs.rs/tokio/latest/tokio/fs/struct.File.html
+/// [`Dir`]: crate::fs::Dir
+/// [`Dir::open`]: crate::fs::Dir::open
+/// [`Dir::create`]: crate::fs::Dir::create
+pub struct File {
+    tokio: tokio::fs::File,
+}
+
+impl File {
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::fs::File`.
+    #[inline]
+    pub fn from_cap_std(cap_std: cap_std::fs::File) -> Self {
+        Self::from_std(cap_std.into_std())
+    }
+
+    /// Constructs a new instance of `Self` from the given `std::fs::File`.
+    ///
+    /// This grants access the resources the `std::fs::File` instance already
+    /// has access to.
+    #[inline]
+    pub fn from_std(std: fs::File) -> Self {
+        Self {
+            tokio: tokio::fs::File::from_std(std),
+        }
+    }
+
+    /// Consumes `self` and returns a `std::fs::File`, waiting for any
+    /// in-flight operations to complete first.
+    #[inline]
+    pub async fn into_std(self) -> fs::File {
+        self.tokio.into_std().await
+    }
+
+    /// Consumes `self` and returns a `cap_std::fs::File`, waiting for any
+    /// in-flight operations to complete first.
+    #[inline]
+    pub async fn into_cap_std(self) -> cap_std::fs::File {
+        cap_std::fs::File::from_std(self.into_std().await)
+    }
+
+    /// Attempts to convert `self` into a `std::fs::File` immediately, failing
+    /// if there are operations still in flight.
+    #[inline]
+    pub fn try_into_std(self) -> Result<fs::File, Self> {
+        self.tokio.try_into_std().map_err(|tokio| Self { tokio })
+    }
+
+    /// Attempts to sync all OS-internal metadata to disk.
+    ///
+    /// This corresponds to [`tokio::fs::File::sync_all`].
+    ///
+    /// [`tokio::fs::File::sync_all`]: https://docs.rs/tokio/latest/tokio/fs/struct.File.html#method.sync_all
+    #[inline]
+    pub async fn sync_all(&self) -> io::Result<()> {
+        self.tokio.sync_all().await
+    }
+
+    /// This function is similar to `sync_all`, except that it may not
+    /// synchronize file metadata to a filesystem.
+    ///
+    /// This corresponds to [`tokio::fs::File::sync_data`].
+    ///
+    /// [`tokio::fs::File::sync_data`]: https://docs.rs/tokio/latest/tokio/fs/struct.File.html#method.sync_data
+    #[inline]
+    pub async fn sync_data(&self) -> io::Result<()> {
+        self.tokio.sync_data().await
+    }
+
+    /// Truncates or extends the underlying file, updating the size of this
+    /// file to become size.
+    ///
+    /// This corresponds to [`tokio::fs::File::set_len`].
+    ///
+    /// [`tokio::fs::File::set_len`]: https://docs.rs/tokio/latest/tokio/fs/struct.File.html#method.set_len
+    #[inline]
+    pub async fn set_len(&self, size: u64) -> io::Result<()> {
+        self.tokio.set_len(size).await
+    }
+
+    /// Queries metadata about the underlying file.
+    ///
+    /// This corresponds to [`tokio::fs::File::metadata`].
+    ///
+    /// [`tokio::fs::File::metadata`]: https://docs.rs/tokio/latest/tokio/fs/struct.File.html#method.metadata
+    pub async fn metadata(&self) -> io::Result<Metadata> {
+        let file = self.cap_std_clone()?;
+        asyncify(move || file.metadata()).await
+    }
+
+    /// Creates a new `File` instance that shares the same underlying file
+    /// handle as the existing `File` instance.
+    ///
+    /// This corresponds to [`tokio::fs::File::try_clone`].
+    ///
+    /// [`tokio::fs::File::try_clone`]: https://docs.rs/tokio/latest/tokio/fs/struct.File.html#method.try_clone
+    #[inline]
+    pub async fn try_clone(&self) -> io::Result<Self> {
+        let tokio = self.tokio.try_clone().await?;
+        Ok(Self { tokio })
+    }
+
+    /// Changes the permissions on the underlying file.
+    ///
+    /// This corresponds to [`tokio::fs::File::set_permissions`].
+    ///
+    /// [`tokio::fs::File::set_permissions`]: https://docs.rs/tokio/latest/tokio/fs/struct.File.html#method.set_permissions
+    pub async fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
+        let file = self.cap_std_clone()?;
+        asyncify(move || file.set_permissions(perm)).await
+    }
+
+    /// Constructs a new instance of `Self` in read-only mode by opening the
+    /// given path as a file using the host process' ambient authority.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function is not sandboxed and may access any path that the host
+    /// process has access to.
+    pub async fn open_ambient<P: AsRef<Path>>(
+        path: P,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file =
+            asyncify(move || cap_std::fs::File::open_ambient(path, ambient_authority)).await?;
+        Ok(Self::from_cap_std(file))
+    }
+
+    /// Constructs a new instance of `Self` in write-only mode by opening,
+    /// creating or truncating, the given path as a file using the host
+    /// process' ambient authority.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function is not sandboxed and may access any path that the host
+    /// process has access to.
+    pub async fn create_ambient<P: AsRef<Path>>(
+        path: P,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file =
+            asyncify(move || cap_std::fs::File::create_ambient(path, ambient_authority)).await?;
+        Ok(Self::from_cap_std(file))
+    }
+
+    /// Constructs a new instance of `Self` with the options specified by
+    /// `options` by opening the given path as a file using the host process'
+    /// ambient authority.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function is not sandboxed and may access any path that the host
+    /// process has access to.
+    pub async fn open_ambient_with<P: AsRef<Path>>(
+        path: P,
+        options: &OpenOptions,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let options = options.clone();
+        let file = asyncify(move || {
+            cap_std::fs::File::open_ambient_with(path, &options, ambient_authority)
+        })
+        .await?;
+        Ok(Self::from_cap_std(file))
+    }
+
+    /// Returns a new `OpenOptions` object.
+    ///
+    /// This corresponds to [`tokio::fs::File::options`].
+    ///
+    /// [`tokio::fs::File::options`]: https://docs.rs/tokio/latest/tokio/fs/struct.File.html#method.options
+    #[must_use]
+    #[inline]
+    pub fn options() -> OpenOptions {
+        OpenOptions::new()
+    }
+
+    /// Duplicates the handle, so that a blocking operation on it can be moved
+    /// onto the blocking thread pool.
+    fn cap_std_clone(&self) -> io::Result<cap_std::fs::File> {
+        self.tokio
+            .as_filelike_view::<cap_std::fs::File>()
+            .try_clone()
+    }
+}
+
+impl From<cap_std::fs::File> for File {
+    #[inline]
+    fn from(cap_std: cap_std::fs::File) -> Self {
+        Self::from_cap_std(cap_std)
+    }
+}
+
+#[cfg(not(windows))]
+impl AsRawFd for File {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.tokio.as_raw_fd()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsFd for File {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.tokio.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl AsRawHandle for File {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.tokio.as_raw_handle()
+    }
+}
+
+#[cfg(windows)]
+impl AsHandle for File {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.tokio.as_handle()
+    }
+}
+
+impl AsyncRead for File {
+    #[inline]
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.tokio).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for File {
+    #[inline]
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.tokio).poll_write(cx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.tokio).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.tokio).poll_shutdown(cx)
+    }
+}
+
+impl AsyncSeek for File {
+    #[inline]
+    fn start_seek(mut self: Pin<&mut Self>, position: io::SeekFrom) -> io::Result<()> {
+        Pin::new(&mut self.tokio).start_seek(position)
+    }
+
+    #[inline]
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Pin::new(&mut self.tokio).poll_complete(cx)
+    }
+}
+
+impl fmt::Debug for File {
+    // Like `cap_std`'s version, which doesn't print the path.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.tokio.as_filelike_view::<cap_std::fs::File>().fmt(f)
+    }
+}
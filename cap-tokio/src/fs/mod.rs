@@ -0,0 +1,42 @@
+//! A capability-based async filesystem API modeled after [`tokio::fs`].
+//!
+//! This corresponds to [`tokio::fs`] and [`cap_std::fs`].
+//!
+//! Instead of [`tokio::fs`'s free functions] and [`tokio::fs::File`]'s
+//! constructors which operate on bare paths, this crate has methods on [`Dir`]
+//! which operate on paths which must be relative to the directory.
+//!
+//! [`tokio::fs`'s free functions]: https://docs.rs/tokio/latest/tokio/fs/index.html#functions
+
+mod dir;
+mod dir_entry;
+mod file;
+mod read_dir;
+
+pub use dir::Dir;
+pub use dir_entry::DirEntry;
+pub use file::File;
+pub use read_dir::ReadDir;
+
+// Re-export things from `cap_std` that we can use as-is.
+pub use cap_std::fs::{
+    CopyOptions, DirBuilder, FileType, Metadata, OpenOptions, Permissions, RenameOptions,
+};
+
+use std::io;
+use tokio::task::spawn_blocking;
+
+/// Runs a blocking filesystem operation on tokio's blocking thread pool.
+pub(crate) async fn asyncify<F, T>(f: F) -> io::Result<T>
+where
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match spawn_blocking(f).await {
+        Ok(res) => res,
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::Other,
+            "background task failed",
+        )),
+    }
+}
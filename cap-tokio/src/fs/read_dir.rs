@@ -0,0 +1,117 @@
+use crate::fs::DirEntry;
+use std::collections::VecDeque;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::{fmt, io};
+use tokio::task::{spawn_blocking, JoinHandle};
+
+/// How many entries to read on the blocking thread pool at a time.
+const CHUNK_SIZE: usize = 32;
+
+/// Entries read so far, the underlying iterator, and whether it may have more
+/// entries.
+type Chunk = (
+    VecDeque<io::Result<cap_std::fs::DirEntry>>,
+    cap_std::fs::ReadDir,
+    bool,
+);
+
+/// A stream of the entries in a directory.
+///
+/// This corresponds to [`tokio::fs::ReadDir`].
+///
+/// Entries are read in batches on tokio's blocking thread pool.
+///
+/// [`tokio::fs::ReadDir`]: https://docs.rs/tokio/latest/tokio/fs/struct.ReadDir.html
+pub struct ReadDir {
+    state: State,
+}
+
+enum State {
+    Idle(Option<Chunk>),
+    Pending(JoinHandle<Chunk>),
+}
+
+impl ReadDir {
+    pub(crate) fn from_cap_std(cap_std: cap_std::fs::ReadDir) -> Self {
+        Self {
+            state: State::Idle(Some((VecDeque::new(), cap_std, true))),
+        }
+    }
+
+    /// Returns the next entry in the directory stream.
+    ///
+    /// This corresponds to [`tokio::fs::ReadDir::next_entry`].
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancellation safe.
+    ///
+    /// [`tokio::fs::ReadDir::next_entry`]: https://docs.rs/tokio/latest/tokio/fs/struct.ReadDir.html#method.next_entry
+    pub async fn next_entry(&mut self) -> io::Result<Option<DirEntry>> {
+        poll_fn(|cx| self.poll_next_entry(cx)).await
+    }
+
+    /// Polls for the next directory entry in the stream.
+    ///
+    /// This corresponds to [`tokio::fs::ReadDir::poll_next_entry`].
+    ///
+    /// [`tokio::fs::ReadDir::poll_next_entry`]: https://docs.rs/tokio/latest/tokio/fs/struct.ReadDir.html#method.poll_next_entry
+    pub fn poll_next_entry(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Option<DirEntry>>> {
+        loop {
+            match &mut self.state {
+                State::Idle(chunk) => {
+                    let Some((entries, _, remain)) = chunk else {
+                        return Poll::Ready(Ok(None));
+                    };
+                    if let Some(entry) = entries.pop_front() {
+                        return Poll::Ready(entry.map(|entry| Some(DirEntry::from_cap_std(entry))));
+                    }
+                    if !*remain {
+                        return Poll::Ready(Ok(None));
+                    }
+
+                    let (mut entries, mut read_dir, _) = chunk.take().unwrap();
+                    self.state = State::Pending(spawn_blocking(move || {
+                        let remain = next_chunk(&mut entries, &mut read_dir);
+                        (entries, read_dir, remain)
+                    }));
+                }
+                State::Pending(handle) => match ready!(Pin::new(handle).poll(cx)) {
+                    Ok(chunk) => self.state = State::Idle(Some(chunk)),
+                    Err(_) => {
+                        self.state = State::Idle(None);
+                        return Poll::Ready(Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            "background task failed",
+                        )));
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Reads up to `CHUNK_SIZE` entries, returning whether there may be more.
+fn next_chunk(
+    entries: &mut VecDeque<io::Result<cap_std::fs::DirEntry>>,
+    read_dir: &mut cap_std::fs::ReadDir,
+) -> bool {
+    for _ in 0..CHUNK_SIZE {
+        match read_dir.next() {
+            Some(entry) => entries.push_back(entry),
+            None => return false,
+        }
+    }
+    true
+}
+
+impl fmt::Debug for ReadDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.state {
+            State::Idle(Some((_, read_dir, _))) => read_dir.fmt(f),
+            _ => f.debug_struct("ReadDir").finish_non_exhaustive(),
+        }
+    }
+}
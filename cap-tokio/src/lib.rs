@@ -0,0 +1,36 @@
+//! A capability-based async API modeled after [`tokio`].
+//!
+//! This corresponds to [`tokio::fs`] and [`tokio::net`], with [`Dir`] and
+//! [`Pool`] in place of ambient paths and addresses, as in [`cap_std`].
+//!
+//! As with `tokio::fs`, filesystem operations are run on tokio's blocking
+//! thread pool, so these APIs must be used from within a tokio runtime. Path
+//! resolution is done by the same code as in `cap_std`, so paths are
+//! sandboxed in exactly the same way.
+//!
+//! [`Dir`]: fs::Dir
+//! [`Pool`]: net::Pool
+//! [`tokio::net`]: https://docs.rs/tokio/latest/tokio/net/index.html
+
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/bytecodealliance/cap-std/main/media/cap-std.svg"
+)]
+#![doc(
+    html_favicon_url = "https://raw.githubusercontent.com/bytecodealliance/cap-std/main/media/cap-std.ico"
+)]
+
+pub mod fs;
+#[cfg(not(target_os = "wasi"))] // Disable `net` on WASI until it has networking support.
+pub mod net;
+pub mod tempfile;
+
+/// Re-export because we use this in our public API.
+pub use cap_std;
+
+// Re-export ambient_authority etc. so that users can use our version.
+#[doc(hidden)]
+pub use cap_std::ambient_authority_known_at_compile_time;
+pub use cap_std::{ambient_authority, AmbientAuthority};
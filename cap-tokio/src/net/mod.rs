@@ -0,0 +1,28 @@
+//! A capability-based async network API modeled after [`tokio::net`].
+//!
+//! This corresponds to [`tokio::net`] and [`cap_std::net`].
+//!
+//! Instead of [`tokio::net`]'s constructor methods which take an address to
+//! connect to, this crate has async methods on [`Pool`] which operate on
+//! addresses which must be present in the pool.
+//!
+//! [`tokio::net`]: https://docs.rs/tokio/latest/tokio/net/index.html
+
+mod pool;
+mod tcp_listener;
+mod tcp_stream;
+mod udp_socket;
+
+pub use pool::*;
+pub use tcp_listener::*;
+pub use tcp_stream::*;
+pub use udp_socket::*;
+
+// Re-export the name resolution capability from `cap_std::net`.
+pub use cap_std::net::{Resolve, Resolver, StaticResolver};
+// Re-export things from `std::net` that we can use as-is.
+pub use std::net::{
+    AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, SocketAddrV4, SocketAddrV6,
+};
+// Addresses are resolved with tokio's non-blocking resolver.
+pub use tokio::net::ToSocketAddrs;
@@ -0,0 +1,381 @@
+use crate::net::{Resolver, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use cap_primitives::net::{no_socket_addrs, Access};
+use cap_primitives::{ipnet, AmbientAuthority};
+use std::time::Duration;
+use std::{io, net};
+use tokio::net::lookup_host;
+
+/// A pool of network addresses, for use in async code.
+///
+/// This corresponds to [`cap_std::net::Pool`], with async versions of its
+/// methods for binding and connecting sockets.
+///
+/// As in `cap_std`, each address is checked against the pool before a socket
+/// is created for it. Host names are resolved with tokio's resolver, and each
+/// resolved address is checked. Methods ending in `_host` instead resolve
+/// names granted with [`Pool::insert_host`] or similar with a [`Resolver`].
+#[derive(Clone, Default)]
+pub struct Pool {
+    cap: cap_std::net::Pool,
+}
+
+impl Pool {
+    /// Construct a new empty pool.
+    pub fn new() -> Self {
+        Self {
+            cap: cap_std::net::Pool::new(),
+        }
+    }
+
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::net::Pool`.
+    #[inline]
+    pub fn from_cap_std(cap_std: cap_std::net::Pool) -> Self {
+        Self { cap: cap_std }
+    }
+
+    /// Returns a reference to the underlying `cap_std::net::Pool`.
+    #[inline]
+    pub fn as_cap_std(&self) -> &cap_std::net::Pool {
+        &self.cap
+    }
+
+    /// Add a specific [`net::SocketAddr`] to the pool.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any IP address.
+    pub fn insert_socket_addr(
+        &mut self,
+        addr: net::SocketAddr,
+        ambient_authority: AmbientAuthority,
+    ) {
+        self.cap.insert_socket_addr(addr, ambient_authority)
+    }
+
+    /// Add a range of network addresses, accepting any port, to the pool.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any IP address.
+    pub fn insert_ip_net_port_any(
+        &mut self,
+        ip_net: ipnet::IpNet,
+        ambient_authority: AmbientAuthority,
+    ) {
+        self.cap.insert_ip_net_port_any(ip_net, ambient_authority)
+    }
+
+    /// Add a range of network addresses, accepting a range of ports, to the
+    /// pool.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any IP address.
+    pub fn insert_ip_net_port_range(
+        &mut self,
+        ip_net: ipnet::IpNet,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        ambient_authority: AmbientAuthority,
+    ) {
+        self.cap
+            .insert_ip_net_port_range(ip_net, ports_start, ports_end, ambient_authority)
+    }
+
+    /// Add a range of network addresses with a specific port to the pool.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any IP address.
+    pub fn insert_ip_net(
+        &mut self,
+        ip_net: ipnet::IpNet,
+        port: u16,
+        ambient_authority: AmbientAuthority,
+    ) {
+        self.cap.insert_ip_net(ip_net, port, ambient_authority)
+    }
+
+    /// Add a host name with a specific port to the pool.
+    ///
+    /// This corresponds to [`cap_std::net::Pool::insert_host`].
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any host name.
+    pub fn insert_host(
+        &mut self,
+        host: &str,
+        port: u16,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<()> {
+        self.cap.insert_host(host, port, ambient_authority)
+    }
+
+    /// Add a host name, accepting any port, to the pool.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any host name.
+    pub fn insert_host_port_any(
+        &mut self,
+        host: &str,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<()> {
+        self.cap.insert_host_port_any(host, ambient_authority)
+    }
+
+    /// Add a host name, accepting a range of ports, to the pool.
+    ///
+    /// # Ambient Authority
+    ///
+    /// This function allows ambient access to any host name.
+    pub fn insert_host_port_range(
+        &mut self,
+        host: &str,
+        ports_start: u16,
+        ports_end: Option<u16>,
+        ambient_authority: AmbientAuthority,
+    ) -> io::Result<()> {
+        self.cap
+            .insert_host_port_range(host, ports_start, ports_end, ambient_authority)
+    }
+
+    /// Creates a new `TcpListener` which will be bound to the specified
+    /// address.
+    ///
+    /// This corresponds to [`tokio::net::TcpListener::bind`].
+    ///
+    /// [`tokio::net::TcpListener::bind`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpListener.html#method.bind
+    #[doc(alias = "bind")]
+    pub async fn bind_tcp_listener<A: ToSocketAddrs>(&self, addr: A) -> io::Result<TcpListener> {
+        let addrs = lookup_host(addr).await?;
+
+        let mut last_err = None;
+        for addr in addrs {
+            self.cap
+                ._pool()
+                .check_addr_access(&addr, Access::TCP_BIND)?;
+            match tokio::net::TcpListener::bind(addr).await {
+                Ok(tcp_listener) => return Ok(TcpListener::from_tokio(tcp_listener)),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Err(no_socket_addrs()),
+        }
+    }
+
+    /// Opens a TCP connection to a remote host.
+    ///
+    /// This corresponds to [`tokio::net::TcpStream::connect`].
+    ///
+    /// [`tokio::net::TcpStream::connect`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html#method.connect
+    #[doc(alias = "connect")]
+    pub async fn connect_tcp_stream<A: ToSocketAddrs>(&self, addr: A) -> io::Result<TcpStream> {
+        let addrs = lookup_host(addr).await?;
+
+        let mut last_err = None;
+        for addr in addrs {
+            self.cap
+                ._pool()
+                .check_addr_access(&addr, Access::TCP_CONNECT)?;
+            match tokio::net::TcpStream::connect(addr).await {
+                Ok(tcp_stream) => return Ok(TcpStream::from_tokio(tcp_stream)),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Err(no_socket_addrs()),
+        }
+    }
+
+    /// Opens a TCP connection to a remote host, by name.
+    ///
+    /// This corresponds to [`cap_std::net::Pool::connect_tcp_stream_host`].
+    /// The name is resolved with `resolver` on a blocking thread, and the
+    /// host name and port must have been granted with [`Pool::insert_host`]
+    /// or similar.
+    #[doc(alias = "connect")]
+    pub async fn connect_tcp_stream_host(
+        &self,
+        resolver: &Resolver,
+        host: &str,
+        port: u16,
+    ) -> io::Result<TcpStream> {
+        let addrs = self
+            .resolve_host(resolver, host, port, Access::TCP_CONNECT)
+            .await?;
+
+        let mut last_err = None;
+        for addr in addrs {
+            match tokio::net::TcpStream::connect(addr).await {
+                Ok(tcp_stream) => return Ok(TcpStream::from_tokio(tcp_stream)),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Err(no_socket_addrs()),
+        }
+    }
+
+    /// Opens a TCP connection to a remote host with a timeout.
+    ///
+    /// This corresponds to [`std::net::TcpStream::connect_timeout`]. If the
+    /// connection isn't established within `timeout`, this fails with
+    /// [`io::ErrorKind::TimedOut`].
+    #[doc(alias = "connect")]
+    pub async fn connect_timeout_tcp_stream(
+        &self,
+        addr: &SocketAddr,
+        timeout: Duration,
+    ) -> io::Result<TcpStream> {
+        self.cap
+            ._pool()
+            .check_addr_access(addr, Access::TCP_CONNECT)?;
+        match tokio::time::timeout(timeout, tokio::net::TcpStream::connect(addr)).await {
+            Ok(tcp_stream) => Ok(TcpStream::from_tokio(tcp_stream?)),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "connection timed out",
+            )),
+        }
+    }
+
+    /// Creates a UDP socket from the given address.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::bind`].
+    ///
+    /// [`tokio::net::UdpSocket::bind`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.bind
+    #[doc(alias = "bind")]
+    pub async fn bind_udp_socket<A: ToSocketAddrs>(&self, addr: A) -> io::Result<UdpSocket> {
+        let addrs = lookup_host(addr).await?;
+
+        let mut last_err = None;
+        for addr in addrs {
+            self.cap
+                ._pool()
+                .check_addr_access(&addr, Access::UDP_BIND)?;
+            match tokio::net::UdpSocket::bind(addr).await {
+                Ok(udp_socket) => return Ok(UdpSocket::from_tokio(udp_socket)),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Err(no_socket_addrs()),
+        }
+    }
+
+    /// Sends data on the socket to the given address. On success, returns the
+    /// number of bytes written.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::send_to`].
+    ///
+    /// [`tokio::net::UdpSocket::send_to`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.send_to
+    #[doc(alias = "send_to")]
+    pub async fn send_to_udp_socket_addr<A: ToSocketAddrs>(
+        &self,
+        udp_socket: &UdpSocket,
+        buf: &[u8],
+        addr: A,
+    ) -> io::Result<usize> {
+        let mut addrs = lookup_host(addr).await?;
+
+        // `UdpSocket::send_to` only sends to the first address.
+        let addr = addrs.next().ok_or_else(no_socket_addrs)?;
+        self.cap
+            ._pool()
+            .check_addr_access(&addr, Access::UDP_CONNECT)?;
+        udp_socket.tokio.send_to(buf, addr).await
+    }
+
+    /// Connects this UDP socket to a remote address, allowing the `send` and
+    /// `recv` methods to be used to send data and also applies filters to
+    /// only receive data from the specified address.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::connect`].
+    ///
+    /// [`tokio::net::UdpSocket::connect`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.connect
+    #[doc(alias = "connect")]
+    pub async fn connect_udp_socket<A: ToSocketAddrs>(
+        &self,
+        udp_socket: &UdpSocket,
+        addr: A,
+    ) -> io::Result<()> {
+        let addrs = lookup_host(addr).await?;
+
+        let mut last_err = None;
+        for addr in addrs {
+            self.cap
+                ._pool()
+                .check_addr_access(&addr, Access::UDP_CONNECT)?;
+            match udp_socket.tokio.connect(addr).await {
+                Ok(()) => return Ok(()),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Err(no_socket_addrs()),
+        }
+    }
+
+    /// Connects this UDP socket to a remote host, by name.
+    ///
+    /// This corresponds to [`cap_std::net::Pool::connect_udp_socket_host`].
+    /// The name is resolved with `resolver` on a blocking thread, and the
+    /// host name and port must have been granted with [`Pool::insert_host`]
+    /// or similar.
+    #[doc(alias = "connect")]
+    pub async fn connect_udp_socket_host(
+        &self,
+        udp_socket: &UdpSocket,
+        resolver: &Resolver,
+        host: &str,
+        port: u16,
+    ) -> io::Result<()> {
+        let addrs = self
+            .resolve_host(resolver, host, port, Access::UDP_CONNECT)
+            .await?;
+
+        let mut last_err = None;
+        for addr in addrs {
+            match udp_socket.tokio.connect(addr).await {
+                Ok(()) => return Ok(()),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Err(no_socket_addrs()),
+        }
+    }
+
+    /// Resolve `host` with `resolver`, which may block, on a blocking
+    /// thread, checking the name and the results against the pool.
+    async fn resolve_host(
+        &self,
+        resolver: &Resolver,
+        host: &str,
+        port: u16,
+        access: Access,
+    ) -> io::Result<Vec<SocketAddr>> {
+        let pool = self.cap._pool().clone();
+        let resolver = resolver.clone();
+        let host = host.to_owned();
+        tokio::task::spawn_blocking(move || pool.resolve_host(&resolver, &host, port, access))
+            .await?
+    }
+}
+
+impl From<cap_std::net::Pool> for Pool {
+    #[inline]
+    fn from(cap_std: cap_std::net::Pool) -> Self {
+        Self::from_cap_std(cap_std)
+    }
+}
@@ -0,0 +1,156 @@
+use crate::net::{SocketAddr, TcpStream};
+use io_lifetimes::FromSocketlike;
+#[cfg(not(windows))]
+use io_lifetimes::{AsFd, BorrowedFd};
+#[cfg(windows)]
+use io_lifetimes::{AsSocket, BorrowedSocket};
+#[cfg(not(windows))]
+use std::os::unix::io::{AsRawFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawSocket, RawSocket};
+use std::task::{Context, Poll};
+use std::{fmt, io, net};
+
+/// A TCP socket server, listening for connections, for use in async code.
+///
+/// This corresponds to [`tokio::net::TcpListener`].
+///
+/// This `TcpListener` has no `bind` method. To bind it to a socket address,
+/// first obtain a [`Pool`] permitting the address, and then call
+/// [`Pool::bind_tcp_listener`].
+///
+/// [`tokio::net::TcpListener`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpListener.html
+/// [`Pool`]: crate::net::Pool
+/// [`Pool::bind_tcp_listener`]: crate::net::Pool::bind_tcp_listener
+pub struct TcpListener {
+    tokio: tokio::net::TcpListener,
+}
+
+impl TcpListener {
+    /// Constructs a new instance of `Self` from the given
+    /// `tokio::net::TcpListener`.
+    ///
+    /// This grants access the resources the `tokio::net::TcpListener`
+    /// instance already has access to.
+    #[inline]
+    pub fn from_tokio(tokio: tokio::net::TcpListener) -> Self {
+        Self { tokio }
+    }
+
+    /// Constructs a new instance of `Self` from the given
+    /// `std::net::TcpListener`, putting it in non-blocking mode.
+    ///
+    /// This grants access the resources the `std::net::TcpListener` instance
+    /// already has access to. This must be called from within a tokio
+    /// runtime.
+    pub fn from_std(std: net::TcpListener) -> io::Result<Self> {
+        std.set_nonblocking(true)?;
+        Ok(Self::from_tokio(tokio::net::TcpListener::from_std(std)?))
+    }
+
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::net::TcpListener`, putting it in non-blocking mode.
+    ///
+    /// This must be called from within a tokio runtime.
+    #[inline]
+    pub fn from_cap_std(cap_std: cap_std::net::TcpListener) -> io::Result<Self> {
+        Self::from_std(net::TcpListener::from_into_socketlike(cap_std))
+    }
+
+    /// Consumes `self` and returns the underlying `tokio::net::TcpListener`.
+    #[inline]
+    pub fn into_tokio(self) -> tokio::net::TcpListener {
+        self.tokio
+    }
+
+    /// Returns the local socket address of this listener.
+    ///
+    /// This corresponds to [`tokio::net::TcpListener::local_addr`].
+    ///
+    /// [`tokio::net::TcpListener::local_addr`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpListener.html#method.local_addr
+    #[inline]
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.tokio.local_addr()
+    }
+
+    /// Accept a new incoming connection from this listener.
+    ///
+    /// This corresponds to [`tokio::net::TcpListener::accept`].
+    ///
+    /// [`tokio::net::TcpListener::accept`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpListener.html#method.accept
+    #[inline]
+    pub async fn accept(&self) -> io::Result<(TcpStream, SocketAddr)> {
+        let (tcp_stream, addr) = self.tokio.accept().await?;
+        Ok((TcpStream::from_tokio(tcp_stream), addr))
+    }
+
+    /// Polls to accept a new incoming connection to this listener.
+    ///
+    /// This corresponds to [`tokio::net::TcpListener::poll_accept`].
+    ///
+    /// [`tokio::net::TcpListener::poll_accept`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpListener.html#method.poll_accept
+    #[inline]
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<(TcpStream, SocketAddr)>> {
+        self.tokio
+            .poll_accept(cx)
+            .map_ok(|(tcp_stream, addr)| (TcpStream::from_tokio(tcp_stream), addr))
+    }
+
+    /// Sets the value for the `IP_TTL` option on this socket.
+    ///
+    /// This corresponds to [`tokio::net::TcpListener::set_ttl`].
+    ///
+    /// [`tokio::net::TcpListener::set_ttl`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpListener.html#method.set_ttl
+    #[inline]
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.tokio.set_ttl(ttl)
+    }
+
+    /// Gets the value of the `IP_TTL` option for this socket.
+    ///
+    /// This corresponds to [`tokio::net::TcpListener::ttl`].
+    ///
+    /// [`tokio::net::TcpListener::ttl`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpListener.html#method.ttl
+    #[inline]
+    pub fn ttl(&self) -> io::Result<u32> {
+        self.tokio.ttl()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsRawFd for TcpListener {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.tokio.as_raw_fd()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsFd for TcpListener {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.tokio.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl AsRawSocket for TcpListener {
+    #[inline]
+    fn as_raw_socket(&self) -> RawSocket {
+        self.tokio.as_raw_socket()
+    }
+}
+
+#[cfg(windows)]
+impl AsSocket for TcpListener {
+    #[inline]
+    fn as_socket(&self) -> BorrowedSocket<'_> {
+        self.tokio.as_socket()
+    }
+}
+
+impl fmt::Debug for TcpListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.tokio.fmt(f)
+    }
+}
@@ -0,0 +1,233 @@
+use crate::net::SocketAddr;
+use io_lifetimes::FromSocketlike;
+#[cfg(not(windows))]
+use io_lifetimes::{AsFd, BorrowedFd};
+#[cfg(windows)]
+use io_lifetimes::{AsSocket, BorrowedSocket};
+#[cfg(not(windows))]
+use std::os::unix::io::{AsRawFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawSocket, RawSocket};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{fmt, io, net};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// A TCP stream between a local and a remote socket, for use in async code.
+///
+/// This corresponds to [`tokio::net::TcpStream`], and it implements
+/// [`AsyncRead`] and [`AsyncWrite`] in the same way.
+///
+/// This `TcpStream` has no `connect` method. To create a `TcpStream`, first
+/// obtain a [`Pool`] permitting the address, and then call
+/// [`Pool::connect_tcp_stream`].
+///
+/// [`tokio::net::TcpStream`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html
+/// [`Pool`]: crate::net::Pool
+/// [`Pool::connect_tcp_stream`]: crate::net::Pool::connect_tcp_stream
+pub struct TcpStream {
+    tokio: tokio::net::TcpStream,
+}
+
+impl TcpStream {
+    /// Constructs a new instance of `Self` from the given
+    /// `tokio::net::TcpStream`.
+    ///
+    /// This grants access the resources the `tokio::net::TcpStream` instance
+    /// already has access to.
+    #[inline]
+    pub fn from_tokio(tokio: tokio::net::TcpStream) -> Self {
+        Self { tokio }
+    }
+
+    /// Constructs a new instance of `Self` from the given
+    /// `std::net::TcpStream`, putting it in non-blocking mode.
+    ///
+    /// This grants access the resources the `std::net::TcpStream` instance
+    /// already has access to. This must be called from within a tokio
+    /// runtime.
+    pub fn from_std(std: net::TcpStream) -> io::Result<Self> {
+        std.set_nonblocking(true)?;
+        Ok(Self::from_tokio(tokio::net::TcpStream::from_std(std)?))
+    }
+
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::net::TcpStream`, putting it in non-blocking mode.
+    ///
+    /// This must be called from within a tokio runtime.
+    #[inline]
+    pub fn from_cap_std(cap_std: cap_std::net::TcpStream) -> io::Result<Self> {
+        Self::from_std(net::TcpStream::from_into_socketlike(cap_std))
+    }
+
+    /// Consumes `self` and returns the underlying `tokio::net::TcpStream`.
+    #[inline]
+    pub fn into_tokio(self) -> tokio::net::TcpStream {
+        self.tokio
+    }
+
+    /// Returns the socket address of the remote peer of this TCP connection.
+    ///
+    /// This corresponds to [`tokio::net::TcpStream::peer_addr`].
+    ///
+    /// [`tokio::net::TcpStream::peer_addr`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html#method.peer_addr
+    #[inline]
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.tokio.peer_addr()
+    }
+
+    /// Returns the local socket address of this TCP connection.
+    ///
+    /// This corresponds to [`tokio::net::TcpStream::local_addr`].
+    ///
+    /// [`tokio::net::TcpStream::local_addr`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html#method.local_addr
+    #[inline]
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.tokio.local_addr()
+    }
+
+    /// Receives data on the socket from the remote address to which it is
+    /// connected, without removing that data from the queue.
+    ///
+    /// This corresponds to [`tokio::net::TcpStream::peek`].
+    ///
+    /// [`tokio::net::TcpStream::peek`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html#method.peek
+    #[inline]
+    pub async fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.tokio.peek(buf).await
+    }
+
+    /// Sets the value of the `TCP_NODELAY` option on this socket.
+    ///
+    /// This corresponds to [`tokio::net::TcpStream::set_nodelay`].
+    ///
+    /// [`tokio::net::TcpStream::set_nodelay`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html#method.set_nodelay
+    #[inline]
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        self.tokio.set_nodelay(nodelay)
+    }
+
+    /// Gets the value of the `TCP_NODELAY` option on this socket.
+    ///
+    /// This corresponds to [`tokio::net::TcpStream::nodelay`].
+    ///
+    /// [`tokio::net::TcpStream::nodelay`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html#method.nodelay
+    #[inline]
+    pub fn nodelay(&self) -> io::Result<bool> {
+        self.tokio.nodelay()
+    }
+
+    /// Sets the value for the `IP_TTL` option on this socket.
+    ///
+    /// This corresponds to [`tokio::net::TcpStream::set_ttl`].
+    ///
+    /// [`tokio::net::TcpStream::set_ttl`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html#method.set_ttl
+    #[inline]
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.tokio.set_ttl(ttl)
+    }
+
+    /// Gets the value of the `IP_TTL` option for this socket.
+    ///
+    /// This corresponds to [`tokio::net::TcpStream::ttl`].
+    ///
+    /// [`tokio::net::TcpStream::ttl`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html#method.ttl
+    #[inline]
+    pub fn ttl(&self) -> io::Result<u32> {
+        self.tokio.ttl()
+    }
+
+    /// Gets the value of the `SO_ERROR` option on this socket.
+    ///
+    /// This corresponds to [`tokio::net::TcpStream::take_error`].
+    ///
+    /// [`tokio::net::TcpStream::take_error`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html#method.take_error
+    #[inline]
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.tokio.take_error()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsRawFd for TcpStream {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.tokio.as_raw_fd()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsFd for TcpStream {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.tokio.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl AsRawSocket for TcpStream {
+    #[inline]
+    fn as_raw_socket(&self) -> RawSocket {
+        self.tokio.as_raw_socket()
+    }
+}
+
+#[cfg(windows)]
+impl AsSocket for TcpStream {
+    #[inline]
+    fn as_socket(&self) -> BorrowedSocket<'_> {
+        self.tokio.as_socket()
+    }
+}
+
+impl AsyncRead for TcpStream {
+    #[inline]
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.tokio).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for TcpStream {
+    #[inline]
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.tokio).poll_write(cx, buf)
+    }
+
+    #[inline]
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.tokio).poll_write_vectored(cx, bufs)
+    }
+
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        self.tokio.is_write_vectored()
+    }
+
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.tokio).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.tokio).poll_shutdown(cx)
+    }
+}
+
+impl fmt::Debug for TcpStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.tokio.fmt(f)
+    }
+}
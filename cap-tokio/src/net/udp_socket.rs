@@ -0,0 +1,231 @@
+use crate::net::SocketAddr;
+use io_lifetimes::FromSocketlike;
+#[cfg(not(windows))]
+use io_lifetimes::{AsFd, BorrowedFd};
+#[cfg(windows)]
+use io_lifetimes::{AsSocket, BorrowedSocket};
+#[cfg(not(windows))]
+use std::os::unix::io::{AsRawFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawSocket, RawSocket};
+use std::{fmt, io, net};
+
+/// A UDP socket, for use in async code.
+///
+/// This corresponds to [`tokio::net::UdpSocket`].
+///
+/// This `UdpSocket` has no `bind`, `connect`, or `send_to` methods. To create
+/// a `UdpSocket` bound to an address or to send a message to an address, first
+/// obtain a [`Pool`] permitting the address, and then call
+/// [`Pool::bind_udp_socket`], or [`Pool::connect_udp_socket`], or
+/// [`Pool::send_to_udp_socket_addr`].
+///
+/// [`tokio::net::UdpSocket`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html
+/// [`Pool`]: crate::net::Pool
+/// [`Pool::bind_udp_socket`]: crate::net::Pool::bind_udp_socket
+/// [`Pool::connect_udp_socket`]: crate::net::Pool::connect_udp_socket
+/// [`Pool::send_to_udp_socket_addr`]: crate::net::Pool::send_to_udp_socket_addr
+pub struct UdpSocket {
+    pub(crate) tokio: tokio::net::UdpSocket,
+}
+
+impl UdpSocket {
+    /// Constructs a new instance of `Self` from the given
+    /// `tokio::net::UdpSocket`.
+    ///
+    /// This grants access the resources the `tokio::net::UdpSocket` instance
+    /// already has access to.
+    #[inline]
+    pub fn from_tokio(tokio: tokio::net::UdpSocket) -> Self {
+        Self { tokio }
+    }
+
+    /// Constructs a new instance of `Self` from the given
+    /// `std::net::UdpSocket`, putting it in non-blocking mode.
+    ///
+    /// This grants access the resources the `std::net::UdpSocket` instance
+    /// already has access to. This must be called from within a tokio
+    /// runtime.
+    pub fn from_std(std: net::UdpSocket) -> io::Result<Self> {
+        std.set_nonblocking(true)?;
+        Ok(Self::from_tokio(tokio::net::UdpSocket::from_std(std)?))
+    }
+
+    /// Constructs a new instance of `Self` from the given
+    /// `cap_std::net::UdpSocket`, putting it in non-blocking mode.
+    ///
+    /// This must be called from within a tokio runtime.
+    #[inline]
+    pub fn from_cap_std(cap_std: cap_std::net::UdpSocket) -> io::Result<Self> {
+        Self::from_std(net::UdpSocket::from_into_socketlike(cap_std))
+    }
+
+    /// Consumes `self` and returns the underlying `tokio::net::UdpSocket`.
+    #[inline]
+    pub fn into_tokio(self) -> tokio::net::UdpSocket {
+        self.tokio
+    }
+
+    /// Receives a single datagram message on the socket.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::recv_from`].
+    ///
+    /// [`tokio::net::UdpSocket::recv_from`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.recv_from
+    #[inline]
+    pub async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.tokio.recv_from(buf).await
+    }
+
+    /// Receives a single datagram message on the socket, without removing it
+    /// from the queue.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::peek_from`].
+    ///
+    /// [`tokio::net::UdpSocket::peek_from`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.peek_from
+    #[inline]
+    pub async fn peek_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.tokio.peek_from(buf).await
+    }
+
+    /// Returns the socket address of the remote peer this socket was
+    /// connected to.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::peer_addr`].
+    ///
+    /// [`tokio::net::UdpSocket::peer_addr`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.peer_addr
+    #[inline]
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.tokio.peer_addr()
+    }
+
+    /// Returns the socket address that this socket was created from.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::local_addr`].
+    ///
+    /// [`tokio::net::UdpSocket::local_addr`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.local_addr
+    #[inline]
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.tokio.local_addr()
+    }
+
+    /// Sends data on the socket to the remote address to which it is
+    /// connected.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::send`].
+    ///
+    /// [`tokio::net::UdpSocket::send`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.send
+    #[inline]
+    pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.tokio.send(buf).await
+    }
+
+    /// Receives a single datagram message on the socket from the remote
+    /// address to which it is connected.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::recv`].
+    ///
+    /// [`tokio::net::UdpSocket::recv`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.recv
+    #[inline]
+    pub async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.tokio.recv(buf).await
+    }
+
+    /// Receives single datagram on the socket from the remote address to
+    /// which it is connected, without removing the message from input queue.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::peek`].
+    ///
+    /// [`tokio::net::UdpSocket::peek`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.peek
+    #[inline]
+    pub async fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.tokio.peek(buf).await
+    }
+
+    /// Sets the value of the `SO_BROADCAST` option for this socket.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::set_broadcast`].
+    ///
+    /// [`tokio::net::UdpSocket::set_broadcast`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.set_broadcast
+    #[inline]
+    pub fn set_broadcast(&self, broadcast: bool) -> io::Result<()> {
+        self.tokio.set_broadcast(broadcast)
+    }
+
+    /// Gets the value of the `SO_BROADCAST` option for this socket.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::broadcast`].
+    ///
+    /// [`tokio::net::UdpSocket::broadcast`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.broadcast
+    #[inline]
+    pub fn broadcast(&self) -> io::Result<bool> {
+        self.tokio.broadcast()
+    }
+
+    /// Sets the value for the `IP_TTL` option on this socket.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::set_ttl`].
+    ///
+    /// [`tokio::net::UdpSocket::set_ttl`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.set_ttl
+    #[inline]
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.tokio.set_ttl(ttl)
+    }
+
+    /// Gets the value of the `IP_TTL` option for this socket.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::ttl`].
+    ///
+    /// [`tokio::net::UdpSocket::ttl`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.ttl
+    #[inline]
+    pub fn ttl(&self) -> io::Result<u32> {
+        self.tokio.ttl()
+    }
+
+    /// Gets the value of the `SO_ERROR` option on this socket.
+    ///
+    /// This corresponds to [`tokio::net::UdpSocket::take_error`].
+    ///
+    /// [`tokio::net::UdpSocket::take_error`]: https://docs.rs/tokio/latest/tokio/net/struct.UdpSocket.html#method.take_error
+    #[inline]
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.tokio.take_error()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsRawFd for UdpSocket {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.tokio.as_raw_fd()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsFd for UdpSocket {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.tokio.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl AsRawSocket for UdpSocket {
+    #[inline]
+    fn as_raw_socket(&self) -> RawSocket {
+        self.tokio.as_raw_socket()
+    }
+}
+
+#[cfg(windows)]
+impl AsSocket for UdpSocket {
+    #[inline]
+    fn as_socket(&self) -> BorrowedSocket<'_> {
+        self.tokio.as_socket()
+    }
+}
+
+impl fmt::Debug for UdpSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.tokio.fmt(f)
+    }
+}
@@ -0,0 +1,148 @@
+//! Temporary files.
+//!
+//! This corresponds to [`cap_tempfile::TempFile`], for use in async code.
+
+use crate::fs::{asyncify, Dir, File, Permissions};
+use std::ffi::OsStr;
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{fmt, io};
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+
+/// A file in a directory that is by default deleted when it goes out of
+/// scope, but may also be written persistently.
+///
+/// This corresponds to [`cap_tempfile::TempFile`]; see its documentation for
+/// details on naming and permissions. Creating and replacing the file run on
+/// tokio's blocking thread pool. If the file is dropped without being
+/// replaced, it is removed synchronously.
+pub struct TempFile {
+    inner: cap_tempfile::TempFile<'static>,
+    file: File,
+}
+
+impl TempFile {
+    /// Create a new temporary file in the provided directory.
+    pub async fn new(dir: &Dir) -> io::Result<Self> {
+        let dir = dir.shared();
+        let (inner, file) = asyncify(move || {
+            let inner = cap_tempfile::TempFile::new_shared(dir)?;
+            let file = inner.as_file().try_clone()?;
+            Ok((inner, file))
+        })
+        .await?;
+        Ok(Self {
+            inner,
+            file: File::from_cap_std(file),
+        })
+    }
+
+    /// Create a new temporary file in the provided directory that will not
+    /// have a name.
+    ///
+    /// This corresponds to [`cap_tempfile::TempFile::new_anonymous`].
+    pub async fn new_anonymous(dir: &Dir) -> io::Result<File> {
+        let dir = dir.shared();
+        let file = asyncify(move || cap_tempfile::TempFile::new_anonymous(&dir)).await?;
+        Ok(File::from_cap_std(file))
+    }
+
+    /// Get a reference to the underlying file.
+    #[inline]
+    pub fn as_file(&self) -> &File {
+        &self.file
+    }
+
+    /// Get a mutable reference to the underlying file.
+    #[inline]
+    pub fn as_file_mut(&mut self) -> &mut File {
+        &mut self.file
+    }
+
+    /// Write the file to the target directory with the provided name.
+    /// Any existing file will be replaced.
+    ///
+    /// This corresponds to [`cap_tempfile::TempFile::replace`]. Pending
+    /// writes are flushed first.
+    pub async fn replace(self, destname: impl AsRef<OsStr>) -> io::Result<()> {
+        let destname = destname.as_ref().to_owned();
+        let inner = self.finish().await?;
+        asyncify(move || inner.replace(destname)).await
+    }
+
+    /// Write the file to the target directory with the provided name and
+    /// permissions. Any existing file will be replaced.
+    ///
+    /// This corresponds to
+    /// [`cap_tempfile::TempFile::replace_with_permissions`]. Pending writes
+    /// are flushed first.
+    pub async fn replace_with_permissions(
+        self,
+        destname: impl AsRef<OsStr>,
+        permissions: Permissions,
+    ) -> io::Result<()> {
+        let destname = destname.as_ref().to_owned();
+        let inner = self.finish().await?;
+        asyncify(move || inner.replace_with_permissions(destname, permissions)).await
+    }
+
+    /// Flushes pending writes and waits for in-flight operations on the
+    /// async file, so that the contents are complete before they're
+    /// persisted.
+    async fn finish(mut self) -> io::Result<cap_tempfile::TempFile<'static>> {
+        poll_fn(|cx| Pin::new(&mut self.file).poll_flush(cx)).await?;
+        self.file.into_std().await;
+        Ok(self.inner)
+    }
+}
+
+impl AsyncRead for TempFile {
+    #[inline]
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.file).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for TempFile {
+    #[inline]
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.file).poll_write(cx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.file).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.file).poll_shutdown(cx)
+    }
+}
+
+impl AsyncSeek for TempFile {
+    #[inline]
+    fn start_seek(mut self: Pin<&mut Self>, position: io::SeekFrom) -> io::Result<()> {
+        Pin::new(&mut self.file).start_seek(position)
+    }
+
+    #[inline]
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Pin::new(&mut self.file).poll_complete(cx)
+    }
+}
+
+impl fmt::Debug for TempFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
@@ -0,0 +1,136 @@
+#[macro_use]
+mod sys_common;
+
+use sys_common::io::tmpdir;
+
+#[test]
+fn plain_operations() {
+    let tmpdir = tmpdir();
+    let confined = check!(tmpdir.confine_to_mount());
+
+    check!(confined.create_dir_all("a/b"));
+    check!(confined.write("a/b/file", b"hello"));
+    assert_eq!(check!(confined.read("a/b/file")), b"hello");
+    assert_eq!(check!(confined.read_to_string("a/../a/b/file")), "hello");
+    assert!(check!(confined.metadata("a/b/file")).is_file());
+    assert!(confined.is_dir("a/b"));
+
+    let names = check!(confined.read_dir("a/b"))
+        .map(|entry| check!(entry).file_name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["file"]);
+
+    let sub = check!(confined.open_dir("a"));
+    check!(confined.rename("a/b/file", &sub, "moved"));
+    assert_eq!(check!(sub.read("moved")), b"hello");
+    for entry in check!(sub.entries()) {
+        let entry = check!(entry);
+        if entry.file_name() == "moved" {
+            assert!(check!(entry.metadata()).is_file());
+            check!(entry.remove_file());
+        }
+    }
+    assert!(!sub.exists("moved"));
+
+    check!(confined.remove_dir("a/b"));
+    check!(confined.write("a/b", b""));
+    check!(confined.remove_dir_all("a"));
+    assert!(!check!(confined.try_exists("a")));
+}
+
+#[test]
+fn directories_as_files() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    let confined = check!(tmpdir.confine_to_mount());
+
+    // A directory opened as a `File` could be converted into an unconfined
+    // `Dir`, so directories can only be opened with `open_dir`.
+    assert!(confined.open(".").is_err());
+    assert!(confined.open("dir").is_err());
+    assert!(confined
+        .open_with("dir", cap_std::fs::OpenOptions::new().read(true))
+        .is_err());
+    for entry in check!(confined.entries()) {
+        assert!(check!(entry).open().is_err());
+    }
+    check!(confined.open_dir("."));
+}
+
+#[test]
+fn escapes() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.write("file", b""));
+    let confined = check!(check!(tmpdir.open_dir("dir")).confine_to_mount());
+
+    error_contains!(
+        confined.open("../file"),
+        "a path led outside of the filesystem"
+    );
+    error_contains!(
+        confined.metadata("../file"),
+        "a path led outside of the filesystem"
+    );
+    error_contains!(
+        confined.remove_file("../file"),
+        "a path led outside of the filesystem"
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn mount_points() {
+    use cap_std::ambient_authority;
+    use cap_std::fs::Dir;
+    use std::os::unix::fs::MetadataExt;
+
+    // `/proc` is normally a separate mount; skip the test if it isn't.
+    let root_dev = check!(std::fs::metadata("/")).dev();
+    match std::fs::metadata("/proc") {
+        Ok(metadata) if metadata.dev() != root_dev => {}
+        _ => return,
+    }
+
+    let root = check!(Dir::open_ambient_dir("/", ambient_authority()));
+    let confined = check!(root.confine_to_mount());
+    let xdev = Some(rustix::io::Errno::XDEV.raw_os_error());
+
+    assert_eq!(
+        confined.open("proc/version").unwrap_err().raw_os_error(),
+        xdev
+    );
+    assert_eq!(
+        confined
+            .metadata("proc/version")
+            .unwrap_err()
+            .raw_os_error(),
+        xdev
+    );
+    assert_eq!(
+        confined
+            .symlink_metadata("proc")
+            .unwrap_err()
+            .raw_os_error(),
+        xdev
+    );
+    assert_eq!(confined.read_dir("proc").unwrap_err().raw_os_error(), xdev);
+    assert_eq!(confined.open_dir("proc").unwrap_err().raw_os_error(), xdev);
+    assert_eq!(
+        confined
+            .remove_file("proc/version")
+            .unwrap_err()
+            .raw_os_error(),
+        xdev
+    );
+
+    let entry = check!(confined.entries())
+        .map(|entry| check!(entry))
+        .find(|entry| entry.file_name() == "proc")
+        .unwrap();
+    assert_eq!(entry.open_dir().unwrap_err().raw_os_error(), xdev);
+    assert_eq!(entry.metadata().unwrap_err().raw_os_error(), xdev);
+
+    // Paths which stay on the root filesystem still work.
+    assert!(confined.is_dir("."));
+}
@@ -0,0 +1,167 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{CopyOptions, Dir};
+use sys_common::io::tmpdir;
+use sys_common::symlink_supported;
+
+fn make_tree(dir: &Dir) {
+    check!(dir.create_dir_all("src/a/b"));
+    check!(dir.write("src/a/b/c.txt", b"hello"));
+    check!(dir.write("src/d.txt", b"world!"));
+}
+
+#[test]
+fn copy_dir_all_basic() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    let copied = check!(tmpdir.copy_dir_all("src", &tmpdir, "dst", &CopyOptions::new()));
+    assert_eq!(copied, 11);
+    assert_eq!(check!(tmpdir.read_to_string("dst/a/b/c.txt")), "hello");
+    assert_eq!(check!(tmpdir.read_to_string("dst/d.txt")), "world!");
+    assert!(tmpdir.is_dir("dst/a/b"));
+
+    // Copying over an existing tree overwrites files.
+    check!(tmpdir.write("src/d.txt", b"again"));
+    check!(tmpdir.copy_dir_all("src", &tmpdir, "dst", &CopyOptions::new()));
+    assert_eq!(check!(tmpdir.read_to_string("dst/d.txt")), "again");
+
+    // Copying between two different `Dir`s.
+    check!(tmpdir.create_dir("other"));
+    let other = check!(tmpdir.open_dir("other"));
+    check!(tmpdir.copy_dir_all("src", &other, "nested/dst", &CopyOptions::new()));
+    assert_eq!(
+        check!(other.read_to_string("nested/dst/a/b/c.txt")),
+        "hello"
+    );
+
+    // Paths can't escape either `Dir`.
+    assert!(other
+        .copy_dir_all("..", &other, "up", &CopyOptions::new())
+        .is_err());
+    assert!(other
+        .copy_dir_all("nested", &other, "../up", &CopyOptions::new())
+        .is_err());
+}
+
+#[test]
+fn copy_dir_all_into_itself() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    check!(tmpdir.copy_dir_all("src", &tmpdir, "src/a/copy", &CopyOptions::new()));
+    assert_eq!(
+        check!(tmpdir.read_to_string("src/a/copy/a/b/c.txt")),
+        "hello"
+    );
+    assert!(!tmpdir.exists("src/a/copy/a/copy"));
+}
+
+#[test]
+fn copy_dir_all_symlinks() {
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+    #[cfg(not(windows))]
+    check!(tmpdir.symlink("a/b/c.txt", "src/link"));
+    #[cfg(windows)]
+    check!(tmpdir.symlink_file("a\\b\\c.txt", "src\\link"));
+
+    check!(tmpdir.copy_dir_all("src", &tmpdir, "dst", &CopyOptions::new()));
+    assert!(check!(tmpdir.symlink_metadata("dst/link")).is_symlink());
+    assert_eq!(check!(tmpdir.read_to_string("dst/link")), "hello");
+
+    // Copying over an earlier copy replaces its symlinks.
+    check!(tmpdir.copy_dir_all("src", &tmpdir, "dst", &CopyOptions::new()));
+    assert_eq!(check!(tmpdir.read_to_string("dst/link")), "hello");
+    check!(tmpdir.remove_file("src/link"));
+    #[cfg(not(windows))]
+    check!(tmpdir.symlink("d.txt", "src/link"));
+    #[cfg(windows)]
+    check!(tmpdir.symlink_file("d.txt", "src\\link"));
+    check!(tmpdir.copy_dir_all("src", &tmpdir, "dst", &CopyOptions::new()));
+    assert!(check!(tmpdir.symlink_metadata("dst/link")).is_symlink());
+    assert_eq!(check!(tmpdir.read_to_string("dst/link")), "world!");
+
+    // A file in the way of a symlink is replaced too, without touching the
+    // file the symlink points to.
+    check!(tmpdir.remove_file("dst/link"));
+    check!(tmpdir.write("dst/link", b"in the way"));
+    check!(tmpdir.copy_dir_all("src", &tmpdir, "dst", &CopyOptions::new()));
+    assert!(check!(tmpdir.symlink_metadata("dst/link")).is_symlink());
+    assert_eq!(check!(tmpdir.read_to_string("dst/link")), "world!");
+
+    // A directory in the way is an error.
+    check!(tmpdir.remove_file("dst/link"));
+    check!(tmpdir.create_dir("dst/link"));
+    assert!(tmpdir
+        .copy_dir_all("src", &tmpdir, "dst", &CopyOptions::new())
+        .is_err());
+    assert!(tmpdir.is_dir("dst/link"));
+}
+
+#[cfg(not(target_os = "wasi"))]
+#[test]
+fn copy_dir_all_preserve() {
+    use cap_fs_ext::DirExt;
+    use cap_std::time::{Duration, SystemClock};
+
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    let mut perms = check!(tmpdir.metadata("src/a/b")).permissions();
+    perms.set_readonly(true);
+    check!(tmpdir.set_permissions("src/a/b", perms));
+
+    let time = SystemClock::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    check!(tmpdir.set_times("src/d.txt", None, Some(time.into())));
+
+    let mut options = CopyOptions::new();
+    options.preserve_permissions(true).preserve_times(true);
+    check!(tmpdir.copy_dir_all("src", &tmpdir, "dst", &options));
+    assert!(check!(tmpdir.metadata("dst/a/b")).permissions().readonly());
+    assert_eq!(
+        check!(check!(tmpdir.metadata("dst/d.txt")).modified()),
+        time
+    );
+    assert_eq!(check!(tmpdir.read_to_string("dst/a/b/c.txt")), "hello");
+
+    let mut perms = check!(tmpdir.metadata("src/a/b")).permissions();
+    perms.set_readonly(false);
+    check!(tmpdir.set_permissions("src/a/b", perms.clone()));
+    check!(tmpdir.set_permissions("dst/a/b", perms));
+}
+
+#[cfg(feature = "fs_utf8")]
+#[test]
+fn copy_dir_all_utf8() {
+    use sys_common::io::tmpdir_utf8;
+
+    let tmpdir = tmpdir_utf8();
+    check!(tmpdir.create_dir_all("src/a"));
+    check!(tmpdir.write("src/a/b.txt", b"b"));
+
+    check!(tmpdir.copy_dir_all("src", &tmpdir, "dst", &CopyOptions::new()));
+    assert_eq!(check!(tmpdir.read_to_string("dst/a/b.txt")), "b");
+}
+
+#[cfg(all(unix, not(target_vendor = "apple")))]
+#[test]
+fn copy_dir_all_fifo() {
+    use cap_fs_ext::DirExt;
+
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+    check!(tmpdir.create_fifo("src/a/fifo", 0o600));
+
+    // Copying a FIFO would block on opening it, so it's an error instead.
+    let err = tmpdir
+        .copy_dir_all("src", &tmpdir, "dst", &CopyOptions::new())
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(!tmpdir.exists("dst/a/fifo"));
+}
@@ -109,3 +109,160 @@ fn test_access() {
     tmpdir.access("file", AccessType::Exists).unwrap();
     tmpdir.access("nope", AccessType::Exists).unwrap_err();
 }
+
+#[cfg(all(unix, not(target_vendor = "apple")))]
+#[test]
+fn test_create_fifo() {
+    use cap_fs_ext::FileTypeExt;
+
+    let tempdir = TempDir::new(ambient_authority()).expect("create tempdir");
+    tempdir.create_dir("dir").expect("create dir");
+    tempdir.create_fifo("dir/fifo", 0o600).expect("create fifo");
+    let metadata = tempdir.symlink_metadata("dir/fifo").expect("stat fifo");
+    assert!(FileTypeExt::is_fifo(&metadata.file_type()));
+
+    assert_eq!(
+        tempdir.create_fifo("dir/fifo", 0o600).unwrap_err().kind(),
+        std::io::ErrorKind::AlreadyExists
+    );
+
+    let dir = tempdir.open_dir("dir").expect("open dir");
+    error_contains!(
+        dir.create_fifo("../escape", 0o600),
+        "a path led outside of the filesystem"
+    );
+    assert!(!tempdir.exists("escape"));
+}
+
+#[cfg(all(unix, not(target_vendor = "apple")))]
+#[test]
+fn test_mknod() {
+    use cap_fs_ext::{FileTypeExt, NodeKind};
+
+    let tempdir = TempDir::new(ambient_authority()).expect("create tempdir");
+    tempdir
+        .mknod("socket", NodeKind::Socket, 0o600, 0)
+        .expect("create socket");
+    assert!(FileTypeExt::is_socket(
+        &tempdir.symlink_metadata("socket").unwrap().file_type()
+    ));
+
+    tempdir
+        .mknod("fifo", NodeKind::Fifo, 0o600, 0)
+        .expect("create fifo");
+    assert!(FileTypeExt::is_fifo(
+        &tempdir.symlink_metadata("fifo").unwrap().file_type()
+    ));
+
+    tempdir
+        .mknod("file", NodeKind::RegularFile, 0o600, 0)
+        .expect("create file");
+    assert!(tempdir.symlink_metadata("file").unwrap().is_file());
+
+    // Don't follow a symlink in the last component.
+    if symlink_supported() {
+        tempdir.symlink("missing", "link").expect("create symlink");
+        assert_eq!(
+            tempdir
+                .mknod("link", NodeKind::Fifo, 0o600, 0)
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::AlreadyExists
+        );
+        assert!(!tempdir.exists("missing"));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_set_owner() {
+    use cap_fs_ext::{FollowSymlinks, OsMetadataExt};
+
+    let tempdir = TempDir::new(ambient_authority()).expect("create tempdir");
+    let file = tempdir.create("file").expect("create file");
+    let metadata = file.metadata().unwrap();
+    let (uid, gid) = (metadata.uid(), metadata.gid());
+
+    // Setting the owner to the current owner is always permitted.
+    tempdir
+        .set_owner("file", Some(uid), Some(gid), FollowSymlinks::Yes)
+        .expect("set owner");
+    tempdir
+        .set_owner("file", None, None, FollowSymlinks::No)
+        .expect("set no owner");
+    file.set_owner(Some(uid), Some(gid))
+        .expect("set file owner");
+
+    tempdir.create_dir("sub").expect("create subdir");
+    let dir = tempdir.open_dir("sub").expect("open subdir");
+    error_contains!(
+        dir.set_owner("..", Some(uid), Some(gid), FollowSymlinks::No),
+        "a path led outside of the filesystem"
+    );
+
+    if symlink_supported() {
+        dir.symlink("..", "up").expect("create symlink");
+        error_contains!(
+            dir.set_owner("up", Some(uid), Some(gid), FollowSymlinks::Yes),
+            "a path led outside of the filesystem"
+        );
+        dir.set_owner("up", Some(uid), Some(gid), FollowSymlinks::No)
+            .expect("set symlink owner");
+    }
+
+    if !rustix::process::geteuid().is_root() {
+        return;
+    }
+
+    tempdir
+        .set_owner("file", Some(1234), Some(5678), FollowSymlinks::Yes)
+        .expect("set owner");
+    let metadata = tempdir.metadata("file").unwrap();
+    assert_eq!((metadata.uid(), metadata.gid()), (1234, 5678));
+
+    file.set_owner(Some(4321), None).expect("set file owner");
+    let metadata = file.metadata().unwrap();
+    assert_eq!((metadata.uid(), metadata.gid()), (4321, 5678));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_set_owner_recursive() {
+    use cap_fs_ext::OsMetadataExt;
+
+    let tempdir = TempDir::new(ambient_authority()).expect("create tempdir");
+    tempdir.create_dir_all("outside").expect("create dir");
+    tempdir.create_dir_all("tree/a/b").expect("create dirs");
+    tempdir.write("tree/a/b/file", b"x").expect("create file");
+    tempdir.write("tree/top", b"x").expect("create file");
+    if symlink_supported() {
+        tempdir
+            .symlink("../outside", "tree/a/escape")
+            .expect("create symlink");
+    }
+    let tree = tempdir.open_dir("tree").expect("open tree");
+
+    let metadata = tempdir.metadata("tree").unwrap();
+    let (uid, gid) = (metadata.uid(), metadata.gid());
+    tree.set_owner_recursive(".", Some(uid), Some(gid))
+        .expect("set owner recursively");
+
+    if !rustix::process::geteuid().is_root() {
+        return;
+    }
+
+    tree.set_owner_recursive(".", Some(1234), Some(5678))
+        .expect("set owner recursively");
+    for path in ["tree", "tree/a", "tree/a/b", "tree/a/b/file", "tree/top"] {
+        let metadata = tempdir.symlink_metadata(path).unwrap();
+        assert_eq!((metadata.uid(), metadata.gid()), (1234, 5678), "{}", path);
+    }
+    if symlink_supported() {
+        let metadata = tempdir.symlink_metadata("tree/a/escape").unwrap();
+        assert_eq!((metadata.uid(), metadata.gid()), (1234, 5678));
+    }
+
+    // The symlink's target, outside the tree, is untouched.
+    let metadata = tempdir.metadata("outside").unwrap();
+    assert_eq!((metadata.uid(), metadata.gid()), (uid, gid));
+}
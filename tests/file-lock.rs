@@ -0,0 +1,87 @@
+#![cfg(not(target_os = "wasi"))]
+
+#[macro_use]
+mod sys_common;
+
+use sys_common::io::tmpdir;
+
+#[test]
+fn lock_whole_file() {
+    let tmpdir = tmpdir();
+    let a = check!(tmpdir.create("file"));
+    let b = check!(tmpdir.open("file"));
+
+    // Shared locks can be held by several handles at once.
+    check!(a.lock_shared());
+    assert!(check!(b.try_lock_shared()));
+    assert!(!check!(b.try_lock_exclusive()));
+    check!(a.unlock());
+    check!(b.unlock());
+
+    // Exclusive locks exclude everything else.
+    check!(a.lock_exclusive());
+    assert!(!check!(b.try_lock_shared()));
+    assert!(!check!(b.try_lock_exclusive()));
+    check!(a.unlock());
+    assert!(check!(b.try_lock_exclusive()));
+    check!(b.unlock());
+}
+
+#[cfg(any(target_os = "linux", windows))]
+#[test]
+fn lock_range() {
+    let tmpdir = tmpdir();
+    let a = check!(tmpdir.open_with(
+        "file",
+        cap_std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+    ));
+    check!(a.set_len(100));
+    let b = check!(tmpdir.open_with(
+        "file",
+        cap_std::fs::OpenOptions::new().read(true).write(true)
+    ));
+
+    check!(a.lock_range_exclusive(0, 10));
+    assert!(!check!(b.try_lock_range_shared(5, 10)));
+    assert!(check!(b.try_lock_range_exclusive(10, 10)));
+    check!(b.unlock_range(10, 10));
+
+    check!(a.unlock_range(0, 10));
+    assert!(check!(b.try_lock_range_shared(5, 10)));
+    assert!(check!(a.try_lock_range_shared(0, 10)));
+    assert!(!check!(a.try_lock_range_exclusive(0, 0)));
+    check!(a.unlock_range(0, 10));
+    check!(b.unlock_range(5, 10));
+}
+
+#[test]
+fn dir_lock_file() {
+    let tmpdir = tmpdir();
+
+    let lock = check!(tmpdir.lock_file("lock"));
+    let other = check!(tmpdir.open("lock"));
+    assert!(!check!(other.try_lock_exclusive()));
+    check!(lock.metadata());
+    drop(lock);
+    assert!(check!(other.try_lock_exclusive()));
+    check!(other.unlock());
+
+    // Paths can't escape the `Dir`.
+    assert!(tmpdir.lock_file("../lock").is_err());
+}
+
+#[cfg(feature = "fs_utf8")]
+#[test]
+fn dir_lock_file_utf8() {
+    use sys_common::io::tmpdir_utf8;
+
+    let tmpdir = tmpdir_utf8();
+    let lock = check!(tmpdir.lock_file("lock"));
+    let other = check!(tmpdir.open("lock"));
+    assert!(!check!(other.try_lock_shared()));
+    drop(lock);
+    assert!(check!(other.try_lock_shared()));
+}
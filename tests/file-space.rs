@@ -0,0 +1,95 @@
+#![cfg(any(target_os = "android", target_os = "linux"))]
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::{FileSpaceExt, OsMetadataExt};
+use cap_std::fs::OpenOptions;
+use std::io::Write;
+use sys_common::io::tmpdir;
+
+const BLOCK: u64 = 64 * 1024;
+
+/// Not all filesystems support holes.
+fn holes_supported(result: std::io::Result<()>) -> bool {
+    match result {
+        Ok(()) => true,
+        Err(err) if err.raw_os_error() == Some(rustix::io::Errno::OPNOTSUPP.raw_os_error()) => {
+            false
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn allocate_and_zero() {
+    let tmpdir = tmpdir();
+    let file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().read(true).write(true).create(true)
+    ));
+
+    check!(file.allocate(0, BLOCK));
+    assert_eq!(check!(file.metadata()).len(), BLOCK);
+
+    (&file).write_all(&[1; 16]).unwrap();
+    if !holes_supported(file.zero_range(0, 8)) {
+        return;
+    }
+    let contents = check!(tmpdir.read("file"));
+    assert_eq!(
+        &contents[..16],
+        &[0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1]
+    );
+}
+
+#[test]
+fn punch_hole_and_extents() {
+    let tmpdir = tmpdir();
+    let file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().read(true).write(true).create(true)
+    ));
+    (&file).write_all(&vec![7; (BLOCK * 3) as usize]).unwrap();
+    check!(file.sync_all());
+
+    if !holes_supported(file.punch_hole(BLOCK, BLOCK)) {
+        return;
+    }
+    assert_eq!(check!(file.metadata()).len(), BLOCK * 3);
+    let contents = check!(tmpdir.read("file"));
+    assert!(contents[BLOCK as usize..(BLOCK * 2) as usize]
+        .iter()
+        .all(|b| *b == 0));
+
+    let extents = file.data_extents().collect::<Result<Vec<_>, _>>().unwrap();
+    // Some filesystems report holes at a coarser granularity, or not at all.
+    if extents.len() == 2 {
+        assert_eq!(extents, vec![0..BLOCK, BLOCK * 2..BLOCK * 3]);
+        assert_eq!(check!(file.seek_data(BLOCK)), Some(BLOCK * 2));
+        assert_eq!(check!(file.seek_hole(0)), BLOCK);
+    }
+    assert_eq!(check!(file.seek_hole(BLOCK * 2)), BLOCK * 3);
+    assert_eq!(check!(file.seek_data(BLOCK * 3)), None);
+}
+
+#[test]
+fn copy_preserves_holes() {
+    let tmpdir = tmpdir();
+    let file = check!(tmpdir.create("sparse"));
+    (&file).write_all(&vec![7; BLOCK as usize]).unwrap();
+    check!(file.set_len(BLOCK * 64));
+    drop(file);
+
+    let original = check!(tmpdir.metadata("sparse"));
+    if original.blocks() * 512 >= original.len() {
+        // This filesystem doesn't support holes.
+        return;
+    }
+
+    assert_eq!(check!(tmpdir.copy("sparse", &tmpdir, "copy")), BLOCK * 64);
+    let copy = check!(tmpdir.metadata("copy"));
+    assert_eq!(copy.len(), BLOCK * 64);
+    assert!(copy.blocks() * 512 < copy.len());
+    assert_eq!(check!(tmpdir.read("sparse")), check!(tmpdir.read("copy")));
+}
@@ -0,0 +1,68 @@
+#[macro_use]
+mod sys_common;
+
+use cap_net_ext::{AddressFamily, Blocking, PoolExt, TcpListenerExt};
+use cap_std::ambient_authority;
+use cap_std::net::{Access, Pool, TcpListener};
+
+#[test]
+fn connect_only() {
+    let mut server_pool = Pool::new();
+    server_pool.insert_ip_net_port_any("127.0.0.0/8".parse().unwrap(), ambient_authority());
+    let listener = check!(server_pool.bind_tcp_listener("127.0.0.1:0"));
+    let addr = check!(listener.local_addr());
+
+    // A client may connect to the backend, but not bind to it, and not use
+    // UDP with it.
+    let mut client_pool = Pool::new();
+    client_pool.insert_ip_net_port_range_access(
+        "127.0.0.1/32".parse().unwrap(),
+        addr.port(),
+        addr.port().checked_add(1),
+        Access::TCP_CONNECT,
+        ambient_authority(),
+    );
+
+    check!(client_pool.connect_tcp_stream(addr));
+    error_contains!(
+        client_pool.bind_tcp_listener(addr),
+        "An address was outside the pool"
+    );
+    error_contains!(
+        client_pool.bind_udp_socket(addr),
+        "An address was outside the pool"
+    );
+
+    assert!(client_pool.tcp_connecter(addr).is_ok());
+    assert!(client_pool.tcp_binder(addr).is_err());
+    assert!(client_pool.udp_connecter(addr).is_err());
+
+    let socket = check!(TcpListener::new(
+        AddressFamily::of_socket_addr(addr),
+        Blocking::Yes
+    ));
+    assert!(client_pool
+        .bind_existing_tcp_listener(&socket, addr)
+        .is_err());
+    check!(client_pool.connect_existing_tcp_listener(&socket, addr));
+}
+
+#[test]
+fn deny_binding() {
+    let mut pool = Pool::new();
+    pool.insert_ip_net_port_any("127.0.0.0/8".parse().unwrap(), ambient_authority());
+    pool.deny_ip_net_port_range_access("127.0.0.0/8".parse().unwrap(), 0, None, Access::BIND);
+
+    error_contains!(
+        pool.bind_tcp_listener("127.0.0.1:0"),
+        "An address was denied by the pool"
+    );
+    error_contains!(
+        pool.bind_udp_socket("127.0.0.1:0"),
+        "An address was denied by the pool"
+    );
+    assert_eq!(
+        pool.to_string(),
+        "allow 127.0.0.0/8:*\ndeny bind 127.0.0.0/8:*\n"
+    );
+}
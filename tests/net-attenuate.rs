@@ -0,0 +1,59 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::ambient_authority;
+use cap_std::net::{Access, Pool};
+
+#[test]
+fn restrict_for_component() {
+    let mut pool = Pool::new();
+    pool.insert_ip_net_port_any("127.0.0.0/8".parse().unwrap(), ambient_authority());
+    let listener = check!(pool.bind_tcp_listener("127.0.0.1:0"));
+    let addr = check!(listener.local_addr());
+
+    // Hand a component connect-only TCP authority to just the listener.
+    let component = pool.restrict(
+        "127.0.0.1/32".parse().unwrap(),
+        addr.port(),
+        addr.port().checked_add(1),
+        Access::TCP_CONNECT,
+    );
+    assert!(component.is_subset_of(&pool));
+    assert!(!pool.is_subset_of(&component));
+
+    check!(component.connect_tcp_stream(addr));
+    error_contains!(
+        component.bind_tcp_listener("127.0.0.1:0"),
+        "An address was outside the pool"
+    );
+    error_contains!(
+        component.connect_tcp_stream("127.0.0.2:80"),
+        "An address was outside the pool"
+    );
+}
+
+#[test]
+fn intersect_policies() {
+    let service = check!(Pool::parse(
+        "allow 127.0.0.0/8:*\nallow api.internal:*\n",
+        ambient_authority()
+    ));
+    let policy = check!(Pool::parse(
+        "allow connect 0.0.0.0/0:*\ndeny 127.0.0.2:*\n",
+        ambient_authority()
+    ));
+
+    let effective = service.intersect(&policy);
+    assert!(effective.is_subset_of(&service));
+    assert!(effective.is_subset_of(&policy));
+    assert_eq!(
+        effective.to_string(),
+        "allow connect 127.0.0.0/8:*\ndeny 127.0.0.2:*\n"
+    );
+
+    assert!(effective.is_subset_of(&service.restrict_access(Access::CONNECT)));
+    assert!(service
+        .restrict_access(Access::CONNECT)
+        .is_subset_of(&service));
+    assert!(Pool::new().is_subset_of(&effective));
+}
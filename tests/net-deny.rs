@@ -0,0 +1,45 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::ambient_authority;
+use cap_std::net::{Access, Pool, Verdict};
+
+#[test]
+fn deny_overrides_allow() {
+    let mut pool = Pool::new();
+    pool.insert_ip_net_port_any("127.0.0.0/8".parse().unwrap(), ambient_authority());
+    pool.deny_ip_net_port_any("127.0.0.2/32".parse().unwrap());
+    pool.deny_ip_net("127.0.0.0/8".parse().unwrap(), 22);
+
+    let listener = check!(pool.bind_tcp_listener("127.0.0.1:0"));
+    let addr = check!(listener.local_addr());
+    check!(pool.connect_tcp_stream(addr));
+
+    error_contains!(
+        pool.bind_tcp_listener("127.0.0.2:0"),
+        "An address was denied by the pool"
+    );
+    error_contains!(
+        pool.connect_tcp_stream("127.0.0.1:22"),
+        "An address was denied by the pool"
+    );
+    error_contains!(
+        pool.connect_tcp_stream("10.0.0.1:80"),
+        "An address was outside the pool"
+    );
+
+    match pool.explain_addr(&"127.0.0.1:22".parse().unwrap(), Access::ALL) {
+        Verdict::Denied(rule) => {
+            assert!(rule.is_deny());
+            assert_eq!(rule.ip_net(), Some("127.0.0.0/8".parse().unwrap()));
+            assert_eq!(rule.ports_start(), 22);
+            assert_eq!(rule.ports_end(), Some(23));
+        }
+        verdict => panic!("unexpected verdict: {:?}", verdict),
+    }
+    assert_eq!(
+        pool.explain_addr(&addr, Access::ALL),
+        Verdict::Allowed(pool.rules()[0].clone())
+    );
+    assert_eq!(pool.rules().len(), 3);
+}
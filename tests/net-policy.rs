@@ -0,0 +1,66 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::ambient_authority;
+use cap_std::net::{Pool, Rule};
+use sys_common::io::tmpdir;
+
+const POLICY: &str = "\
+# Internal services.
+allow 127.0.0.0/8:*
+deny 127.0.0.2:*
+
+allow [::1]:5000-5010
+allow api.internal:443
+";
+
+#[test]
+fn load_from_dir() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("net.policy", POLICY));
+
+    let text = check!(tmpdir.read_to_string("net.policy"));
+    let pool = check!(Pool::parse(&text, ambient_authority()));
+    assert_eq!(
+        pool.to_string(),
+        "allow 127.0.0.0/8:*\ndeny 127.0.0.2:*\nallow [::1]:5000-5010\nallow api.internal:443\n"
+    );
+
+    let listener = check!(pool.bind_tcp_listener("127.0.0.1:0"));
+    check!(pool.connect_tcp_stream(check!(listener.local_addr())));
+    error_contains!(
+        pool.connect_tcp_stream("127.0.0.2:80"),
+        "An address was denied by the pool"
+    );
+
+    // Printing and parsing again produces the same rules.
+    let reparsed = check!(Pool::parse(&pool.to_string(), ambient_authority()));
+    assert_eq!(reparsed.rules(), pool.rules());
+}
+
+#[test]
+fn parse_errors() {
+    error_contains!(
+        Pool::parse("allow 127.0.0.1:80\nallow 127.0.0.1", ambient_authority()),
+        "line 2: invalid pool rule: expected `:` and a port"
+    );
+    assert!("allow [::1]:80".parse::<Rule>().is_ok());
+    assert!("allow ::1:80".parse::<Rule>().is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let pool = check!(Pool::parse(POLICY, ambient_authority()));
+    let json = serde_json::to_string(&pool).unwrap();
+    assert_eq!(
+        json,
+        r#"["allow 127.0.0.0/8:*","deny 127.0.0.2:*","allow [::1]:5000-5010","allow api.internal:443"]"#
+    );
+
+    let rules: Vec<Rule> = serde_json::from_str(&json).unwrap();
+    let loaded = Pool::from_rules(rules, ambient_authority());
+    assert_eq!(loaded.rules(), pool.rules());
+
+    assert!(serde_json::from_str::<Vec<Rule>>(r#"["allow nowhere"]"#).is_err());
+}
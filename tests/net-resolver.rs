@@ -0,0 +1,66 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::ambient_authority;
+use cap_std::net::{IpAddr, Ipv4Addr, Pool, Resolver, StaticResolver};
+
+fn resolver() -> Resolver {
+    let mut table = StaticResolver::new();
+    table.insert("service.test", IpAddr::V4(Ipv4Addr::LOCALHOST));
+    Resolver::new(table)
+}
+
+#[test]
+fn connect_tcp_by_name() {
+    let mut listen_pool = Pool::new();
+    listen_pool.insert_socket_addr("127.0.0.1:0".parse().unwrap(), ambient_authority());
+    let listener = check!(listen_pool.bind_tcp_listener("127.0.0.1:0"));
+    let addr = check!(listener.local_addr());
+
+    let mut pool = Pool::new();
+    check!(pool.insert_host("Service.Test", addr.port(), ambient_authority()));
+    let resolver = resolver();
+
+    let stream = check!(pool.connect_tcp_stream_host(&resolver, "service.test", addr.port()));
+    assert_eq!(check!(stream.peer_addr()), addr);
+    let (accepted, _) = check!(listener.accept());
+    assert_eq!(check!(accepted.local_addr()), addr);
+
+    // The grant is for the name, not for the address it resolves to.
+    assert!(pool.connect_tcp_stream(addr).is_err());
+
+    error_contains!(
+        pool.connect_tcp_stream_host(&resolver, "service.test", addr.port().wrapping_add(1)),
+        "A host name was outside the pool"
+    );
+    error_contains!(
+        pool.connect_tcp_stream_host(&resolver, "other.test", addr.port()),
+        "A host name was outside the pool"
+    );
+
+    // A granted name which the resolver doesn't know fails to resolve.
+    check!(pool.insert_host("unknown.test", addr.port(), ambient_authority()));
+    error_contains!(
+        pool.connect_tcp_stream_host(&resolver, "unknown.test", addr.port()),
+        "host name not found in table"
+    );
+}
+
+#[test]
+fn connect_udp_by_name() {
+    let mut pool = Pool::new();
+    pool.insert_socket_addr("127.0.0.1:0".parse().unwrap(), ambient_authority());
+    let a = check!(pool.bind_udp_socket("127.0.0.1:0"));
+    let b = check!(pool.bind_udp_socket("127.0.0.1:0"));
+    let b_addr = check!(b.local_addr());
+
+    let mut pool = Pool::new();
+    check!(pool.insert_host_port_any("service.test", ambient_authority()));
+    check!(pool.connect_udp_socket_host(&a, &resolver(), "service.test", b_addr.port()));
+    check!(a.send(b"hello"));
+
+    let mut buf = [0; 8];
+    let (n, from) = check!(b.recv_from(&mut buf));
+    assert_eq!(&buf[..n], b"hello");
+    assert_eq!(from, check!(a.local_addr()));
+}
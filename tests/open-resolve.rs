@@ -0,0 +1,107 @@
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::{OpenOptions, OpenOptionsResolveExt};
+use std::io;
+use sys_common::io::tmpdir;
+
+#[test]
+fn plain_paths() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.write("dir/file", b"hello"));
+
+    let mut options = OpenOptions::new();
+    options
+        .read(true)
+        .no_xdev(true)
+        .no_magiclinks(true)
+        .no_symlinks(true);
+    check!(tmpdir.open_with("dir/file", &options));
+    check!(tmpdir.open_with("dir/../dir/file", &options));
+
+    error_contains!(
+        check!(tmpdir.open_dir("dir")).open_with("../../file", &options),
+        "a path led outside of the filesystem"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn no_symlinks() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.write("dir/file", b"hello"));
+    check!(tmpdir.symlink("file", "dir/link"));
+    check!(tmpdir.symlink("dir", "dirlink"));
+
+    check!(tmpdir.open("dir/link"));
+    check!(tmpdir.open("dirlink/file"));
+
+    let mut options = OpenOptions::new();
+    options.read(true).no_symlinks(true);
+    let err = tmpdir.open_with("dir/link", &options).unwrap_err();
+    assert_eq!(
+        err.raw_os_error(),
+        Some(rustix::io::Errno::LOOP.raw_os_error())
+    );
+    let err = tmpdir.open_with("dirlink/file", &options).unwrap_err();
+    assert_eq!(
+        err.raw_os_error(),
+        Some(rustix::io::Errno::LOOP.raw_os_error())
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn no_xdev() {
+    use cap_std::ambient_authority;
+    use cap_std::fs::Dir;
+    use std::os::unix::fs::MetadataExt;
+
+    // `/proc` is normally a separate mount; skip the test if it isn't.
+    let root_dev = check!(std::fs::metadata("/")).dev();
+    match std::fs::metadata("/proc") {
+        Ok(metadata) if metadata.dev() != root_dev => {}
+        _ => return,
+    }
+
+    let root = check!(Dir::open_ambient_dir("/", ambient_authority()));
+    check!(root.open("proc/version"));
+
+    let mut options = OpenOptions::new();
+    options.read(true).no_xdev(true);
+    let err = root.open_with("proc/version", &options).unwrap_err();
+    assert_eq!(
+        err.raw_os_error(),
+        Some(rustix::io::Errno::XDEV.raw_os_error())
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn no_magiclinks() {
+    use cap_std::ambient_authority;
+    use cap_std::fs::Dir;
+
+    let root = check!(Dir::open_ambient_dir("/", ambient_authority()));
+    let mut options = OpenOptions::new();
+    options.read(true).no_magiclinks(true);
+    assert!(root.open_with("proc/self/exe", &options).is_err());
+}
+
+#[test]
+fn cached() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+    check!(tmpdir.read("file"));
+
+    // The lookup may or may not be satisfiable from the cache, but it must
+    // never fail for any other reason.
+    let mut options = OpenOptions::new();
+    options.read(true).cached(true);
+    match tmpdir.open_with("file", &options) {
+        Ok(_) => {}
+        Err(err) => assert_eq!(err.kind(), io::ErrorKind::WouldBlock),
+    }
+}
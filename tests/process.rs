@@ -0,0 +1,94 @@
+#![cfg(any(target_os = "android", target_os = "linux", target_os = "freebsd"))]
+
+#[macro_use]
+mod sys_common;
+
+use cap_std::ambient_authority;
+use cap_std::fs::Dir;
+use cap_std::process::{Command, Stdio};
+use std::os::unix::io::AsRawFd;
+use sys_common::io::tmpdir;
+
+fn sh() -> Command {
+    let bin = check!(Dir::open_ambient_dir("/bin", ambient_authority()));
+    let mut command = check!(Command::open(&bin, "sh"));
+    command.arg("-c");
+    command
+}
+
+#[test]
+fn output_and_status() {
+    let output = check!(sh().arg("echo hello; echo oops >&2").output());
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"hello\n");
+    assert_eq!(output.stderr, b"oops\n");
+
+    let status = check!(sh().arg("exit 3").stdout(Stdio::null()).status());
+    assert_eq!(status.code(), Some(3));
+}
+
+#[test]
+fn current_dir() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    let dir = check!(tmpdir.open_dir("dir"));
+
+    let status = check!(sh().arg("echo hello > file").current_dir(dir).status());
+    assert!(status.success());
+    assert_eq!(check!(tmpdir.read("dir/file")), b"hello\n");
+}
+
+#[test]
+fn environment() {
+    let output = check!(sh()
+        .arg("echo \"$FOO ${HOME-unset} ${BAR-unset}\"")
+        .env("FOO", "foo")
+        .env("BAR", "bar")
+        .env_remove("BAR")
+        .output());
+    assert_eq!(output.stdout, b"foo unset unset\n");
+}
+
+#[test]
+fn inherited_fds() {
+    let tmpdir = tmpdir();
+    let file = check!(tmpdir.create("file"));
+
+    let status = check!(sh().arg("echo hello >&5").fd(5, file).status());
+    assert!(status.success());
+    assert_eq!(check!(tmpdir.read("file")), b"hello\n");
+
+    // File descriptors which aren't passed explicitly aren't inherited.
+    let file = check!(tmpdir.open("file"));
+    let status = check!(sh()
+        .arg(format!("echo hello >&{}", file.as_raw_fd()))
+        .stderr(Stdio::null())
+        .status());
+    assert!(!status.success());
+}
+
+#[test]
+fn bad_programs() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("data", b"not a program"));
+
+    assert!(Command::open(&tmpdir, "missing").is_err());
+    assert!(check!(Command::open(&tmpdir, "data")).spawn().is_err());
+    assert!(sh().arg("x\0y").spawn().is_err());
+}
+
+#[test]
+fn bad_program_with_fds() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("data", b"not a program"));
+    let file = check!(tmpdir.create("file"));
+
+    // Exec failures are reported even if one of the targets is the file
+    // descriptor `std` uses internally to report them.
+    for fd in 3..16 {
+        let mut command = check!(Command::open(&tmpdir, "data"));
+        command.fd(fd, check!(file.try_clone()));
+        assert!(command.spawn().is_err(), "fd {}", fd);
+        assert!(command.status().is_err(), "fd {}", fd);
+    }
+}
@@ -0,0 +1,104 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{CopyOptions, OpenOptions};
+use std::io;
+use sys_common::io::tmpdir;
+
+/// Not all filesystems support copy-on-write clones.
+fn reflink_supported<T>(result: io::Result<T>) -> bool {
+    match result {
+        Ok(_) => true,
+        Err(err) if err.kind() == io::ErrorKind::Unsupported => false,
+        Err(err) => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn reflink_basic() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("src", b"hello world"));
+
+    if !reflink_supported(tmpdir.reflink("src", &tmpdir, "dst")) {
+        return;
+    }
+    assert_eq!(check!(tmpdir.read("dst")), b"hello world");
+}
+
+#[test]
+fn reflink_missing() {
+    let tmpdir = tmpdir();
+    assert_eq!(
+        tmpdir
+            .reflink("missing", &tmpdir, "dst")
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::NotFound
+    );
+}
+
+#[test]
+fn reflink_escape() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("nested"));
+    check!(tmpdir.write("src", b"hello"));
+    let nested = check!(tmpdir.open_dir("nested"));
+
+    error_contains!(
+        nested.reflink("../src", &nested, "dst"),
+        "a path led outside of the filesystem"
+    );
+    error_contains!(
+        tmpdir.reflink("src", &nested, "../dst"),
+        "a path led outside of the filesystem"
+    );
+}
+
+#[test]
+fn copy_with_reflink_falls_back() {
+    let tmpdir = tmpdir();
+    let contents = vec![7_u8; 100_000];
+    check!(tmpdir.write("src", &contents));
+
+    let mut options = CopyOptions::new();
+    options.reflink(true);
+    let copied = check!(tmpdir.copy_with("src", &tmpdir, "dst", &options));
+    assert_eq!(copied, contents.len() as u64);
+    assert_eq!(check!(tmpdir.read("dst")), contents);
+}
+
+#[test]
+fn copy_with_preserve_times() {
+    use cap_fs_ext::DirExt;
+    use cap_std::time::{Duration, SystemClock};
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("src", b"hello"));
+    let time = SystemClock::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    check!(tmpdir.set_times("src", None, Some(time.into())));
+
+    let mut options = CopyOptions::new();
+    options.reflink(true).preserve_times(true);
+    check!(tmpdir.copy_with("src", &tmpdir, "dst", &options));
+    assert_eq!(check!(check!(tmpdir.metadata("dst")).modified()), time);
+    assert_eq!(check!(tmpdir.read("dst")), b"hello");
+}
+
+#[test]
+fn clone_range() {
+    let tmpdir = tmpdir();
+    let src = check!(tmpdir.open_with(
+        "src",
+        OpenOptions::new().read(true).write(true).create(true)
+    ));
+    check!(src.set_len(64 * 1024));
+    let dst = check!(tmpdir.open_with(
+        "dst",
+        OpenOptions::new().read(true).write(true).create(true)
+    ));
+
+    if !reflink_supported(dst.clone_range_from(&src, 0, 0, 0)) {
+        return;
+    }
+    assert_eq!(check!(dst.metadata()).len(), 64 * 1024);
+}
@@ -0,0 +1,127 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{DirRights, RenameOptions};
+use std::io;
+use sys_common::io::tmpdir;
+
+#[test]
+fn rename_noreplace() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("a", b"a"));
+    check!(tmpdir.write("b", b"b"));
+
+    assert_eq!(
+        tmpdir
+            .rename_noreplace("a", &tmpdir, "b")
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::AlreadyExists
+    );
+    assert_eq!(check!(tmpdir.read("a")), b"a");
+    assert_eq!(check!(tmpdir.read("b")), b"b");
+
+    check!(tmpdir.rename_noreplace("a", &tmpdir, "c"));
+    assert!(!tmpdir.exists("a"));
+    assert_eq!(check!(tmpdir.read("c")), b"a");
+
+    // Directories work too, on platforms with native support.
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.create_dir("other"));
+    match tmpdir.rename_noreplace("dir", &tmpdir, "other") {
+        Err(err) if err.kind() == io::ErrorKind::Unsupported => return,
+        result => assert_eq!(result.unwrap_err().kind(), io::ErrorKind::AlreadyExists),
+    }
+    check!(tmpdir.rename_noreplace("dir", &tmpdir, "renamed"));
+    assert!(tmpdir.is_dir("renamed"));
+}
+
+#[test]
+fn rename_noreplace_subdirs() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("x/y"));
+    check!(tmpdir.write("x/file", b"file"));
+    let x = check!(tmpdir.open_dir("x"));
+
+    check!(x.rename_noreplace("file", &tmpdir, "y_file"));
+    assert_eq!(check!(tmpdir.read("y_file")), b"file");
+
+    // Paths can't escape the directory.
+    assert!(x.rename_noreplace("../y_file", &x, "file").is_err());
+    assert!(x.rename_noreplace("y", &x, "../escaped").is_err());
+    assert!(tmpdir.exists("y_file"));
+    assert!(!tmpdir.exists("escaped"));
+}
+
+#[test]
+fn exchange() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("a", b"a"));
+    check!(tmpdir.create_dir("b"));
+    check!(tmpdir.write("b/file", b"file"));
+
+    match tmpdir.exchange("a", &tmpdir, "b") {
+        Err(err) if err.kind() == io::ErrorKind::Unsupported => return,
+        // Some filesystems don't support `RENAME_EXCHANGE`.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        Err(err) if err.raw_os_error() == Some(rustix::io::Errno::INVAL.raw_os_error()) => return,
+        result => check!(result),
+    }
+    assert!(tmpdir.is_dir("a"));
+    assert_eq!(check!(tmpdir.read("a/file")), b"file");
+    assert_eq!(check!(tmpdir.read("b")), b"a");
+
+    // Both entries must exist.
+    assert_eq!(
+        tmpdir.exchange("a", &tmpdir, "c").unwrap_err().kind(),
+        io::ErrorKind::NotFound
+    );
+}
+
+#[test]
+fn rename_with_default() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("a", b"a"));
+    check!(tmpdir.write("b", b"b"));
+
+    check!(tmpdir.rename_with("a", &tmpdir, "b", &RenameOptions::new()));
+    assert!(!tmpdir.exists("a"));
+    assert_eq!(check!(tmpdir.read("b")), b"a");
+}
+
+#[test]
+fn rename_noreplace_restricted() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("a", b"a"));
+
+    let dir = check!(tmpdir.restrict(DirRights::read_only()));
+    assert_eq!(
+        dir.rename_noreplace("a", &dir, "b").unwrap_err().kind(),
+        io::ErrorKind::PermissionDenied
+    );
+    assert_eq!(
+        dir.exchange("a", &dir, "b").unwrap_err().kind(),
+        io::ErrorKind::PermissionDenied
+    );
+    assert!(tmpdir.exists("a"));
+}
+
+#[cfg(feature = "fs_utf8")]
+#[test]
+fn rename_noreplace_utf8() {
+    use sys_common::io::tmpdir_utf8;
+
+    let tmpdir = tmpdir_utf8();
+    check!(tmpdir.write("a", b"a"));
+    check!(tmpdir.write("b", b"b"));
+
+    assert_eq!(
+        tmpdir
+            .rename_noreplace("a", &tmpdir, "b")
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::AlreadyExists
+    );
+    check!(tmpdir.rename_noreplace("a", &tmpdir, "c"));
+    assert_eq!(check!(tmpdir.read("c")), b"a");
+}
@@ -0,0 +1,210 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{DirRights, OpenOptions, RenameOptions};
+use std::io;
+use sys_common::io::tmpdir;
+
+fn denied<T: std::fmt::Debug>(result: io::Result<T>) {
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn read_only() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("sub/file", b"contents"));
+    check!(tmpdir.write("file", b"contents"));
+
+    let dir = check!(tmpdir.restrict(DirRights::read_only()));
+    assert_eq!(dir.rights(), DirRights::read_only());
+
+    // Reading is permitted.
+    assert_eq!(check!(dir.read("file")), b"contents");
+    assert_eq!(check!(dir.read_to_string("sub/file")), "contents");
+    assert!(dir.is_file("file"));
+    assert!(dir.is_dir("sub"));
+    assert!(check!(dir.metadata("file")).is_file());
+    assert_eq!(check!(dir.entries()).count(), 2);
+    check!(dir.open("file"));
+
+    // Modifications aren't.
+    denied(dir.create("new"));
+    denied(dir.write("file", b"changed"));
+    denied(dir.open_with("file", OpenOptions::new().append(true)));
+    denied(dir.open_with("file", OpenOptions::new().read(true).truncate(true)));
+    denied(dir.create_dir("new"));
+    denied(dir.create_dir_all("new/dir"));
+    denied(dir.remove_file("file"));
+    denied(dir.remove_dir("sub"));
+    denied(dir.remove_dir_all("sub"));
+    denied(dir.rename("file", &dir, "renamed"));
+    denied(dir.copy("file", &dir, "copy"));
+    denied(dir.hard_link("file", &dir, "link"));
+    #[cfg(not(windows))]
+    denied(dir.symlink("file", "symlink"));
+    let perms = check!(dir.metadata("file")).permissions();
+    denied(dir.set_permissions("file", perms.clone()));
+
+    // Files opened through it can't be used to modify them either.
+    let file = check!(dir.open("file"));
+    denied(file.set_permissions(perms));
+    denied(file.set_len(0));
+
+    // Directories can't be opened as files, which would bypass the rights.
+    assert!(dir.open(".").is_err());
+    assert!(dir.open("sub").is_err());
+    assert!(dir.open_with("sub", OpenOptions::new().read(true)).is_err());
+
+    assert_eq!(check!(tmpdir.read("file")), b"contents");
+    assert_eq!(check!(tmpdir.entries()).count(), 2);
+    assert_eq!(check!(tmpdir.read_dir("sub")).count(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn read_only_custom_flags() {
+    use cap_std::fs::OpenOptionsExt;
+
+    let tmpdir = tmpdir();
+    let dir = check!(tmpdir.restrict(DirRights::read_only()));
+    denied(
+        dir.open_with(
+            "new",
+            OpenOptions::new()
+                .read(true)
+                .custom_flags(rustix::fs::OFlags::CREATE.bits() as i32),
+        ),
+    );
+    assert!(!tmpdir.exists("new"));
+}
+
+#[test]
+fn inherited() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("sub/dir"));
+    check!(tmpdir.write("sub/file", b"contents"));
+
+    let dir = check!(tmpdir.restrict(DirRights::read_only()));
+
+    let sub = check!(dir.open_dir("sub"));
+    assert_eq!(sub.rights(), DirRights::read_only());
+    denied(sub.remove_file("file"));
+    denied(sub.write("new", b""));
+
+    for entry in check!(dir.read_dir("sub")) {
+        let entry = check!(entry);
+        if check!(entry.file_type()).is_dir() {
+            let child = check!(entry.open_dir());
+            assert_eq!(child.rights(), DirRights::read_only());
+            assert!(entry.open().is_err());
+            denied(child.create_dir("new"));
+            denied(entry.remove_dir());
+        } else {
+            assert_eq!(entry.file_name(), "file");
+            check!(entry.open());
+            denied(entry.open_with(OpenOptions::new().write(true)));
+            denied(entry.remove_file());
+        }
+    }
+
+    assert_eq!(check!(tmpdir.read_dir("sub")).count(), 2);
+}
+
+#[test]
+fn partial_rights() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"contents"));
+
+    let mut rights = DirRights::read_only();
+    rights.create = true;
+    rights.write = true;
+    let dir = check!(tmpdir.restrict(rights));
+
+    // Creating and writing is permitted.
+    check!(dir.write("new", b"new"));
+    check!(check!(dir.open_with("new", OpenOptions::new().write(true))).set_len(2));
+    let perms = check!(dir.metadata("new")).permissions();
+    denied(check!(dir.open("new")).set_permissions(perms));
+    check!(dir.write("new", b"new"));
+    check!(dir.create_dir("sub"));
+    check!(dir.copy("file", &dir, "copy"));
+    assert_eq!(check!(tmpdir.read("new")), b"new");
+    assert_eq!(check!(tmpdir.read("copy")), b"contents");
+
+    // Removing and renaming isn't.
+    denied(dir.remove_file("new"));
+    denied(dir.rename("new", &dir, "renamed"));
+
+    // Restricting further intersects the rights.
+    let mut rights = DirRights::all();
+    rights.write = false;
+    let narrower = check!(dir.restrict(rights));
+    let mut expected = DirRights::read_only();
+    expected.create = true;
+    assert_eq!(narrower.rights(), expected);
+    check!(narrower.create_dir("other"));
+    denied(narrower.write("file", b"changed"));
+
+    // Renaming needs the right in both directories.
+    let full = check!(tmpdir.restrict(DirRights::all()));
+    check!(full.rename("new", &full, "renamed"));
+    denied(full.rename("renamed", &dir, "new"));
+    denied(dir.rename("renamed", &full, "new"));
+    check!(full.remove_file("renamed"));
+}
+
+#[test]
+fn rename_over_existing() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("from", b"from"));
+    check!(tmpdir.write("to", b"to"));
+
+    let mut rights = DirRights::read_only();
+    rights.rename = true;
+    let dir = check!(tmpdir.restrict(rights));
+
+    // A plain rename may replace the destination, which needs the right to
+    // remove entries.
+    denied(dir.rename("from", &dir, "to"));
+    denied(dir.rename_with("from", &dir, "to", &RenameOptions::new()));
+    assert_eq!(check!(tmpdir.read("to")), b"to");
+
+    // Renames which can't replace the destination don't.
+    assert_eq!(
+        dir.rename_noreplace("from", &dir, "to").unwrap_err().kind(),
+        io::ErrorKind::AlreadyExists
+    );
+    check!(dir.rename_noreplace("from", &dir, "renamed"));
+    assert_eq!(check!(tmpdir.read("renamed")), b"from");
+    assert_eq!(check!(tmpdir.read("to")), b"to");
+
+    // Only the destination's rights need to permit removing.
+    let full = check!(tmpdir.restrict(DirRights::all()));
+    check!(dir.rename("renamed", &full, "to"));
+    assert_eq!(check!(tmpdir.read("to")), b"from");
+}
+
+#[cfg(feature = "fs_utf8")]
+#[test]
+fn read_only_utf8() {
+    use sys_common::io::tmpdir_utf8;
+
+    let tmpdir = tmpdir_utf8();
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("sub/file", b"contents"));
+
+    let dir = check!(tmpdir.restrict(DirRights::read_only()));
+    assert_eq!(check!(dir.read("sub/file")), b"contents");
+    assert!(dir.open(".").is_err());
+    denied(dir.write("file", b""));
+    denied(dir.remove_file("sub/file"));
+
+    let sub = check!(dir.open_dir("sub"));
+    denied(sub.create_dir("new"));
+    for entry in check!(sub.entries()) {
+        let entry = check!(entry);
+        assert_eq!(check!(entry.file_name()), "file");
+        denied(entry.remove_file());
+    }
+}
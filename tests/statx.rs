@@ -0,0 +1,63 @@
+#![cfg(target_os = "linux")]
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::{FileStatxExt, FollowSymlinks, StatxExt};
+use sys_common::io::tmpdir;
+
+#[test]
+fn statx_basic() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"contents"));
+
+    let ext = check!(tmpdir.statx("file", FollowSymlinks::Yes));
+    let file = check!(tmpdir.open("file"));
+    let file_ext = check!(file.statx());
+
+    // Both ways of querying the same file agree.
+    assert_eq!(ext.mount_id(), file_ext.mount_id());
+    assert_eq!(ext.created(), file_ext.created());
+
+    // Where the birth time is available, it matches `Metadata::created`.
+    if let Some(created) = ext.created() {
+        assert_eq!(created, check!(check!(file.metadata()).created()));
+    }
+
+    // A freshly written file isn't immutable or append-only.
+    assert_ne!(ext.is_immutable(), Some(true));
+    assert_ne!(ext.is_append_only(), Some(true));
+}
+
+#[test]
+fn statx_follow() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.symlink("dir", "link"));
+
+    // The symlink and the directory are on the same mount.
+    let dir = check!(tmpdir.statx("dir", FollowSymlinks::Yes));
+    let link = check!(tmpdir.statx("link", FollowSymlinks::No));
+    let followed = check!(tmpdir.statx("link", FollowSymlinks::Yes));
+    assert_eq!(dir.mount_id(), link.mount_id());
+    assert_eq!(dir.mount_id(), followed.mount_id());
+    assert_eq!(dir.created(), followed.created());
+}
+
+#[test]
+fn statx_escape() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.symlink("..", "up"));
+    let dir = check!(tmpdir.open_dir("dir"));
+
+    error_contains!(
+        dir.statx("..", FollowSymlinks::Yes),
+        "a path led outside of the filesystem"
+    );
+    error_contains!(
+        tmpdir.statx("up", FollowSymlinks::Yes),
+        "a path led outside of the filesystem"
+    );
+    check!(tmpdir.statx("up", FollowSymlinks::No));
+}
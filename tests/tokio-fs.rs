@@ -0,0 +1,114 @@
+#[macro_use]
+mod sys_common;
+
+use cap_tokio::fs::{Dir, OpenOptions};
+use cap_tokio::tempfile::TempFile;
+use std::io::SeekFrom;
+use sys_common::io::tmpdir;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+#[tokio::test]
+async fn read_write() {
+    let tmpdir = tmpdir();
+    let dir = Dir::from_cap_std(check!(tmpdir.try_clone()));
+
+    check!(dir.write("file", b"hello").await);
+    assert_eq!(check!(dir.read("file").await), b"hello");
+    assert_eq!(check!(dir.read_to_string("file").await), "hello");
+    assert!(dir.is_file("file").await);
+    assert!(!dir.is_dir("file").await);
+    assert_eq!(check!(dir.metadata("file").await).len(), 5);
+
+    let mut file = check!(dir.create("other").await);
+    check!(file.write_all(b"world").await);
+    check!(file.flush().await);
+    drop(file);
+    assert_eq!(check!(tmpdir.read("other")), b"world");
+}
+
+#[tokio::test]
+async fn file_io() {
+    let tmpdir = tmpdir();
+    let dir = Dir::from_cap_std(check!(tmpdir.try_clone()));
+
+    let mut file = check!(
+        dir.open_with(
+            "file",
+            OpenOptions::new().read(true).write(true).create(true)
+        )
+        .await
+    );
+    check!(file.write_all(b"hello world").await);
+    check!(file.seek(SeekFrom::Start(6)).await);
+    let mut buf = String::new();
+    check!(file.read_to_string(&mut buf).await);
+    assert_eq!(buf, "world");
+
+    check!(file.set_len(5).await);
+    assert_eq!(check!(file.metadata().await).len(), 5);
+    check!(file.sync_all().await);
+}
+
+#[tokio::test]
+async fn dirs_and_entries() {
+    let tmpdir = tmpdir();
+    let dir = Dir::from_cap_std(check!(tmpdir.try_clone()));
+
+    check!(dir.create_dir_all("a/b").await);
+    for i in 0..100 {
+        check!(dir.write(format!("a/b/{}", i), b"").await);
+    }
+    let sub = check!(dir.open_dir("a").await);
+    assert!(sub.is_dir("b").await);
+
+    let mut entries = check!(sub.read_dir("b").await);
+    let mut names = Vec::new();
+    while let Some(entry) = check!(entries.next_entry().await) {
+        assert!(check!(entry.file_type().await).is_file());
+        names.push(entry.file_name().into_string().unwrap());
+    }
+    names.sort_by_key(|name| name.parse::<u32>().unwrap());
+    assert_eq!(names, (0..100).map(|i| i.to_string()).collect::<Vec<_>>());
+
+    check!(dir.remove_dir_all("a").await);
+    assert!(!dir.exists("a").await);
+}
+
+#[tokio::test]
+async fn sandboxed() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("nested"));
+    let dir = Dir::from_cap_std(check!(tmpdir.open_dir("nested")));
+
+    error_contains!(
+        dir.open("../file").await,
+        "a path led outside of the filesystem"
+    );
+    error_contains!(
+        dir.write("../file", b"").await,
+        "a path led outside of the filesystem"
+    );
+    error_contains!(
+        dir.open_dir("..").await,
+        "a path led outside of the filesystem"
+    );
+}
+
+#[tokio::test]
+async fn tempfile() {
+    let tmpdir = tmpdir();
+    let dir = Dir::from_cap_std(check!(tmpdir.try_clone()));
+
+    let mut tf = check!(TempFile::new(&dir).await);
+    check!(tf.write_all(b"hello").await);
+    check!(tf.replace("file").await);
+    assert_eq!(check!(dir.read("file").await), b"hello");
+
+    let tf = check!(TempFile::new(&dir).await);
+    drop(tf);
+    assert_eq!(check!(tmpdir.entries()).count(), 1);
+
+    let mut file = check!(TempFile::new_anonymous(&dir).await);
+    check!(file.write_all(b"anonymous").await);
+    assert_eq!(check!(tmpdir.entries()).count(), 1);
+}
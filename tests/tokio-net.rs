@@ -0,0 +1,158 @@
+use cap_std::ambient_authority;
+use cap_tokio::net::{IpAddr, Ipv4Addr, Pool, Resolver, StaticResolver};
+use std::io::ErrorKind;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+macro_rules! t {
+    ($e:expr) => {
+        match $e {
+            Ok(t) => t,
+            Err(e) => panic!("received error for `{}`: {}", stringify!($e), e),
+        }
+    };
+}
+
+fn localhost_pool() -> Pool {
+    let mut pool = Pool::new();
+    pool.insert_ip_net_port_any("127.0.0.1/32".parse().unwrap(), ambient_authority());
+    pool
+}
+
+#[tokio::test]
+async fn tcp_round_trip() {
+    let pool = localhost_pool();
+    let listener = t!(pool.bind_tcp_listener("127.0.0.1:0").await);
+    let addr = t!(listener.local_addr());
+
+    let client = tokio::spawn(async move {
+        let mut stream = t!(pool.connect_tcp_stream(addr).await);
+        t!(stream.write_all(b"hello").await);
+    });
+
+    let (mut stream, peer) = t!(listener.accept().await);
+    assert_eq!(peer.ip(), Ipv4Addr::LOCALHOST);
+    let mut buf = Vec::new();
+    t!(stream.read_to_end(&mut buf).await);
+    assert_eq!(buf, b"hello");
+    t!(client.await);
+}
+
+#[tokio::test]
+async fn tcp_connect_timeout() {
+    let pool = localhost_pool();
+    let listener = t!(pool.bind_tcp_listener("127.0.0.1:0").await);
+    let addr = t!(listener.local_addr());
+
+    let stream = t!(pool
+        .connect_timeout_tcp_stream(&addr, Duration::from_secs(10))
+        .await);
+    assert_eq!(t!(stream.peer_addr()), addr);
+}
+
+#[tokio::test]
+async fn outside_pool() {
+    let pool = Pool::new();
+    let addr = "127.0.0.1:1".parse().unwrap();
+
+    let err = pool.bind_tcp_listener("127.0.0.1:0").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+    let err = pool.connect_tcp_stream(addr).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+    let err = pool
+        .connect_timeout_tcp_stream(&addr, Duration::from_secs(10))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+    let err = pool.bind_udp_socket("127.0.0.1:0").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+}
+
+#[tokio::test]
+async fn udp_send_to() {
+    let pool = localhost_pool();
+    let a = t!(pool.bind_udp_socket("127.0.0.1:0").await);
+    let b = t!(pool.bind_udp_socket("127.0.0.1:0").await);
+    let b_addr = t!(b.local_addr());
+
+    // A pool permitting only `b`'s address can send to `b`, but not to `a`.
+    let mut send_pool = Pool::new();
+    send_pool.insert_socket_addr(b_addr, ambient_authority());
+    assert_eq!(
+        t!(send_pool.send_to_udp_socket_addr(&a, b"hi", b_addr).await),
+        2
+    );
+    let err = send_pool
+        .send_to_udp_socket_addr(&b, b"hi", t!(a.local_addr()))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+    let mut buf = [0; 8];
+    let (n, from) = t!(b.recv_from(&mut buf).await);
+    assert_eq!(&buf[..n], b"hi");
+    assert_eq!(from, t!(a.local_addr()));
+
+    t!(send_pool.connect_udp_socket(&a, b_addr).await);
+    assert_eq!(t!(a.send(b"yo").await), 2);
+    let n = t!(b.recv(&mut buf).await);
+    assert_eq!(&buf[..n], b"yo");
+}
+
+fn resolver() -> Resolver {
+    let mut table = StaticResolver::new();
+    table.insert("service.test", IpAddr::V4(Ipv4Addr::LOCALHOST));
+    Resolver::new(table)
+}
+
+#[tokio::test]
+async fn tcp_connect_by_name() {
+    let listen_pool = localhost_pool();
+    let listener = t!(listen_pool.bind_tcp_listener("127.0.0.1:0").await);
+    let addr = t!(listener.local_addr());
+
+    let mut pool = Pool::new();
+    t!(pool.insert_host("Service.Test", addr.port(), ambient_authority()));
+    let resolver = resolver();
+
+    let stream = t!(pool
+        .connect_tcp_stream_host(&resolver, "service.test", addr.port())
+        .await);
+    assert_eq!(t!(stream.peer_addr()), addr);
+    let (accepted, _) = t!(listener.accept().await);
+    assert_eq!(t!(accepted.local_addr()), addr);
+
+    // The grant is for the name, not for the address it resolves to.
+    let err = pool.connect_tcp_stream(addr).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+    let err = pool
+        .connect_tcp_stream_host(&resolver, "other.test", addr.port())
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+    let err = pool
+        .connect_tcp_stream_host(&resolver, "service.test", addr.port().wrapping_add(1))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+}
+
+#[tokio::test]
+async fn udp_connect_by_name() {
+    let pool = localhost_pool();
+    let a = t!(pool.bind_udp_socket("127.0.0.1:0").await);
+    let b = t!(pool.bind_udp_socket("127.0.0.1:0").await);
+    let b_addr = t!(b.local_addr());
+
+    let mut pool = Pool::new();
+    t!(pool.insert_host_port_any("service.test", ambient_authority()));
+    t!(pool
+        .connect_udp_socket_host(&a, &resolver(), "service.test", b_addr.port())
+        .await);
+    assert_eq!(t!(a.send(b"hello").await), 5);
+
+    let mut buf = [0; 8];
+    let (n, from) = t!(b.recv_from(&mut buf).await);
+    assert_eq!(&buf[..n], b"hello");
+    assert_eq!(from, t!(a.local_addr()));
+}
@@ -0,0 +1,94 @@
+#![cfg(unix)]
+
+#[macro_use]
+mod sys_common;
+
+use cap_std::ambient_authority;
+use cap_std::net::{Pool, TcpListener};
+use cap_std::os::unix::net::{ReceivedFds, UnixDatagram, UnixStream};
+use io_lifetimes::AsFd;
+use std::io::Write;
+use sys_common::io::tmpdir;
+
+#[test]
+fn send_dir_and_file() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.write("dir/file", b"hello"));
+    let dir = check!(tmpdir.open_dir("dir"));
+    let file = check!(tmpdir.create("log"));
+
+    let (a, b) = check!(UnixStream::pair());
+    assert_eq!(
+        check!(a.send_with_fds(b"x", &[dir.as_fd(), file.as_fd()])),
+        1
+    );
+    drop(dir);
+    drop(file);
+
+    let mut fds = ReceivedFds::new(4);
+    let mut buf = [0; 4];
+    assert_eq!(check!(b.recv_with_fds(&mut buf, &mut fds)), 1);
+    assert_eq!(&buf[..1], b"x");
+    assert_eq!(fds.len(), 2);
+    assert!(!fds.is_truncated());
+
+    let dir = check!(fds.take_dir());
+    assert_eq!(check!(dir.read("file")), b"hello");
+    let mut file = check!(fds.take_file());
+    check!(file.write_all(b"logged"));
+    assert_eq!(check!(tmpdir.read("log")), b"logged");
+    assert!(fds.is_empty());
+    assert!(fds.take_fd().is_err());
+}
+
+#[test]
+fn wrong_types() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b""));
+    let file = check!(tmpdir.open("file"));
+
+    let (a, b) = check!(UnixStream::pair());
+    check!(a.send_with_fds(b"x", &[file.as_fd(), tmpdir.as_fd()]));
+
+    let mut fds = ReceivedFds::new(2);
+    check!(b.recv_with_fds(&mut [0; 1], &mut fds));
+    assert!(fds.take_dir().is_err());
+    assert!(fds.take_file().is_err());
+}
+
+#[test]
+fn bounded() {
+    let tmpdir = tmpdir();
+    let (a, b) = check!(UnixDatagram::pair());
+    let fds = [tmpdir.as_fd(); 8];
+    check!(a.send_with_fds(b"x", &fds));
+
+    let mut received = ReceivedFds::new(2);
+    check!(b.recv_with_fds(&mut [0; 1], &mut received));
+    assert_eq!(received.len(), 2);
+    assert!(received.is_truncated());
+
+    // Reusing the `ReceivedFds` replaces its contents.
+    check!(a.send_with_fds(b"y", &[]));
+    let mut buf = [0; 1];
+    check!(b.recv_with_fds(&mut buf, &mut received));
+    assert_eq!(&buf, b"y");
+    assert!(received.is_empty());
+    assert!(!received.is_truncated());
+}
+
+#[test]
+fn send_socket() {
+    let mut pool = Pool::new();
+    pool.insert_socket_addr("127.0.0.1:0".parse().unwrap(), ambient_authority());
+    let listener = check!(pool.bind_tcp_listener("127.0.0.1:0"));
+
+    let (a, b) = check!(UnixStream::pair());
+    check!(a.send_with_fds(b"x", &[listener.as_fd()]));
+
+    let mut fds = ReceivedFds::new(1);
+    check!(b.recv_with_fds(&mut [0; 1], &mut fds));
+    let received: TcpListener = check!(fds.take());
+    assert_eq!(check!(received.local_addr()), check!(listener.local_addr()));
+}
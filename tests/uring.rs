@@ -0,0 +1,112 @@
+#![cfg(all(target_os = "linux", feature = "io_uring"))]
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::{OpenOptions, Uring, UringExt};
+use sys_common::io::tmpdir;
+
+/// Create an io_uring instance, or `None` if io_uring is unavailable, such
+/// as when it's disabled by a `seccomp` policy.
+fn uring() -> Option<Uring> {
+    match Uring::new(4) {
+        Ok(uring) => Some(uring),
+        Err(err) => {
+            eprintln!("skipping: io_uring is unavailable: {}", err);
+            None
+        }
+    }
+}
+
+#[test]
+fn read_write() {
+    let Some(mut uring) = uring() else { return };
+    let tmpdir = tmpdir();
+
+    check!(tmpdir.write_with_uring(&mut uring, "file", b"hello world"));
+    assert_eq!(check!(tmpdir.read("file")), b"hello world");
+    assert_eq!(
+        check!(tmpdir.read_with_uring(&mut uring, "file")),
+        b"hello world"
+    );
+
+    check!(tmpdir.write_with_uring(&mut uring, "empty", b""));
+    assert!(check!(tmpdir.read_with_uring(&mut uring, "empty")).is_empty());
+}
+
+#[test]
+fn file_io() {
+    let Some(mut uring) = uring() else { return };
+    let tmpdir = tmpdir();
+
+    let file = check!(tmpdir.open_with_uring(
+        &mut uring,
+        "file",
+        OpenOptions::new().read(true).write(true).create(true)
+    ));
+    assert_eq!(check!(uring.write_at(&file, b"hello world", 0)), 11);
+    let mut buf = [0; 5];
+    assert_eq!(check!(uring.read_at(&file, &mut buf, 6)), 5);
+    assert_eq!(&buf, b"world");
+    assert_eq!(check!(uring.read_at(&file, &mut buf, 11)), 0);
+}
+
+#[test]
+fn open_many() {
+    let Some(mut uring) = uring() else { return };
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+
+    // More paths than the ring has entries, so they're submitted in chunks.
+    let paths = (0..10).map(|i| format!("dir/{}", i)).collect::<Vec<_>>();
+    for path in &paths {
+        check!(tmpdir.write(path, path));
+    }
+    let mut with_missing = paths.clone();
+    with_missing.insert(3, "dir/missing".to_owned());
+
+    let results =
+        tmpdir.open_many_with_uring(&mut uring, &with_missing, OpenOptions::new().read(true));
+    assert_eq!(results.len(), 11);
+    for (path, result) in with_missing.iter().zip(results) {
+        if path == "dir/missing" {
+            assert!(result.is_err());
+            continue;
+        }
+        let file = check!(result);
+        let mut buf = [0; 16];
+        let n = check!(uring.read_at(&file, &mut buf, 0));
+        assert_eq!(&buf[..n], path.as_bytes());
+    }
+}
+
+#[test]
+fn sandboxed() {
+    let Some(mut uring) = uring() else { return };
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("nested"));
+    check!(tmpdir.write("file", b""));
+    let nested = check!(tmpdir.open_dir("nested"));
+
+    error_contains!(
+        nested.open_with_uring(&mut uring, "../file", OpenOptions::new().read(true)),
+        "a path led outside of the filesystem"
+    );
+    error_contains!(
+        nested.read_with_uring(&mut uring, "../file"),
+        "a path led outside of the filesystem"
+    );
+    error_contains!(
+        nested.write_with_uring(&mut uring, "../file", b""),
+        "a path led outside of the filesystem"
+    );
+    error_contains!(
+        nested.read_with_uring(&mut uring, "/file"),
+        "a path led outside of the filesystem"
+    );
+
+    let results =
+        nested.open_many_with_uring(&mut uring, &["../file", "."], OpenOptions::new().read(true));
+    error_contains!(results[0], "a path led outside of the filesystem");
+    assert!(results[1].is_ok());
+}
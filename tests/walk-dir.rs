@@ -0,0 +1,246 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{Dir, FollowSymlinks};
+use std::io;
+use std::path::PathBuf;
+use sys_common::io::tmpdir;
+use sys_common::symlink_supported;
+
+/// Create a small tree:
+///
+/// ```text
+/// a/
+/// a/b/
+/// a/b/c.txt
+/// a/d.txt
+/// e.txt
+/// ```
+fn make_tree(dir: &Dir) {
+    check!(dir.create_dir_all("a/b"));
+    check!(dir.write("a/b/c.txt", b"c"));
+    check!(dir.write("a/d.txt", b"d"));
+    check!(dir.write("e.txt", b"e"));
+}
+
+fn paths<I: IntoIterator<Item = io::Result<cap_std::fs::WalkEntry>>>(iter: I) -> Vec<PathBuf> {
+    iter.into_iter()
+        .map(|entry| check!(entry).into_path())
+        .collect()
+}
+
+#[test]
+fn walk_dir_basic() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    let walk = check!(tmpdir.walk_dir(".")).sort_by_file_name();
+    let entries = walk
+        .into_iter()
+        .map(|entry| {
+            let entry = check!(entry);
+            (
+                entry.path().to_path_buf(),
+                entry.depth(),
+                entry.file_type().is_dir(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        vec![
+            (PathBuf::from("./a"), 1, true),
+            (PathBuf::from("./a/b"), 2, true),
+            (PathBuf::from("./a/b/c.txt"), 3, false),
+            (PathBuf::from("./a/d.txt"), 2, false),
+            (PathBuf::from("./e.txt"), 1, false),
+        ]
+    );
+
+    // Paths are usable with the `Dir` that `walk_dir` was called on.
+    for entry in check!(tmpdir.walk_dir("a")) {
+        let entry = check!(entry);
+        assert!(tmpdir.exists(entry.path()), "{:?}", entry.path());
+    }
+}
+
+#[test]
+fn walk_dir_depth() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    let walk = check!(tmpdir.walk_dir("a"))
+        .sort_by_file_name()
+        .max_depth(1);
+    assert_eq!(
+        paths(walk),
+        vec![PathBuf::from("a/b"), PathBuf::from("a/d.txt")]
+    );
+
+    let walk = check!(tmpdir.walk_dir("a"))
+        .sort_by_file_name()
+        .min_depth(2);
+    assert_eq!(paths(walk), vec![PathBuf::from("a/b/c.txt")]);
+
+    let walk = check!(tmpdir.walk_dir("a")).max_depth(0);
+    assert!(paths(walk).is_empty());
+}
+
+#[test]
+fn walk_dir_contents_first() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    let walk = check!(tmpdir.walk_dir("a"))
+        .sort_by_file_name()
+        .contents_first(true);
+    assert_eq!(
+        paths(walk),
+        vec![
+            PathBuf::from("a/b/c.txt"),
+            PathBuf::from("a/b"),
+            PathBuf::from("a/d.txt"),
+        ]
+    );
+
+    // Removing everything in post-order leaves an empty directory.
+    for entry in check!(tmpdir.walk_dir("a")).contents_first(true) {
+        let entry = check!(entry);
+        if entry.file_type().is_dir() {
+            check!(tmpdir.remove_dir(entry.path()));
+        } else {
+            check!(tmpdir.remove_file(entry.path()));
+        }
+    }
+    assert_eq!(check!(tmpdir.read_dir("a")).count(), 0);
+}
+
+#[test]
+fn walk_dir_filter_entry() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    let walk = check!(tmpdir.walk_dir("."))
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != "b");
+    assert_eq!(
+        paths(walk),
+        vec![
+            PathBuf::from("./a"),
+            PathBuf::from("./a/d.txt"),
+            PathBuf::from("./e.txt"),
+        ]
+    );
+
+    let mut walk = check!(tmpdir.walk_dir(".")).sort_by_file_name().into_iter();
+    let first = check!(walk.next().unwrap());
+    assert_eq!(first.path(), PathBuf::from("./a"));
+    walk.skip_current_dir();
+    assert_eq!(paths(walk), vec![PathBuf::from("./e.txt")]);
+}
+
+#[test]
+fn walk_dir_symlinks() {
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+    check!(tmpdir.create_dir("top"));
+    check!(tmpdir.write("outside.txt", b"outside"));
+    let inner = check!(tmpdir.open_dir("top"));
+    make_tree(&inner);
+    #[cfg(not(windows))]
+    check!(inner.symlink("a/b", "link"));
+    #[cfg(windows)]
+    check!(inner.symlink_dir("a\\b", "link"));
+
+    // By default, symlinks are yielded but not followed.
+    let entries = check!(inner.walk_dir("."))
+        .into_iter()
+        .map(|entry| check!(entry))
+        .filter(|entry| entry.path_is_symlink())
+        .collect::<Vec<_>>();
+    assert_eq!(entries.len(), 1);
+    assert!(entries[0].file_type().is_symlink());
+
+    // When following, the link's target is descended into.
+    let walk = check!(inner.walk_dir("."))
+        .sort_by_file_name()
+        .follow_symlinks(FollowSymlinks::Yes)
+        .min_depth(2);
+    assert!(paths(walk).contains(&PathBuf::from("./link/c.txt")));
+
+    // A link to an ancestor is reported as an error rather than looping.
+    #[cfg(not(windows))]
+    check!(inner.symlink("..", "a/b/up"));
+    #[cfg(windows)]
+    check!(inner.symlink_dir("..", "a\\b\\up"));
+    let results = check!(inner.walk_dir("a"))
+        .sort_by_file_name()
+        .follow_symlinks(FollowSymlinks::Yes)
+        .into_iter()
+        .map(|result| result.map(|entry| entry.into_path()))
+        .collect::<Vec<_>>();
+    let ok = results
+        .iter()
+        .filter_map(|result| result.as_ref().ok().cloned())
+        .collect::<Vec<_>>();
+    let expected = ["a/b", "a/b/c.txt", "a/b/up", "a/d.txt"]
+        .iter()
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    assert_eq!(ok, expected);
+    // The loop is detected at the first link back to the starting directory,
+    // right after the link itself is yielded.
+    assert_eq!(results.len(), 5);
+    assert!(results[3].is_err());
+    #[cfg(not(windows))]
+    assert_eq!(
+        results[3].as_ref().unwrap_err().raw_os_error(),
+        Some(rustix::io::Errno::LOOP.raw_os_error())
+    );
+
+    // A link which leads outside the starting directory is never followed.
+    #[cfg(not(windows))]
+    check!(inner.symlink("../outside.txt", "escape"));
+    #[cfg(windows)]
+    check!(inner.symlink_file("..\\outside.txt", "escape"));
+    let results = check!(inner.walk_dir("."))
+        .follow_symlinks(FollowSymlinks::Yes)
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+    assert!(!results
+        .iter()
+        .any(|entry| entry.path() == PathBuf::from("./escape")));
+}
+
+#[cfg(feature = "fs_utf8")]
+#[test]
+fn walk_dir_utf8() {
+    use camino::Utf8PathBuf;
+    use sys_common::io::tmpdir_utf8;
+
+    let tmpdir = tmpdir_utf8();
+    check!(tmpdir.create_dir_all("a/b"));
+    check!(tmpdir.write("a/b/c.txt", b"c"));
+
+    let entries = check!(tmpdir.walk_dir("a"))
+        .sort_by_file_name()
+        .into_iter()
+        .map(|entry| check!(entry).into_path())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        vec![Utf8PathBuf::from("a/b"), Utf8PathBuf::from("a/b/c.txt")]
+    );
+
+    let entries = check!(tmpdir.walk_dir("a"))
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != "b")
+        .count();
+    assert_eq!(entries, 0);
+}
@@ -0,0 +1,91 @@
+#![cfg(any(target_os = "android", target_os = "linux"))]
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::{WatchEvent, WatchExt};
+use std::path::PathBuf;
+use sys_common::io::tmpdir;
+
+#[test]
+fn watch_basic() {
+    let tmpdir = tmpdir();
+    let mut watcher = check!(tmpdir.watch("."));
+
+    check!(tmpdir.write("file", b"contents"));
+    check!(tmpdir.rename("file", &tmpdir, "renamed"));
+    check!(tmpdir.remove_file("renamed"));
+
+    assert_eq!(
+        check!(watcher.next().unwrap()),
+        WatchEvent::Created(PathBuf::from("file"))
+    );
+    assert_eq!(
+        check!(watcher.next().unwrap()),
+        WatchEvent::Modified(PathBuf::from("file"))
+    );
+    assert_eq!(
+        check!(watcher.next().unwrap()),
+        WatchEvent::Renamed {
+            from: PathBuf::from("file"),
+            to: PathBuf::from("renamed"),
+        }
+    );
+    assert_eq!(
+        check!(watcher.next().unwrap()),
+        WatchEvent::Removed(PathBuf::from("renamed"))
+    );
+}
+
+#[test]
+fn watch_subdir() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("sub/dir"));
+    check!(tmpdir.create_dir("other"));
+    let mut watcher = check!(tmpdir.watch("sub"));
+
+    // Changes outside of the watched directory, including within its
+    // subdirectories, aren't reported.
+    check!(tmpdir.write("outside", b""));
+    check!(tmpdir.write("sub/dir/nested", b""));
+
+    // Renames into and out of the watched directory are reported as
+    // creations and removals.
+    check!(tmpdir.rename("outside", &tmpdir, "sub/inside"));
+    check!(tmpdir.rename("sub/inside", &tmpdir, "other/outside"));
+
+    assert_eq!(
+        check!(watcher.next().unwrap()),
+        WatchEvent::Created(PathBuf::from("sub/inside"))
+    );
+    assert_eq!(
+        check!(watcher.next().unwrap()),
+        WatchEvent::Removed(PathBuf::from("sub/inside"))
+    );
+
+    // Once the watched directory is removed, the watcher ends.
+    check!(tmpdir.remove_dir_all("sub"));
+    assert_eq!(
+        check!(watcher.next().unwrap()),
+        WatchEvent::Removed(PathBuf::from("sub/dir"))
+    );
+    assert!(watcher.next().is_none());
+}
+
+#[test]
+fn watch_escape() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("file", b""));
+
+    assert!(tmpdir.watch("..").is_err());
+    assert!(tmpdir.watch("sub/../..").is_err());
+    assert!(tmpdir.watch("/").is_err());
+    assert!(tmpdir.watch("file").is_err());
+
+    #[cfg(not(windows))]
+    if sys_common::symlink_supported() {
+        check!(tmpdir.symlink("..", "link"));
+        assert!(tmpdir.watch("link").is_err());
+    }
+}
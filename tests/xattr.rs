@@ -0,0 +1,194 @@
+#![cfg(any(target_os = "android", target_os = "linux"))]
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::{FileXattrExt, FollowSymlinks, XattrExt};
+use std::ffi::OsString;
+use std::io;
+use sys_common::io::tmpdir;
+
+/// Not all filesystems support user extended attributes.
+fn xattrs_supported(result: io::Result<()>) -> bool {
+    match result {
+        Ok(()) => true,
+        Err(err) if err.raw_os_error() == Some(rustix::io::Errno::OPNOTSUPP.raw_os_error()) => {
+            false
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn xattr_basic() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"contents"));
+    if !xattrs_supported(tmpdir.set_xattr("file", "user.test", b"value", FollowSymlinks::Yes)) {
+        return;
+    }
+
+    assert_eq!(
+        check!(tmpdir.get_xattr("file", "user.test", FollowSymlinks::Yes)),
+        Some(b"value".to_vec())
+    );
+    assert_eq!(
+        check!(tmpdir.get_xattr("file", "user.missing", FollowSymlinks::Yes)),
+        None
+    );
+    assert!(check!(tmpdir.list_xattrs("file", FollowSymlinks::Yes))
+        .contains(&OsString::from("user.test")));
+
+    check!(tmpdir.remove_xattr("file", "user.test", FollowSymlinks::Yes));
+    assert_eq!(
+        check!(tmpdir.get_xattr("file", "user.test", FollowSymlinks::Yes)),
+        None
+    );
+    assert!(tmpdir
+        .remove_xattr("file", "user.test", FollowSymlinks::Yes)
+        .is_err());
+
+    // Large values are read in full.
+    let large = vec![b'x'; 3000];
+    check!(tmpdir.set_xattr("file", "user.large", &large, FollowSymlinks::Yes));
+    assert_eq!(
+        check!(tmpdir.get_xattr("file", "user.large", FollowSymlinks::Yes)),
+        Some(large)
+    );
+}
+
+#[test]
+fn xattr_file() {
+    let tmpdir = tmpdir();
+    let file = check!(tmpdir.create("file"));
+    if !xattrs_supported(file.set_xattr("user.test", b"value")) {
+        return;
+    }
+
+    assert_eq!(check!(file.get_xattr("user.test")), Some(b"value".to_vec()));
+    assert_eq!(
+        check!(tmpdir.get_xattr("file", "user.test", FollowSymlinks::No)),
+        Some(b"value".to_vec())
+    );
+    assert!(check!(file.list_xattrs()).contains(&OsString::from("user.test")));
+    check!(file.remove_xattr("user.test"));
+    assert_eq!(check!(file.get_xattr("user.test")), None);
+}
+
+#[test]
+fn xattr_symlinks() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("file", b"contents"));
+    let sub = check!(tmpdir.open_dir("sub"));
+    if !xattrs_supported(tmpdir.set_xattr("file", "user.test", b"value", FollowSymlinks::Yes)) {
+        return;
+    }
+    check!(tmpdir.symlink("file", "link"));
+    check!(sub.symlink("../file", "escape"));
+
+    // Following a symlink reads the target's attributes.
+    assert_eq!(
+        check!(tmpdir.get_xattr("link", "user.test", FollowSymlinks::Yes)),
+        Some(b"value".to_vec())
+    );
+
+    // A symlink's own attributes can't be accessed without resolving an
+    // ambient path, so not following it fails.
+    assert!(tmpdir
+        .get_xattr("link", "user.test", FollowSymlinks::No)
+        .is_err());
+
+    // Symlinks can't be used to escape the `Dir`.
+    assert!(sub
+        .get_xattr("escape", "user.test", FollowSymlinks::Yes)
+        .is_err());
+    assert!(sub
+        .get_xattr("../file", "user.test", FollowSymlinks::Yes)
+        .is_err());
+    assert!(sub
+        .set_xattr("../file", "user.other", b"value", FollowSymlinks::Yes)
+        .is_err());
+}
+
+#[test]
+fn xattr_dir() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    if !xattrs_supported(tmpdir.set_xattr("sub", "user.test", b"value", FollowSymlinks::Yes)) {
+        return;
+    }
+
+    // A `Dir`'s own attributes are accessible through ".".
+    let sub = check!(tmpdir.open_dir("sub"));
+    assert_eq!(
+        check!(sub.get_xattr(".", "user.test", FollowSymlinks::Yes)),
+        Some(b"value".to_vec())
+    );
+}
+
+#[test]
+fn xattr_fifo() {
+    use cap_fs_ext::DirExt;
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_fifo("fifo", 0o600));
+
+    // Accessing a FIFO's attributes would require opening it, so it's
+    // refused rather than blocking or consuming data.
+    for follow in [FollowSymlinks::Yes, FollowSymlinks::No] {
+        let err = tmpdir.get_xattr("fifo", "user.test", follow).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let err = tmpdir
+            .set_xattr("fifo", "user.test", b"value", follow)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(tmpdir.list_xattrs("fifo", follow).is_err());
+    }
+
+    // Symlinks themselves are refused too.
+    check!(tmpdir.symlink("fifo", "link"));
+    let err = tmpdir
+        .get_xattr("link", "user.test", FollowSymlinks::No)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+}
+
+#[test]
+fn xattr_write_only() {
+    use cap_std::fs::{Permissions, PermissionsExt};
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"contents"));
+    check!(tmpdir.set_permissions("file", Permissions::from_mode(0o200)));
+
+    // The file can't be opened for reading, but its attributes can still be
+    // set, listed, and removed, which only need write permission.
+    if !xattrs_supported(tmpdir.set_xattr("file", "user.test", b"value", FollowSymlinks::Yes)) {
+        return;
+    }
+    assert!(check!(tmpdir.list_xattrs("file", FollowSymlinks::No))
+        .contains(&OsString::from("user.test")));
+    check!(tmpdir.remove_xattr("file", "user.test", FollowSymlinks::No));
+    assert!(!check!(tmpdir.list_xattrs("file", FollowSymlinks::No))
+        .contains(&OsString::from("user.test")));
+}
+
+#[cfg(feature = "fs_utf8")]
+#[test]
+fn xattr_utf8() {
+    use cap_fs_ext::XattrExtUtf8;
+    use sys_common::io::tmpdir_utf8;
+
+    let tmpdir = tmpdir_utf8();
+    check!(tmpdir.write("file", b"contents"));
+    if !xattrs_supported(tmpdir.set_xattr("file", "user.test", b"value", FollowSymlinks::Yes)) {
+        return;
+    }
+    assert_eq!(
+        check!(tmpdir.get_xattr("file", "user.test", FollowSymlinks::Yes)),
+        Some(b"value".to_vec())
+    );
+    assert!(
+        check!(tmpdir.list_xattrs("file", FollowSymlinks::Yes)).contains(&"user.test".to_owned())
+    );
+}